@@ -3,7 +3,12 @@ use crate::comments::{Comment, CommentLevel, CommitComments};
 
 impl App {
     /// Load all comments for current branch
+    ///
+    /// Comments on virtual commits only live in memory, so there are none.
     pub fn load_comments(&mut self) {
+        if self.virtual_commits.is_some() {
+            return;
+        }
         match crate::git::notes::read_all_for_branch(&self.repo, &self.current_branch) {
             Ok(all_comments) => {
                 for commit_comments in all_comments {
@@ -15,6 +20,8 @@ impl App {
                 eprintln!("Warning: Failed to load comments: {e}");
             }
         }
+        // Viewed marks may have changed
+        self.rebuild_layout();
     }
 
     /// Get comments for currently selected commit
@@ -25,7 +32,7 @@ impl App {
 
     /// Start creating a comment based on cursor position
     pub fn start_comment_creation(&mut self) {
-        if let Some(file) = self.selected_file() {
+        if let Some(file) = self.cursor_file() {
             // Detect comment context based on cursor position
             let (level, line_number, line_type, hunk_header) = self.detect_comment_context();
 
@@ -88,7 +95,7 @@ impl App {
                     });
 
                 cc.add_comment(comment);
-                crate::git::notes::write_comments(&self.repo, &self.current_branch, oid, cc)?;
+                self.persist_commit_review(oid)?;
                 self.status_message = Some("Comment saved".to_string());
             }
 
@@ -106,7 +113,7 @@ impl App {
 
     /// View comments at current location (line, hunk, or file)
     pub fn view_comments_at_current_location(&mut self) {
-        if let Some(file) = self.selected_file() {
+        if let Some(file) = self.cursor_file() {
             if let Some(commit_comments) = self.current_commit_comments() {
                 let mut all_comments = Vec::new();
 
@@ -158,7 +165,7 @@ impl App {
 
     /// Delete comment at index in view mode
     pub fn delete_comment_at_index(&mut self, index: usize) -> anyhow::Result<()> {
-        let file = self.selected_file().map(|f| f.new_path.clone());
+        let file = self.cursor_file().map(|f| f.new_path.clone());
         let commit_oid = self.selected_commit().map(|c| c.id);
 
         if let (Some(file_path), Some(oid)) = (file, commit_oid) {
@@ -174,23 +181,7 @@ impl App {
 
                 if let Some(&actual_idx) = file_comment_indices.get(index) {
                     cc.remove_comment(actual_idx);
-
-                    if cc.is_empty() {
-                        crate::git::notes::delete_comment(
-                            &self.repo,
-                            &self.current_branch,
-                            oid,
-                            0,
-                        )?;
-                        self.comments_by_commit.remove(&cid);
-                    } else {
-                        crate::git::notes::write_comments(
-                            &self.repo,
-                            &self.current_branch,
-                            oid,
-                            cc,
-                        )?;
-                    }
+                    self.persist_commit_review(oid)?;
 
                     self.status_message = Some("Comment deleted".to_string());
                     self.comment_mode = CommentMode::Normal;
@@ -200,11 +191,38 @@ impl App {
         Ok(())
     }
 
+    /// Write a commit's review state back to git notes
+    ///
+    /// Deletes the note once it holds neither comments nor review marks.
+    /// Virtual commits aren't in the repository, so their state stays in
+    /// memory.
+    pub(super) fn persist_commit_review(&mut self, oid: git2::Oid) -> anyhow::Result<()> {
+        let cid = oid.to_string();
+        match self.comments_by_commit.get(&cid) {
+            Some(cc) if self.virtual_commits.is_some() => {
+                if cc.is_empty() && !cc.has_review_marks() {
+                    self.comments_by_commit.remove(&cid);
+                }
+                return Ok(());
+            }
+            Some(cc) if cc.is_empty() && !cc.has_review_marks() => {
+                crate::git::notes::delete_commit_note(&self.repo, &self.current_branch, oid)?;
+                self.comments_by_commit.remove(&cid);
+            }
+            Some(cc) => {
+                crate::git::notes::write_comments(&self.repo, &self.current_branch, oid, cc)?;
+            }
+            None => {}
+        }
+        self.refresh_watch_snapshot();
+        Ok(())
+    }
+
     /// Close any open dialog/mode
     pub fn close_dialog(&mut self) {
         match self.comment_mode {
             CommentMode::Normal => {}
-            CommentMode::Creating { .. } => {
+            CommentMode::Creating { .. } | CommentMode::Verdict { .. } => {
                 self.cancel_comment();
             }
             CommentMode::ViewingComments(_) => {
@@ -214,7 +232,6 @@ impl App {
     }
 
     /// Clear status message
-    #[allow(dead_code)]
     pub fn clear_status_message(&mut self) {
         self.status_message = None;
     }
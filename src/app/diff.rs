@@ -1,4 +1,6 @@
+use super::loader::{DiffKey, DiffTarget};
 use super::App;
+use crate::git::{merge::MergeDiffMode, range_diff::PairStatus, CommitInfo, FileDiff};
 
 impl App {
     /// Expand context for entire diff (git2 doesn't support per-hunk expansion)
@@ -8,6 +10,14 @@ impl App {
         self.load_diff_for_current_commit();
         // Reset scroll to show the expanded context
         self.scroll_offset = 0;
+
+        if self
+            .current_virtual_commit()
+            .is_some_and(|c| !c.has_contents())
+        {
+            self.status_message =
+                Some("Some files only have the patch's hunks: base not found".to_string());
+        }
     }
 
     /// Reset context to default
@@ -17,41 +27,214 @@ impl App {
         self.scroll_offset = 0;
     }
 
-    /// Load diff for currently selected commit
-    fn load_diff_for_current_commit(&mut self) {
-        if let Some(commit) = self.selected_commit() {
-            // Generate diff with current context level
-            let diff_options = crate::git::DiffOptions {
-                context_lines: self.current_context_lines,
+    /// Show the diff for the selected commit, loading it in the background
+    ///
+    /// Cached diffs are shown immediately. Otherwise the diff is cleared and
+    /// `loading_diff` is set until `poll_diff_loader` receives it. The
+    /// neighbouring commits are prefetched either way.
+    pub(super) fn load_diff_for_current_commit(&mut self) {
+        let Some(key) = self.diff_key(self.selected_commit_index) else {
+            return;
+        };
+
+        if let Some(files) = self.diff_loader.cache.get(&key) {
+            let files = files.clone();
+            self.loading_diff = None;
+            self.show_diff(files);
+        } else {
+            self.all_files.clear();
+            self.current_files.clear();
+            self.selected_file_index = 0;
+            self.rebuild_layout();
+            self.loading_diff = Some(key.clone());
+            if let DiffTarget::Virtual { index } = key.target {
+                let result = self.virtual_diff(index, &key);
+                self.handle_loaded_diff(key, Some(result));
+            } else {
+                self.diff_loader.request(key);
+            }
+        }
+
+        for index in [
+            self.selected_commit_index + 1,
+            self.selected_commit_index.wrapping_sub(1),
+        ] {
+            match self.diff_key(index) {
+                Some(key) if !matches!(key.target, DiffTarget::Virtual { .. }) => {
+                    self.diff_loader.prefetch(key);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Handle diffs finished by the background loader
+    ///
+    /// Returns true if the displayed diff changed.
+    pub fn poll_diff_loader(&mut self) -> bool {
+        let mut changed = false;
+        while let Some((key, result)) = self.diff_loader.try_recv() {
+            changed |= self.handle_loaded_diff(key, result);
+        }
+        changed
+    }
+
+    /// Block until the diff being loaded for display has arrived
+    pub fn wait_for_diff(&mut self) {
+        while self.loading_diff.is_some() {
+            let Some((key, result)) = self.diff_loader.recv() else {
+                self.loading_diff = None;
+                self.status_message = Some("Diff loader stopped".to_string());
+                return;
             };
+            self.handle_loaded_diff(key, result);
+        }
+    }
+
+    fn handle_loaded_diff(
+        &mut self,
+        key: DiffKey,
+        result: Option<Result<Vec<FileDiff>, String>>,
+    ) -> bool {
+        let is_current = self.loading_diff.as_ref() == Some(&key);
+        match result {
+            Some(Ok(files)) => {
+                self.diff_loader.cache.insert(key.clone(), files);
+                if is_current {
+                    self.loading_diff = None;
+                    let files = self
+                        .diff_loader
+                        .cache
+                        .get(&key)
+                        .cloned()
+                        .unwrap_or_default();
+                    self.show_diff(files);
+                }
+            }
+            Some(Err(e)) if is_current => {
+                self.loading_diff = None;
+                self.restore_view = None;
+                self.status_message = Some(format!("Failed to load diff: {e}"));
+            }
+            Some(Err(_)) => {}
+            // Dropped as stale, but it's wanted again (e.g. quickly back and forth)
+            None if is_current => {
+                self.diff_loader.request(key);
+                return false;
+            }
+            None => {}
+        }
+        is_current
+    }
 
-            match crate::git::generate_diff(&self.repo, commit.id, &diff_options) {
-                Ok(diff) => match crate::git::diff_to_text(&diff) {
-                    Ok(text) => match crate::git::parse_diff(&text) {
-                        Ok(files) => {
-                            self.current_files = files;
-                            self.selected_file_index = 0;
-                        }
-                        Err(e) => {
-                            eprintln!("Failed to parse diff: {e}");
-                            self.current_files = Vec::new();
-                        }
-                    },
-                    Err(e) => {
-                        eprintln!("Failed to convert diff to text: {e}");
-                        self.current_files = Vec::new();
-                    }
-                },
-                Err(e) => {
-                    eprintln!("Failed to generate diff: {e}");
-                    self.current_files = Vec::new();
+    /// Display a loaded diff, restoring the position saved by a reload
+    fn show_diff(&mut self, files: Vec<FileDiff>) {
+        self.all_files = files;
+        self.selected_file_index = 0;
+        self.apply_file_filter();
+
+        let restore = self.restore_view.take();
+        if let Some((_, path, scroll, cursor)) =
+            restore.filter(|(oid, ..)| Some(*oid) == self.selected_commit().map(|c| c.id))
+        {
+            if let Some(idx) = self.current_files.iter().position(|f| f.new_path == path) {
+                self.selected_file_index = idx;
+                self.rebuild_layout();
+                self.scroll_offset = scroll;
+                self.cursor_line = cursor;
+                // Re-clamp in case the diff got shorter
+                self.scroll(0);
+            }
+        }
+    }
+
+    /// Cache key for the diff of the commit at `index` with current settings
+    fn diff_key(&self, index: usize) -> Option<DiffKey> {
+        let commit = self.commits.get(index)?;
+        let pair = self.range_diff.as_ref().and_then(|pairs| pairs.get(index));
+
+        // Modified range-diff pairs compare the two versions of the commit
+        let target = match (self.tree_diff, pair.map(|p| (p.status, &p.old, &p.new))) {
+            _ if self.virtual_commits.is_some() => DiffTarget::Virtual { index },
+            (Some((old, new)), _) => DiffTarget::Trees { old, new },
+            (_, Some((PairStatus::Modified, Some(old), Some(new)))) => {
+                let (old, new) = (old.id, new.id);
+                if self.show_interdiff {
+                    DiffTarget::Interdiff { old, new }
+                } else {
+                    DiffTarget::RangeDiff { old, new }
                 }
             }
+            _ => DiffTarget::Commit {
+                oid: commit.id,
+                merge_mode: self.merge_mode_for(commit),
+            },
+        };
+
+        Some(DiffKey {
+            target,
+            context_lines: self.current_context_lines,
+            whitespace: self.whitespace_mode,
+            paths: self.path_filter.clone(),
+        })
+    }
+
+    /// Merge diff mode applied to the selected commit (None for non-merges)
+    ///
+    /// Falls back to the combined diff when the chosen mode doesn't fit this
+    /// merge (e.g. parent 3 of a two-parent merge, remerge of an octopus).
+    pub fn effective_merge_mode(&self) -> Option<MergeDiffMode> {
+        self.merge_mode_for(self.selected_commit()?)
+    }
+
+    fn merge_mode_for(&self, commit: &CommitInfo) -> Option<MergeDiffMode> {
+        if !commit.is_merge() {
+            return None;
         }
+        let parent_count = commit.parent_ids.len();
+        Some(match self.merge_diff_mode {
+            MergeDiffMode::Parent(n) if n >= parent_count => MergeDiffMode::Combined,
+            MergeDiffMode::Remerge if parent_count != 2 => MergeDiffMode::Combined,
+            mode => mode,
+        })
+    }
+
+    /// Cycle how merge commits are diffed (combined, each parent, remerge)
+    pub fn cycle_merge_diff_mode(&mut self) {
+        let Some(mode) = self.effective_merge_mode() else {
+            self.status_message = Some("Not a merge commit".to_string());
+            return;
+        };
+        let parent_count = self.selected_commit().map_or(0, |c| c.parent_ids.len());
+        self.merge_diff_mode = mode.next(parent_count);
+        self.load_diff_for_current_commit();
+        self.scroll_offset = 0;
+        self.cursor_line = 0;
+    }
+
+    /// Cycle which whitespace changes are ignored and regenerate the diff
+    pub fn cycle_whitespace_mode(&mut self) {
+        self.whitespace_mode = self.whitespace_mode.next();
+        self.load_diff_for_current_commit();
+        self.scroll_offset = 0;
+        self.cursor_line = 0;
+    }
+
+    /// Toggle highlighting of whitespace errors in added lines
+    pub fn toggle_whitespace_errors(&mut self) {
+        self.highlight_whitespace_errors = !self.highlight_whitespace_errors;
+    }
+
+    /// Review the direct diff between two trees as a single entry (`--diff`)
+    pub fn set_tree_diff(&mut self, old_tree: git2::Oid, new_tree: git2::Oid, entry: CommitInfo) {
+        self.commits = vec![entry];
+        self.selected_commit_index = 0;
+        self.tree_diff = Some((old_tree, new_tree));
     }
 
     /// Initialize diff for first commit
     pub fn init_diff(&mut self) {
         self.load_diff_for_current_commit();
+        self.wait_for_diff();
     }
 }
@@ -0,0 +1,407 @@
+use super::App;
+use crate::config::DiffMode;
+use crate::git::{FileDiff, LineType};
+
+/// One column of the side-by-side view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Old,
+    New,
+}
+
+/// What one row of the diff view shows
+///
+/// Indices refer to `App::current_files`, the file's hunks and the hunk's
+/// lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffRow {
+    Blank,
+    /// Rule between two files
+    Separator,
+    OldPath {
+        file: usize,
+    },
+    NewPath {
+        file: usize,
+    },
+    /// Placeholder for a viewed file (side-by-side)
+    Collapsed {
+        file: usize,
+    },
+    ExpandAbove {
+        file: usize,
+        hunk: usize,
+    },
+    HunkHeader {
+        file: usize,
+        hunk: usize,
+    },
+    Line {
+        file: usize,
+        hunk: usize,
+        line: usize,
+    },
+    /// Old and new lines shown next to each other (side-by-side)
+    Pair {
+        file: usize,
+        hunk: usize,
+        old: Option<usize>,
+        new: Option<usize>,
+    },
+    ExpandBelow {
+        file: usize,
+        hunk: usize,
+    },
+}
+
+impl DiffRow {
+    /// File the row belongs to, if any
+    pub fn file(self) -> Option<usize> {
+        match self {
+            DiffRow::Blank | DiffRow::Separator => None,
+            DiffRow::OldPath { file }
+            | DiffRow::NewPath { file }
+            | DiffRow::Collapsed { file }
+            | DiffRow::ExpandAbove { file, .. }
+            | DiffRow::HunkHeader { file, .. }
+            | DiffRow::Line { file, .. }
+            | DiffRow::Pair { file, .. }
+            | DiffRow::ExpandBelow { file, .. } => Some(file),
+        }
+    }
+
+    /// File and hunk the row belongs to, if any
+    pub fn hunk(self) -> Option<(usize, usize)> {
+        match self {
+            DiffRow::ExpandAbove { file, hunk }
+            | DiffRow::HunkHeader { file, hunk }
+            | DiffRow::Line { file, hunk, .. }
+            | DiffRow::Pair { file, hunk, .. }
+            | DiffRow::ExpandBelow { file, hunk } => Some((file, hunk)),
+            _ => None,
+        }
+    }
+
+    /// Index of the hunk line the row shows on `side`
+    ///
+    /// Side-by-side rows with only one line fall back to that line, inline
+    /// rows show the same line on both sides.
+    pub fn line(self, side: Side) -> Option<usize> {
+        match (self, side) {
+            (DiffRow::Line { line, .. }, _) => Some(line),
+            (DiffRow::Pair { old, new, .. }, Side::Old) => old.or(new),
+            (DiffRow::Pair { old, new, .. }, Side::New) => new.or(old),
+            _ => None,
+        }
+    }
+
+    /// Column a cursor aiming at `side` lands in: side-by-side rows with one
+    /// line always target that line's column
+    pub fn cursor_side(self, side: Side) -> Side {
+        match self {
+            DiffRow::Pair { old: None, .. } => Side::New,
+            DiffRow::Pair { new: None, .. } => Side::Old,
+            _ => side,
+        }
+    }
+}
+
+/// Precomputed row index of the diff view
+///
+/// Built once per diff (and whenever files are collapsed or expanded, or
+/// the mode or side-by-side file changes) so rendering only has to format
+/// the rows in the viewport, and search, scrolling and comments agree on
+/// what each row shows.
+#[derive(Debug, Clone, Default)]
+pub struct DiffLayout {
+    rows: Vec<DiffRow>,
+}
+
+impl DiffLayout {
+    /// Lay out `files` inline, collapsing those `is_viewed` reports as viewed
+    pub fn build(files: &[FileDiff], is_viewed: impl Fn(&FileDiff) -> bool) -> Self {
+        let mut rows = Vec::new();
+
+        for (file, diff) in files.iter().enumerate() {
+            if file > 0 {
+                rows.extend([DiffRow::Blank, DiffRow::Separator, DiffRow::Blank]);
+            }
+            rows.extend([
+                DiffRow::OldPath { file },
+                DiffRow::NewPath { file },
+                DiffRow::Blank,
+            ]);
+
+            // Viewed files collapse to their header
+            if is_viewed(diff) {
+                continue;
+            }
+
+            for (hunk, hunk_diff) in diff.hunks.iter().enumerate() {
+                if hunk_diff.available_lines_above() > 0 {
+                    rows.push(DiffRow::ExpandAbove { file, hunk });
+                }
+                rows.push(DiffRow::HunkHeader { file, hunk });
+                rows.extend((0..hunk_diff.lines.len()).map(|line| DiffRow::Line {
+                    file,
+                    hunk,
+                    line,
+                }));
+                // Unknown file length: offer to expand anyway
+                if diff
+                    .new_file_lines
+                    .is_none_or(|n| hunk_diff.can_expand_below(n))
+                {
+                    rows.push(DiffRow::ExpandBelow { file, hunk });
+                }
+                rows.push(DiffRow::Blank);
+            }
+        }
+
+        Self { rows }
+    }
+
+    /// Lay out one file side by side, pairing removed lines with the added
+    /// lines that follow them
+    pub fn build_side_by_side(file: usize, diff: &FileDiff, viewed: bool) -> Self {
+        if viewed {
+            return Self {
+                rows: vec![DiffRow::Collapsed { file }],
+            };
+        }
+
+        let mut rows = Vec::new();
+        for (hunk, hunk_diff) in diff.hunks.iter().enumerate() {
+            rows.push(DiffRow::HunkHeader { file, hunk });
+
+            let lines = &hunk_diff.lines;
+            let mut i = 0;
+            while i < lines.len() {
+                let removed_start = i;
+                while i < lines.len() && lines[i].line_type == LineType::Removed {
+                    i += 1;
+                }
+                let added_start = i;
+                while i < lines.len() && lines[i].line_type == LineType::Added {
+                    i += 1;
+                }
+
+                if removed_start == i {
+                    // Context appears on both sides
+                    rows.push(DiffRow::Pair {
+                        file,
+                        hunk,
+                        old: Some(i),
+                        new: Some(i),
+                    });
+                    i += 1;
+                    continue;
+                }
+
+                let removed = added_start - removed_start;
+                let added = i - added_start;
+                rows.extend((0..removed.max(added)).map(|j| DiffRow::Pair {
+                    file,
+                    hunk,
+                    old: (j < removed).then_some(removed_start + j),
+                    new: (j < added).then_some(added_start + j),
+                }));
+            }
+            rows.push(DiffRow::Blank);
+        }
+
+        Self { rows }
+    }
+
+    /// Total number of rows
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// The row at `index`
+    pub fn row(&self, index: usize) -> Option<DiffRow> {
+        self.rows.get(index).copied()
+    }
+
+    /// Every row, top to bottom
+    pub fn rows(&self) -> &[DiffRow] {
+        &self.rows
+    }
+
+    /// File shown at `index`, counting blank and separator rows as part of
+    /// the file above them
+    pub fn file_at(&self, index: usize) -> Option<usize> {
+        let end = index.saturating_add(1).min(self.rows.len());
+        self.rows[..end].iter().rev().find_map(|row| row.file())
+    }
+
+    /// Rows from `start`, at most `count` of them
+    pub fn window(&self, start: usize, count: usize) -> &[DiffRow] {
+        let start = start.min(self.rows.len());
+        let end = start.saturating_add(count).min(self.rows.len());
+        &self.rows[start..end]
+    }
+}
+
+impl App {
+    /// Recompute the diff layout after the shown files or viewed marks change
+    ///
+    /// Side-by-side mode shows only the selected file. Search matches are
+    /// recomputed since they refer to rows.
+    pub(super) fn rebuild_layout(&mut self) {
+        self.diff_layout = match self.config.display.diff_mode {
+            DiffMode::Inline => DiffLayout::build(&self.current_files, |f| self.is_file_viewed(f)),
+            DiffMode::SideBySide => match self.selected_file() {
+                Some(file) => DiffLayout::build_side_by_side(
+                    self.selected_file_index,
+                    file,
+                    self.is_file_viewed(file),
+                ),
+                None => DiffLayout::default(),
+            },
+        };
+        if !self.search_matches.is_empty() {
+            self.refresh_search_matches();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{Hunk, HunkLine, LineType};
+
+    fn file(path: &str, hunk_starts: &[usize]) -> FileDiff {
+        let line = |n: usize| HunkLine {
+            line_type: LineType::Context,
+            old_line_num: Some(n),
+            new_line_num: Some(n),
+            content: String::new(),
+            raw: Vec::new(),
+            parent_markers: None,
+            no_newline: false,
+        };
+        FileDiff {
+            old_path: path.to_string(),
+            new_path: path.to_string(),
+            hunks: hunk_starts
+                .iter()
+                .map(|&start| Hunk {
+                    old_start: start,
+                    old_lines: 2,
+                    new_start: start,
+                    new_lines: 2,
+                    header: format!("@@ -{start},2 +{start},2 @@"),
+                    lines: vec![line(start), line(start + 1)],
+                })
+                .collect(),
+            old_file_lines: Some(10),
+            new_file_lines: Some(10),
+            old_size: None,
+            new_size: None,
+            old_blob_id: None,
+            new_blob_id: None,
+        }
+    }
+
+    #[test]
+    fn test_build_layout() {
+        let files = vec![file("a.rs", &[1]), file("b.rs", &[5, 9])];
+        let layout = DiffLayout::build(&files, |_| false);
+
+        use DiffRow::*;
+        let expected = [
+            OldPath { file: 0 },
+            NewPath { file: 0 },
+            Blank,
+            HunkHeader { file: 0, hunk: 0 },
+            Line {
+                file: 0,
+                hunk: 0,
+                line: 0,
+            },
+            Line {
+                file: 0,
+                hunk: 0,
+                line: 1,
+            },
+            ExpandBelow { file: 0, hunk: 0 },
+            Blank,
+            Blank,
+            Separator,
+            Blank,
+            OldPath { file: 1 },
+            NewPath { file: 1 },
+            Blank,
+            ExpandAbove { file: 1, hunk: 0 },
+            HunkHeader { file: 1, hunk: 0 },
+        ];
+        assert_eq!(layout.window(0, expected.len()), &expected);
+
+        // The last hunk reaches the end of the file: no expand row below it
+        assert_eq!(
+            layout.window(layout.len() - 2, 2)[0],
+            Line {
+                file: 1,
+                hunk: 1,
+                line: 1
+            }
+        );
+        assert!(layout.window(layout.len(), 5).is_empty());
+
+        // Viewed files collapse to their header
+        let collapsed = DiffLayout::build(&files, |f| f.new_path == "b.rs");
+        assert_eq!(collapsed.len(), 8 + 3 + 3);
+    }
+
+    #[test]
+    fn test_build_side_by_side_layout() {
+        let mut diff = file("a.rs", &[1]);
+        let line = diff.hunks[0].lines[0].clone();
+        let typed = |line_type| HunkLine {
+            line_type,
+            ..line.clone()
+        };
+        // context, -, -, +, context, +
+        diff.hunks[0].lines = vec![
+            typed(LineType::Context),
+            typed(LineType::Removed),
+            typed(LineType::Removed),
+            typed(LineType::Added),
+            typed(LineType::Context),
+            typed(LineType::Added),
+        ];
+
+        let layout = DiffLayout::build_side_by_side(2, &diff, false);
+        let pair = |old, new| DiffRow::Pair {
+            file: 2,
+            hunk: 0,
+            old,
+            new,
+        };
+        assert_eq!(
+            layout.rows(),
+            &[
+                DiffRow::HunkHeader { file: 2, hunk: 0 },
+                pair(Some(0), Some(0)),
+                pair(Some(1), Some(3)),
+                pair(Some(2), None),
+                pair(Some(4), Some(4)),
+                pair(None, Some(5)),
+                DiffRow::Blank,
+            ]
+        );
+
+        // Rows with one line offer it on both sides
+        assert_eq!(layout.row(3).unwrap().line(Side::New), Some(2));
+        assert_eq!(layout.row(2).unwrap().line(Side::Old), Some(1));
+        assert_eq!(layout.file_at(6), Some(2));
+
+        let collapsed = DiffLayout::build_side_by_side(2, &diff, true);
+        assert_eq!(collapsed.rows(), &[DiffRow::Collapsed { file: 2 }]);
+    }
+}
@@ -0,0 +1,140 @@
+use super::{App, FilterMode};
+use crate::git::{CommitInfo, PathFilter};
+use std::path::Path;
+
+impl App {
+    /// Apply CLI pathspecs and config excludes to diffs and the commit list
+    pub fn set_path_filter(&mut self, filter: PathFilter) {
+        self.path_filter = filter;
+        let commits = std::mem::take(&mut self.commits);
+        self.commits = self.filter_commits(commits);
+        self.selected_commit_index = self
+            .selected_commit_index
+            .min(self.commits.len().saturating_sub(1));
+    }
+
+    /// Record commits that only touch filtered-out files
+    ///
+    /// They are dropped from the list when `hide_excluded_commits` is set,
+    /// and dimmed in the log pane otherwise.
+    pub(super) fn filter_commits(&mut self, commits: Vec<CommitInfo>) -> Vec<CommitInfo> {
+        self.excluded_commits.clear();
+        if self.path_filter.is_empty() {
+            return commits;
+        }
+
+        for commit in &commits {
+            let touches =
+                crate::git::commit_touches_filter(&self.repo, commit.id, &self.path_filter)
+                    .unwrap_or(true);
+            if !touches {
+                self.excluded_commits.insert(commit.id);
+            }
+        }
+
+        if self.config.filter.hide_excluded_commits && self.log_options.graph {
+            crate::git::graph::hide_commits(commits, &self.excluded_commits)
+        } else if self.config.filter.hide_excluded_commits {
+            commits
+                .into_iter()
+                .filter(|c| !self.excluded_commits.contains(&c.id))
+                .collect()
+        } else {
+            commits
+        }
+    }
+
+    /// Check whether a commit touches only filtered-out files
+    pub fn is_commit_excluded(&self, commit: &CommitInfo) -> bool {
+        self.excluded_commits.contains(&commit.id)
+    }
+
+    /// Open the file filter prompt
+    pub fn start_file_filter(&mut self) {
+        self.file_filter_mode = FilterMode::Entering;
+    }
+
+    /// Close the prompt, keeping the current filter
+    pub fn confirm_file_filter(&mut self) {
+        self.file_filter_mode = FilterMode::Normal;
+    }
+
+    /// Close the prompt and show all files again
+    pub fn clear_file_filter(&mut self) {
+        self.file_filter_mode = FilterMode::Normal;
+        self.file_filter.clear();
+        self.update_file_filter();
+    }
+
+    /// Re-apply the file filter after the pattern changed
+    pub fn update_file_filter(&mut self) {
+        let selected = self.selected_file().map(|f| f.new_path.clone());
+        self.apply_file_filter();
+
+        // Stay on the same file if it still matches
+        self.selected_file_index = selected
+            .and_then(|path| self.current_files.iter().position(|f| f.new_path == path))
+            .unwrap_or(0);
+        self.rebuild_layout();
+        self.scroll_offset = 0;
+        self.cursor_line = 0;
+    }
+
+    /// Narrow `all_files` to those matching the file filter
+    pub(super) fn apply_file_filter(&mut self) {
+        self.current_files = match file_filter_pathspec(&self.file_filter) {
+            Some(spec) => self
+                .all_files
+                .iter()
+                .filter(|f| {
+                    [&f.new_path, &f.old_path].iter().any(|path| {
+                        spec.matches_path(Path::new(path), git2::PathspecFlags::IGNORE_CASE)
+                    })
+                })
+                .cloned()
+                .collect(),
+            None => self.all_files.clone(),
+        };
+        self.selected_file_index = self
+            .selected_file_index
+            .min(self.current_files.len().saturating_sub(1));
+        self.rebuild_layout();
+    }
+}
+
+/// Build a case-insensitive matcher for the file filter
+///
+/// Plain text matches anywhere in the path; text containing glob characters
+/// is used as a pathspec as-is.
+fn file_filter_pathspec(filter: &str) -> Option<git2::Pathspec> {
+    if filter.is_empty() {
+        return None;
+    }
+    let pattern = if filter.contains(['*', '?', '[']) {
+        filter.to_string()
+    } else {
+        format!("*{filter}*")
+    };
+    git2::Pathspec::new([pattern]).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_filter_pathspec() {
+        assert!(file_filter_pathspec("").is_none());
+
+        let matches = |filter: &str, path: &str| {
+            file_filter_pathspec(filter)
+                .unwrap()
+                .matches_path(Path::new(path), git2::PathspecFlags::IGNORE_CASE)
+        };
+        assert!(matches("diff", "src/ui/diff_view.rs"));
+        assert!(matches("DIFF", "src/ui/diff_view.rs"));
+        assert!(!matches("theme", "src/ui/diff_view.rs"));
+        assert!(matches("src/*.rs", "src/main.rs"));
+        assert!(!matches("*.md", "src/main.rs"));
+    }
+}
@@ -0,0 +1,270 @@
+use crate::git::{
+    merge::MergeDiffMode, range_diff, DiffOptions, FileDiff, PathFilter, WhitespaceMode,
+};
+use anyhow::{Context, Result};
+use encoding_rs::Encoding;
+use git2::{Oid, Repository};
+use std::collections::{HashSet, VecDeque};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// Number of parsed diffs kept in the cache
+const CACHE_CAPACITY: usize = 16;
+
+/// What a diff is generated from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiffTarget {
+    /// A commit against its parent (or parents, for merges)
+    Commit {
+        oid: Oid,
+        merge_mode: Option<MergeDiffMode>,
+    },
+    /// Diff of the patches of two versions of a commit (range-diff)
+    RangeDiff { old: Oid, new: Oid },
+    /// Diff of the trees of two versions of a commit (range-diff)
+    Interdiff { old: Oid, new: Oid },
+    /// Two trees diffed directly (`--diff`)
+    Trees { old: Oid, new: Oid },
+    /// Entry of `App::virtual_commits`, built on the UI thread from memory
+    Virtual { index: usize },
+}
+
+/// Everything that determines a parsed diff
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DiffKey {
+    pub target: DiffTarget,
+    pub context_lines: u32,
+    pub whitespace: WhitespaceMode,
+    pub paths: PathFilter,
+}
+
+impl DiffKey {
+    pub(super) fn options(&self, fallback_encoding: Option<&'static Encoding>) -> DiffOptions {
+        DiffOptions {
+            context_lines: self.context_lines,
+            whitespace: self.whitespace,
+            paths: self.paths.clone(),
+            fallback_encoding,
+        }
+    }
+}
+
+/// Small least-recently-used cache of parsed diffs
+pub struct DiffCache {
+    capacity: usize,
+    entries: VecDeque<(DiffKey, Vec<FileDiff>)>, // Most recently used last
+}
+
+impl DiffCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: VecDeque::new(),
+        }
+    }
+
+    pub fn contains(&self, key: &DiffKey) -> bool {
+        self.entries.iter().any(|(k, _)| k == key)
+    }
+
+    /// Look up a diff, marking it as recently used
+    pub fn get(&mut self, key: &DiffKey) -> Option<&Vec<FileDiff>> {
+        let idx = self.entries.iter().position(|(k, _)| k == key)?;
+        let entry = self.entries.remove(idx)?;
+        self.entries.push_back(entry);
+        self.entries.back().map(|(_, files)| files)
+    }
+
+    /// Add a diff, evicting the least recently used one when full
+    pub fn insert(&mut self, key: DiffKey, files: Vec<FileDiff>) {
+        self.entries.retain(|(k, _)| *k != key);
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((key, files));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Priority {
+    /// The diff the user is waiting for
+    Current,
+    /// A neighbouring commit's diff, loaded ahead of time
+    Prefetch,
+}
+
+struct DiffRequest {
+    key: DiffKey,
+    priority: Priority,
+}
+
+/// Worker reply: the parsed diff, or None when the request was dropped as stale
+type DiffResponse = (DiffKey, Option<Result<Vec<FileDiff>, String>>);
+
+/// Generates diffs on a background thread and caches the results
+pub struct DiffLoader {
+    requests: Sender<DiffRequest>,
+    responses: Receiver<DiffResponse>,
+    pending: HashSet<DiffKey>,
+    pub cache: DiffCache,
+}
+
+impl DiffLoader {
+    /// Start a worker with its own handle on the repository at `repo_path`
+    pub fn spawn(repo_path: &Path, fallback_encoding: Option<&'static Encoding>) -> Self {
+        let (request_tx, request_rx) = mpsc::channel();
+        let (response_tx, response_rx) = mpsc::channel();
+        let repo_path = repo_path.to_path_buf();
+
+        std::thread::spawn(move || {
+            run_worker(&repo_path, fallback_encoding, &request_rx, &response_tx)
+        });
+
+        Self {
+            requests: request_tx,
+            responses: response_rx,
+            pending: HashSet::new(),
+            cache: DiffCache::new(CACHE_CAPACITY),
+        }
+    }
+
+    /// Ask for the diff the user is waiting for
+    pub fn request(&mut self, key: DiffKey) {
+        self.send(key, Priority::Current);
+    }
+
+    /// Load a diff into the cache ahead of time, unless it's already there
+    pub fn prefetch(&mut self, key: DiffKey) {
+        if !self.cache.contains(&key) && !self.pending.contains(&key) {
+            self.send(key, Priority::Prefetch);
+        }
+    }
+
+    fn send(&mut self, key: DiffKey, priority: Priority) {
+        self.pending.insert(key.clone());
+        // A dead worker shows up as a disconnected response channel
+        let _ = self.requests.send(DiffRequest { key, priority });
+    }
+
+    /// Collect finished diffs without blocking
+    pub fn try_recv(&mut self) -> Option<DiffResponse> {
+        let response = self.responses.try_recv().ok()?;
+        self.pending.remove(&response.0);
+        Some(response)
+    }
+
+    /// Wait for the next finished diff (None if the worker has stopped)
+    pub fn recv(&mut self) -> Option<DiffResponse> {
+        let response = self.responses.recv().ok()?;
+        self.pending.remove(&response.0);
+        Some(response)
+    }
+}
+
+/// Worker loop: the newest current request is served first, older current
+/// requests are dropped (the user has moved on), then prefetches in order
+fn run_worker(
+    repo_path: &Path,
+    fallback_encoding: Option<&'static Encoding>,
+    requests: &Receiver<DiffRequest>,
+    responses: &Sender<DiffResponse>,
+) {
+    let repo = Repository::open(repo_path).map_err(|e| e.to_string());
+    let mut queue: Vec<DiffRequest> = Vec::new();
+
+    loop {
+        if queue.is_empty() {
+            match requests.recv() {
+                Ok(request) => queue.push(request),
+                Err(_) => return, // App has gone away
+            }
+        }
+        queue.extend(requests.try_iter());
+
+        let next = match queue.iter().rposition(|r| r.priority == Priority::Current) {
+            Some(idx) => {
+                let request = queue.remove(idx);
+                let (stale, rest) = queue
+                    .drain(..)
+                    .partition(|r| r.priority == Priority::Current);
+                queue = rest;
+                for stale in stale {
+                    if responses.send((stale.key, None)).is_err() {
+                        return;
+                    }
+                }
+                request
+            }
+            None => queue.remove(0),
+        };
+
+        let result = match &repo {
+            Ok(repo) => build_diff(repo, &next.key, &next.key.options(fallback_encoding))
+                .map_err(|e| format!("{e:#}")),
+            Err(e) => Err(format!("Failed to open repository: {e}")),
+        };
+        if responses.send((next.key, Some(result))).is_err() {
+            return;
+        }
+    }
+}
+
+/// Generate and parse the diff for a key
+pub fn build_diff(
+    repo: &Repository,
+    key: &DiffKey,
+    options: &DiffOptions,
+) -> Result<Vec<FileDiff>> {
+    let diff = match key.target {
+        DiffTarget::Virtual { .. } => anyhow::bail!("Virtual commits have no repository diff"),
+        DiffTarget::RangeDiff { old, new } => {
+            return range_diff::diff_of_diffs(repo, old, new, options);
+        }
+        DiffTarget::Interdiff { old, new } => range_diff::interdiff(repo, old, new, options)?,
+        DiffTarget::Trees { old, new } => crate::git::diff::diff_trees(repo, old, new, options)?,
+        DiffTarget::Commit { oid, merge_mode } => match merge_mode {
+            Some(MergeDiffMode::Combined) => {
+                return crate::git::merge::combined_diff(repo, oid, options);
+            }
+            Some(MergeDiffMode::Remerge) => crate::git::merge::remerge_diff(repo, oid, options)?,
+            Some(MergeDiffMode::Parent(n)) => {
+                let commit = repo.find_commit(oid)?;
+                crate::git::merge::diff_against_parent(repo, &commit, n, options)?
+            }
+            None => crate::git::generate_diff(repo, oid, options)?,
+        },
+    };
+    crate::git::files_from_diff(repo, &diff, options).context("Failed to read diff")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(n: u8) -> DiffKey {
+        DiffKey {
+            target: DiffTarget::Commit {
+                oid: Oid::from_bytes(&[n; 20]).unwrap(),
+                merge_mode: None,
+            },
+            context_lines: 3,
+            whitespace: WhitespaceMode::ShowAll,
+            paths: PathFilter::default(),
+        }
+    }
+
+    #[test]
+    fn test_diff_cache_evicts_least_recently_used() {
+        let mut cache = DiffCache::new(2);
+        cache.insert(key(1), Vec::new());
+        cache.insert(key(2), Vec::new());
+
+        // Using 1 makes 2 the oldest entry
+        assert!(cache.get(&key(1)).is_some());
+        cache.insert(key(3), Vec::new());
+
+        assert!(cache.contains(&key(1)));
+        assert!(!cache.contains(&key(2)));
+        assert!(cache.contains(&key(3)));
+    }
+}
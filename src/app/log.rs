@@ -0,0 +1,17 @@
+use super::App;
+use crate::git::CommitInfo;
+
+impl App {
+    /// Refresh ref decorations and the unpushed set shown in the log pane
+    pub fn load_log_decorations(&mut self) {
+        self.ref_decorations = crate::git::refs::ref_decorations(&self.repo).unwrap_or_default();
+        self.unpushed = crate::git::refs::unpushed_commits(&self.repo).unwrap_or(None);
+    }
+
+    /// Number of review comments stored for a commit
+    pub fn comment_count(&self, commit: &CommitInfo) -> usize {
+        self.comments_by_commit
+            .get(&commit.id.to_string())
+            .map_or(0, |cc| cc.comments.len())
+    }
+}
@@ -1,15 +1,37 @@
-use crate::comments::{Comment, CommentLevel, CommitComments};
+use crate::comments::{Comment, CommentLevel, CommitComments, VerdictKind};
 use crate::config::Config;
-use crate::git::{CommitInfo, FileDiff, LineType};
-use git2::Repository;
-use std::collections::HashMap;
+use crate::git::{
+    merge::MergeDiffMode, range_diff::RangeDiffPair, virtual_diff::VirtualCommit, CommitInfo,
+    FileDiff, LineType, LogOptions, PathFilter, WhitespaceMode,
+};
+use git2::{Oid, Repository};
+use loader::{DiffKey, DiffLoader};
+use ratatui::widgets::ListState;
+use std::collections::{HashMap, HashSet};
 
 // Implementation submodules
 mod comments;
 mod diff;
+mod diff_layout;
+mod filter;
+mod loader;
+mod log;
+mod mouse;
 mod navigation;
+mod panes;
+mod range_diff;
+mod reload;
 mod search;
+mod verdict;
 mod view;
+mod viewed;
+mod virtual_commits;
+mod yank;
+
+pub use diff_layout::{DiffLayout, DiffRow, Side};
+pub use mouse::DiffClick;
+pub use reload::RepoWatcher;
+pub use yank::YankTarget;
 
 /// Comment mode state
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -26,6 +48,8 @@ pub enum CommentMode {
     },
     /// Viewing comments at current location
     ViewingComments(Vec<Comment>),
+    /// Recording a verdict for the selected commit
+    Verdict { kind: VerdictKind },
 }
 
 /// Search mode state
@@ -37,10 +61,29 @@ pub enum SearchMode {
     Entering,
 }
 
+/// File filter prompt state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterMode {
+    /// Not editing the filter
+    Normal,
+    /// User is typing the filter pattern
+    Entering,
+}
+
+/// Pane that receives navigation keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    /// j/k move the commit selection
+    Log,
+    /// j/k move the diff cursor
+    Diff,
+}
+
 /// A single search match location
 #[derive(Debug, Clone)]
 pub struct SearchMatch {
     pub line_index: usize,  // Global line index in diff
+    pub side: Option<Side>, // Side-by-side column, None when on both
     pub char_start: usize,  // Character offset in line
     pub char_end: usize,    // End of match
 }
@@ -50,21 +93,39 @@ pub struct App {
     pub repo: Repository,
     pub commits: Vec<CommitInfo>,
     pub config: Config,
+    pub log_options: LogOptions, // How the commit list was walked (reused on reload)
 
     // UI state
     pub selected_commit_index: usize,
     pub selected_file_index: usize,
     pub log_pane_visible: bool,
+    pub focus: Pane,
+    pub log_list_state: ListState, // Scroll position of the commit list
+    pub dragging_log_border: bool, // Log pane border is being resized with the mouse
+    pub details_visible: bool,     // Commit message and metadata above the diff
     pub help_visible: bool,
+    pub stop_requested: bool, // Quit with Q: tell a calling `git difftool` to stop
     pub scroll_offset: usize,
-    pub horizontal_scroll: usize, // Horizontal scroll offset for side-by-side mode
+    pub horizontal_scroll: usize, // Horizontal scroll offset for long lines
     pub cursor_line: usize,       // Current line in diff view
+    pub cursor_side: Side,        // Side-by-side column the cursor targets
+    pub visual_anchor: Option<usize>, // Row where the visual selection started
+    pub yank_pending: bool,       // `y` pressed, waiting for what to copy
+    last_click: Option<(std::time::Instant, usize)>, // Time and row of the last diff click
     pub terminal_width: u16,
     pub terminal_height: u16,
 
     // Current diff data
-    pub current_files: Vec<FileDiff>,
+    pub all_files: Vec<FileDiff>, // Every file in the diff, before the file filter
+    pub current_files: Vec<FileDiff>, // Files shown (matching the file filter)
+    pub diff_layout: DiffLayout,  // Rows of the diff view for current_files
     pub current_context_lines: u32, // Context lines for current diff
+    pub merge_diff_mode: MergeDiffMode, // How merge commits are diffed
+    pub whitespace_mode: WhitespaceMode, // Whitespace changes hidden from the diff
+    pub highlight_whitespace_errors: bool, // Mark trailing/mixed whitespace in added lines
+    pub loading_diff: Option<DiffKey>, // Diff being generated for display, if any
+    diff_loader: DiffLoader,
+    restore_view: Option<(Oid, String, usize, usize)>, // Commit, file, scroll and cursor to keep after a reload
 
     // Comment system state
     pub comment_mode: CommentMode,
@@ -78,6 +139,28 @@ pub struct App {
     pub search_query: String,
     pub search_matches: Vec<SearchMatch>,
     pub current_match_index: Option<usize>,
+
+    // Path filtering
+    pub path_filter: PathFilter, // CLI pathspecs and config excludes
+    pub excluded_commits: HashSet<Oid>, // Commits touching only filtered-out files
+    pub file_filter: String,     // Interactive filter on the file list
+    pub file_filter_mode: FilterMode,
+
+    // Log pane decorations
+    pub ref_decorations: HashMap<Oid, Vec<String>>, // Branches and tags per commit
+    pub unpushed: Option<HashSet<Oid>>,             // None when there is no upstream
+
+    // Watch mode state (None when not watching)
+    pub watcher: Option<RepoWatcher>,
+
+    // Range-diff state (None when reviewing a single series)
+    pub range_diff: Option<Vec<RangeDiffPair>>,
+    pub show_interdiff: bool, // Interdiff instead of diff-of-diffs for modified pairs
+
+    // Patches under review instead of repository commits (None normally)
+    pub virtual_commits: Option<Vec<VirtualCommit>>,
+    // Old and new tree of a direct `--diff` (None normally)
+    pub tree_diff: Option<(Oid, Oid)>,
 }
 
 impl App {
@@ -91,22 +174,48 @@ impl App {
         let (width, height) = crossterm::terminal::size().unwrap_or((80, 24));
 
         let initial_context = config.display.context_lines;
+        let whitespace_mode = config.display.whitespace_mode;
+        let highlight_whitespace_errors = config.display.highlight_whitespace_errors;
+        let fallback_encoding = config
+            .display
+            .fallback_encoding
+            .as_deref()
+            .and_then(crate::git::encoding::encoding_for_label);
+        let diff_loader = DiffLoader::spawn(repo.path(), fallback_encoding);
 
         Self {
             repo,
             commits,
             config,
+            log_options: LogOptions::default(),
             selected_commit_index: 0,
             selected_file_index: 0,
             log_pane_visible: true,
+            focus: Pane::Diff,
+            log_list_state: ListState::default(),
+            dragging_log_border: false,
+            details_visible: false,
             help_visible: false,
+            stop_requested: false,
             scroll_offset: 0,
             horizontal_scroll: 0,
             cursor_line: 0,
+            cursor_side: Side::New,
+            visual_anchor: None,
+            yank_pending: false,
+            last_click: None,
             terminal_width: width,
             terminal_height: height,
+            all_files: Vec::new(),
             current_files: Vec::new(),
+            diff_layout: DiffLayout::default(),
             current_context_lines: initial_context,
+            merge_diff_mode: MergeDiffMode::default(),
+            whitespace_mode,
+            highlight_whitespace_errors,
+            loading_diff: None,
+            diff_loader,
+            restore_view: None,
             comment_mode: CommentMode::Normal,
             comment_draft: String::new(),
             comments_by_commit: HashMap::new(),
@@ -116,6 +225,17 @@ impl App {
             search_query: String::new(),
             search_matches: Vec::new(),
             current_match_index: None,
+            path_filter: PathFilter::default(),
+            excluded_commits: HashSet::new(),
+            file_filter: String::new(),
+            file_filter_mode: FilterMode::Normal,
+            ref_decorations: HashMap::new(),
+            unpushed: None,
+            watcher: None,
+            range_diff: None,
+            show_interdiff: false,
+            virtual_commits: None,
+            tree_diff: None,
         }
     }
 }
@@ -0,0 +1,44 @@
+use super::{App, DiffRow, Side};
+use std::time::{Duration, Instant};
+
+/// Two clicks on the same row within this interval make a double-click
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
+/// What a mouse click in the diff view landed on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffClick {
+    pub row: usize,
+    /// Side-by-side column clicked (None in inline mode)
+    pub side: Option<Side>,
+    /// Click was on a `[n]` comment indicator
+    pub on_indicator: bool,
+}
+
+impl App {
+    /// Handle a click in the diff view
+    ///
+    /// Places the cursor on the clicked row and side. Expand rows expand the
+    /// context, comment indicators open the comment viewer and a double-click
+    /// starts a comment on the row.
+    pub fn click_diff(&mut self, click: DiffClick) {
+        let double_click = self
+            .last_click
+            .is_some_and(|(time, row)| row == click.row && time.elapsed() < DOUBLE_CLICK_INTERVAL);
+        // A third click starts a new double-click
+        self.last_click = (!double_click).then(|| (Instant::now(), click.row));
+
+        self.move_cursor_to(click.row);
+        if let Some(side) = click.side {
+            self.cursor_side = side;
+        }
+
+        match self.diff_layout.row(click.row) {
+            Some(DiffRow::ExpandAbove { .. } | DiffRow::ExpandBelow { .. }) => {
+                self.expand_context();
+            }
+            _ if click.on_indicator => self.view_comments_at_current_location(),
+            _ if double_click => self.start_comment_creation(),
+            _ => {}
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use super::App;
+use super::{App, Pane};
 use crate::git::{CommitInfo, FileDiff};
 
 impl App {
@@ -17,6 +17,7 @@ impl App {
         if index < self.commits.len() && index != self.selected_commit_index {
             self.selected_commit_index = index;
             self.scroll_offset = 0;
+            self.cursor_line = 0;
             self.reset_horizontal_scroll();
             self.reset_context(); // Reset to default context for new commit
         }
@@ -27,6 +28,7 @@ impl App {
         if self.selected_commit_index + 1 < self.commits.len() {
             self.selected_commit_index += 1;
             self.scroll_offset = 0;
+            self.cursor_line = 0;
             self.reset_horizontal_scroll();
             self.reset_context(); // Reset to default context for new commit
         }
@@ -37,32 +39,74 @@ impl App {
         if self.selected_commit_index > 0 {
             self.selected_commit_index -= 1;
             self.scroll_offset = 0;
+            self.cursor_line = 0;
             self.reset_horizontal_scroll();
             self.reset_context(); // Reset to default context for new commit
         }
     }
 
+    /// Select a specific file in the current diff by index
+    pub fn select_file(&mut self, index: usize) {
+        if index < self.current_files.len() {
+            self.selected_file_index = index;
+            self.scroll_offset = 0;
+            self.cursor_line = 0;
+            self.reset_horizontal_scroll();
+            self.rebuild_layout();
+        }
+    }
+
     /// Navigate to next file in current diff
+    ///
+    /// Skips files marked as viewed when `skip_viewed_files` is enabled.
     pub fn next_file(&mut self) {
-        if self.selected_file_index + 1 < self.current_files.len() {
-            self.selected_file_index += 1;
+        let next = (self.selected_file_index + 1..self.current_files.len())
+            .find(|&idx| !self.should_skip_file(idx));
+        if let Some(idx) = next {
+            self.selected_file_index = idx;
             self.scroll_offset = 0;
+            self.cursor_line = 0;
             self.reset_horizontal_scroll();
+            self.rebuild_layout();
         }
     }
 
     /// Navigate to previous file in current diff
+    ///
+    /// Skips files marked as viewed when `skip_viewed_files` is enabled.
     pub fn previous_file(&mut self) {
-        if self.selected_file_index > 0 {
-            self.selected_file_index -= 1;
+        let previous = (0..self.selected_file_index)
+            .rev()
+            .find(|&idx| !self.should_skip_file(idx));
+        if let Some(idx) = previous {
+            self.selected_file_index = idx;
             self.scroll_offset = 0;
+            self.cursor_line = 0;
             self.reset_horizontal_scroll();
+            self.rebuild_layout();
         }
     }
 
+    /// Check if file navigation should pass over the file at index
+    fn should_skip_file(&self, index: usize) -> bool {
+        self.config.ui.skip_viewed_files
+            && self
+                .current_files
+                .get(index)
+                .is_some_and(|f| self.is_file_viewed(f))
+    }
+
     /// Toggle log pane visibility
     pub fn toggle_log_pane(&mut self) {
         self.log_pane_visible = !self.log_pane_visible;
+        if !self.log_pane_visible {
+            self.focus = Pane::Diff;
+        }
+    }
+
+    /// Toggle commit details pane visibility
+    pub fn toggle_details(&mut self) {
+        self.details_visible = !self.details_visible;
     }
 
     /// Toggle help dialog visibility
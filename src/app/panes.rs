@@ -0,0 +1,106 @@
+use super::{App, Pane};
+use crate::config::Config;
+
+/// Smallest and largest share of the terminal width for the log pane
+const MIN_LOG_PANE_RATIO: f32 = 0.1;
+const MAX_LOG_PANE_RATIO: f32 = 0.5;
+
+impl App {
+    /// Move keyboard focus to the next visible pane
+    pub fn cycle_focus(&mut self) {
+        self.focus = match self.focus {
+            Pane::Diff if self.log_pane_visible => Pane::Log,
+            _ => Pane::Diff,
+        };
+    }
+
+    /// Grow or shrink the log pane by `delta` of the terminal width and
+    /// save the new size
+    pub fn resize_log_pane(&mut self, delta: f32) {
+        self.set_log_pane_ratio(self.config.ui.log_pane_width_ratio + delta);
+        self.save_log_pane_ratio();
+    }
+
+    /// Set the log pane's share of the terminal width (not saved)
+    pub fn set_log_pane_ratio(&mut self, ratio: f32) {
+        // Two decimals keep the saved config readable
+        let ratio = (ratio * 100.0).round() / 100.0;
+        self.config.ui.log_pane_width_ratio = ratio.clamp(MIN_LOG_PANE_RATIO, MAX_LOG_PANE_RATIO);
+    }
+
+    /// Persist the log pane size to the config file
+    ///
+    /// Only the ratio is written, so command-line overrides in `self.config`
+    /// stay out of the file.
+    pub fn save_log_pane_ratio(&mut self) {
+        let ratio = self.config.ui.log_pane_width_ratio;
+        if let Err(e) = Config::update_file(|config| config.ui.log_pane_width_ratio = ratio) {
+            self.status_message = Some(format!("Failed to save config: {e}"));
+        }
+    }
+
+    /// Start dragging if `column` is on the border between log pane and diff
+    pub fn start_log_border_drag(&mut self, column: u16, log_pane_width: u16) {
+        // The log pane's right border or the diff view's left border
+        self.dragging_log_border = column + 1 == log_pane_width || column == log_pane_width;
+    }
+
+    /// Follow the mouse while dragging the log pane border
+    pub fn drag_log_border(&mut self, column: u16) {
+        if self.terminal_width > 0 {
+            self.set_log_pane_ratio((column + 1) as f32 / self.terminal_width as f32);
+        }
+    }
+
+    /// Finish dragging, saving the new size
+    pub fn end_log_border_drag(&mut self) {
+        self.dragging_log_border = false;
+        self.save_log_pane_ratio();
+    }
+
+    /// Select the first or last commit in the log
+    pub fn select_commit_edge(&mut self, last: bool) {
+        let index = if last {
+            self.commits.len().saturating_sub(1)
+        } else {
+            0
+        };
+        self.select_commit(index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::test_repo;
+
+    #[test]
+    fn test_focus_and_log_pane_ratio() {
+        let (_dir, repo) = test_repo::init();
+        let mut app = App::new(repo, Vec::new(), Config::default(), "main".to_string());
+        app.terminal_width = 100;
+
+        app.cycle_focus();
+        assert_eq!(app.focus, Pane::Log);
+        app.cycle_focus();
+        assert_eq!(app.focus, Pane::Diff);
+
+        // A hidden log pane can't take focus
+        app.log_pane_visible = false;
+        app.cycle_focus();
+        assert_eq!(app.focus, Pane::Diff);
+
+        app.set_log_pane_ratio(0.9);
+        assert_eq!(app.config.ui.log_pane_width_ratio, 0.5);
+
+        // Dragging the border to column 29 makes the pane 30 columns wide
+        app.start_log_border_drag(34, 35);
+        assert!(app.dragging_log_border);
+        app.drag_log_border(29);
+        assert_eq!(app.config.ui.log_pane_width_ratio, 0.3);
+
+        app.start_log_border_drag(10, 30);
+        assert!(!app.dragging_log_border);
+    }
+}
@@ -0,0 +1,42 @@
+use super::App;
+use crate::git::range_diff::{PairStatus, RangeDiffPair};
+
+impl App {
+    /// Switch the app to reviewing a range-diff between two series versions
+    ///
+    /// Replaces the commit list with one entry per pair.
+    pub fn set_range_diff(&mut self, pairs: Vec<RangeDiffPair>) {
+        self.commits = pairs.iter().map(|p| p.commit().clone()).collect();
+        self.selected_commit_index = 0;
+        self.range_diff = Some(pairs);
+    }
+
+    /// Get the range-diff pair for the selected commit (range-diff mode only)
+    pub fn current_range_pair(&self) -> Option<&RangeDiffPair> {
+        self.range_diff
+            .as_ref()
+            .and_then(|pairs| pairs.get(self.selected_commit_index))
+    }
+
+    /// Toggle between diff-of-diffs and the interdiff for modified pairs
+    pub fn toggle_interdiff(&mut self) {
+        let is_modified = self
+            .current_range_pair()
+            .is_some_and(|p| p.status == PairStatus::Modified);
+        if !is_modified {
+            self.status_message =
+                Some("Interdiff is only available for modified pairs".to_string());
+            return;
+        }
+
+        self.show_interdiff = !self.show_interdiff;
+        self.load_diff_for_current_commit();
+        self.scroll_offset = 0;
+        self.cursor_line = 0;
+        self.status_message = Some(if self.show_interdiff {
+            "Showing interdiff of the two trees".to_string()
+        } else {
+            "Showing diff of the two patches".to_string()
+        });
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use git2::{Oid, Repository};
+
+use super::App;
+
+/// How often the repository is polled for changes in watch mode
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Ref state that determines what git-review displays
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RepoSnapshot {
+    head: Option<Oid>,
+    head_ref: Option<String>,
+    start: Option<Oid>,
+    end: Option<Oid>,
+    notes: Option<Oid>,
+}
+
+/// Polls the repository for changes made outside git-review
+#[derive(Debug)]
+pub struct RepoWatcher {
+    start_ref: String,
+    end_ref: String,
+    snapshot: Option<RepoSnapshot>,
+    last_poll: Instant,
+    /// Patch ids by commit, so reloads only hash commits they haven't seen
+    patch_ids: HashMap<Oid, Option<Oid>>,
+}
+
+impl RepoWatcher {
+    /// Watch the range between two refs (e.g. base branch and HEAD)
+    pub fn new(start_ref: String, end_ref: String) -> Self {
+        Self {
+            start_ref,
+            end_ref,
+            snapshot: None,
+            last_poll: Instant::now(),
+            patch_ids: HashMap::new(),
+        }
+    }
+
+    fn patch_id(&mut self, repo: &Repository, oid: Oid) -> Option<Oid> {
+        *self
+            .patch_ids
+            .entry(oid)
+            .or_insert_with(|| crate::git::patch_id(repo, oid).ok())
+    }
+
+    /// Find the commit in a new list matching an old commit by id or patch id
+    fn find_matching_commit(
+        &mut self,
+        repo: &Repository,
+        oid: Oid,
+        commits: &[crate::git::CommitInfo],
+    ) -> Option<usize> {
+        if let Some(idx) = commits.iter().position(|c| c.id == oid) {
+            return Some(idx);
+        }
+
+        let patch_id = self.patch_id(repo, oid)?;
+        commits
+            .iter()
+            .position(|c| self.patch_id(repo, c.id) == Some(patch_id))
+    }
+
+    fn take_snapshot(&self, repo: &Repository, branch: &str) -> RepoSnapshot {
+        let head = repo.head().ok();
+        RepoSnapshot {
+            head: head.as_ref().and_then(|h| h.target()),
+            head_ref: head.as_ref().and_then(|h| h.name().map(|n| n.to_string())),
+            start: repo.revparse_single(&self.start_ref).ok().map(|o| o.id()),
+            end: repo.revparse_single(&self.end_ref).ok().map(|o| o.id()),
+            notes: crate::git::notes::notes_ref_target(repo, branch)
+                .ok()
+                .flatten(),
+        }
+    }
+}
+
+impl App {
+    /// Enable watch mode for the given commit range
+    pub fn enable_watch(&mut self, start_ref: String, end_ref: String) {
+        let mut watcher = RepoWatcher::new(start_ref, end_ref);
+        watcher.snapshot = Some(watcher.take_snapshot(&self.repo, &self.current_branch));
+        self.watcher = Some(watcher);
+    }
+
+    /// Record our own notes writes so they don't trigger a reload
+    pub(super) fn refresh_watch_snapshot(&mut self) {
+        if let Some(watcher) = self.watcher.as_mut() {
+            let snapshot = watcher.take_snapshot(&self.repo, &self.current_branch);
+            watcher.snapshot = Some(snapshot);
+        }
+    }
+
+    /// Poll the repository and reload if HEAD, the range refs or notes moved
+    ///
+    /// Does nothing until the watch interval has elapsed since the last poll.
+    /// Returns true if anything was reloaded.
+    pub fn check_for_repo_changes(&mut self) -> bool {
+        let Some(watcher) = self.watcher.as_mut() else {
+            return false;
+        };
+        if watcher.last_poll.elapsed() < WATCH_INTERVAL {
+            return false;
+        }
+        watcher.last_poll = Instant::now();
+
+        let snapshot = watcher.take_snapshot(&self.repo, &self.current_branch);
+        let previous = watcher.snapshot.replace(snapshot.clone());
+        let Some(previous) = previous else {
+            return false;
+        };
+        if previous == snapshot {
+            return false;
+        }
+
+        let refs_changed = previous.head != snapshot.head
+            || previous.head_ref != snapshot.head_ref
+            || previous.start != snapshot.start
+            || previous.end != snapshot.end;
+
+        // Notes are kept per branch, so a switch means another notes ref
+        let branch_changed =
+            previous.head_ref != snapshot.head_ref && self.switch_branch().unwrap_or(false);
+
+        if refs_changed {
+            self.load_log_decorations();
+            if let Err(e) = self.reload_commits() {
+                self.status_message = Some(format!("Failed to reload commits: {e}"));
+                return true;
+            }
+        }
+        if branch_changed || previous.notes != snapshot.notes {
+            self.comments_by_commit.clear();
+            self.load_comments();
+        }
+
+        self.status_message = Some("Repository changed - reloaded".to_string());
+        true
+    }
+
+    /// Follow HEAD to the branch it now points at
+    ///
+    /// Returns true if the branch changed, after refreshing the watch
+    /// snapshot against the new branch's notes.
+    fn switch_branch(&mut self) -> anyhow::Result<bool> {
+        let branch = crate::git::current_branch(&self.repo)?;
+        if branch == self.current_branch {
+            return Ok(false);
+        }
+        self.current_branch = branch;
+        self.refresh_watch_snapshot();
+        Ok(true)
+    }
+
+    /// Rebuild the commit list and current diff, preserving position
+    ///
+    /// The selected commit is matched by id, or by patch id when it was
+    /// rewritten (amend, rebase). The selected file and scroll position are
+    /// kept when the file still exists.
+    fn reload_commits(&mut self) -> anyhow::Result<()> {
+        let Some(watcher) = self.watcher.as_ref() else {
+            return Ok(());
+        };
+        let commits = crate::git::get_commit_log_range(
+            &self.repo,
+            &watcher.start_ref,
+            &watcher.end_ref,
+            &self.log_options,
+        )?;
+        let commits = self.filter_commits(commits);
+
+        let old_commit = self.selected_commit().map(|c| c.id);
+        let old_file = self.selected_file().map(|f| f.new_path.clone());
+        let (old_scroll, old_cursor) = (self.scroll_offset, self.cursor_line);
+
+        let new_index = old_commit.and_then(|oid| {
+            let watcher = self.watcher.as_mut()?;
+            watcher.find_matching_commit(&self.repo, oid, &commits)
+        });
+        let same_commit = new_index.is_some_and(|idx| Some(commits[idx].id) == old_commit);
+
+        self.commits = commits;
+        self.selected_commit_index = new_index
+            .unwrap_or(self.selected_commit_index)
+            .min(self.commits.len().saturating_sub(1));
+
+        // Keep the current context expansion when the commit is unchanged
+        if !same_commit {
+            self.current_context_lines = self.config.display.context_lines;
+        }
+        self.scroll_offset = 0;
+        self.cursor_line = 0;
+        // Applied once the new diff has loaded
+        self.restore_view = old_file
+            .zip(new_index.map(|idx| self.commits[idx].id))
+            .map(|(path, oid)| (oid, path, old_scroll, old_cursor));
+        self.load_diff_for_current_commit();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::test_repo;
+
+    #[test]
+    fn test_reload_keeps_selection_after_amend() {
+        let (dir, repo) = test_repo::init();
+        let base = test_repo::commit(&repo, &[("file.txt", b"base\n")], "Base");
+        test_repo::commit(&repo, &[("file.txt", b"one\n")], "First");
+        test_repo::commit(&repo, &[("file.txt", b"two\n")], "Second");
+
+        let commits =
+            crate::git::get_commit_log_range(&repo, &base.to_string(), "HEAD", &Default::default())
+                .unwrap();
+        let repo2 = Repository::open(dir.path()).unwrap();
+        let mut app = App::new(repo2, commits, Config::default(), "main".to_string());
+        app.init_diff();
+        app.enable_watch(base.to_string(), "HEAD".to_string());
+        app.select_commit(0);
+        let old_id = app.commits[0].id;
+
+        // Reword the tip commit (the change itself is identical), then add one
+        test_repo::amend(&repo, &[("file.txt", b"two\n")], "Second (reworded)");
+        test_repo::commit(&repo, &[("file.txt", b"three\n")], "Third");
+
+        app.watcher.as_mut().unwrap().last_poll = Instant::now() - WATCH_INTERVAL;
+        app.check_for_repo_changes();
+        app.wait_for_diff();
+
+        assert_eq!(app.commits.len(), 3);
+        assert_ne!(app.commits[1].id, old_id);
+        assert_eq!(app.selected_commit_index, 1);
+        assert_eq!(
+            app.selected_commit().unwrap().summary(),
+            "Second (reworded)"
+        );
+        assert_eq!(app.current_files.len(), 1);
+    }
+
+    #[test]
+    fn test_reload_follows_branch_switch() {
+        let (dir, repo) = test_repo::init();
+        let base = test_repo::commit(&repo, &[("file.txt", b"base\n")], "Base");
+        let tip = test_repo::commit(&repo, &[("file.txt", b"one\n")], "First");
+        let comments = crate::comments::CommitComments::new(tip.to_string(), "feature".into());
+        crate::git::notes::write_comments(&repo, "feature", tip, &comments).unwrap();
+
+        let commits =
+            crate::git::get_commit_log_range(&repo, &base.to_string(), "HEAD", &Default::default())
+                .unwrap();
+        let branch = crate::git::current_branch(&repo).unwrap();
+        let repo2 = Repository::open(dir.path()).unwrap();
+        let mut app = App::new(repo2, commits, Config::default(), branch);
+        app.init_diff();
+        app.enable_watch(base.to_string(), "HEAD".to_string());
+        assert!(app.comments_by_commit.is_empty());
+
+        repo.branch("feature", &repo.find_commit(tip).unwrap(), false)
+            .unwrap();
+        repo.set_head("refs/heads/feature").unwrap();
+
+        app.watcher.as_mut().unwrap().last_poll = Instant::now() - WATCH_INTERVAL;
+        assert!(app.check_for_repo_changes());
+        app.wait_for_diff();
+
+        assert_eq!(app.current_branch, "feature");
+        assert!(app.comments_by_commit.contains_key(&tip.to_string()));
+    }
+}
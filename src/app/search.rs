@@ -1,4 +1,4 @@
-use super::{App, SearchMatch, SearchMode};
+use super::{App, DiffRow, SearchMatch, SearchMode, Side};
 
 impl App {
     /// Start search mode
@@ -17,57 +17,7 @@ impl App {
             return;
         }
 
-        let query = self.search_query.to_lowercase();
-        let mut line_index = 0;
-        let mut matches = Vec::new();
-
-        // Search through all files in current diff
-        for (file_idx, file) in self.current_files.iter().enumerate() {
-            // Add lines for file separator (except first file)
-            if file_idx > 0 {
-                line_index += 3; // blank + separator + blank
-            }
-
-            // File header lines (search in file paths)
-            let old_path_lower = file.old_path.to_lowercase();
-            let new_path_lower = file.new_path.to_lowercase();
-
-            find_matches_in_line(&old_path_lower, line_index, &query, &mut matches);
-            line_index += 1;
-            find_matches_in_line(&new_path_lower, line_index, &query, &mut matches);
-            line_index += 2; // new path + blank
-
-            // Hunks
-            for hunk in &file.hunks {
-                // Search hunk header
-                find_matches_in_line(&hunk.header.to_lowercase(), line_index, &query, &mut matches);
-                line_index += 1;
-
-                // Account for expand button above if available
-                if hunk.available_lines_above() > 0 {
-                    line_index += 1;
-                }
-
-                // Hunk lines
-                for line in &hunk.lines {
-                    let content_lower = line.content.to_lowercase();
-                    find_matches_in_line(&content_lower, line_index, &query, &mut matches);
-                    line_index += 1;
-                }
-
-                // Account for expand button below if available
-                if let Some(file_lines) = file.new_file_lines {
-                    if hunk.can_expand_below(file_lines) {
-                        line_index += 1;
-                    }
-                }
-
-                // Blank line after hunk
-                line_index += 1;
-            }
-        }
-
-        self.search_matches = matches;
+        self.search_matches = self.find_search_matches();
 
         // Jump to first match if any found
         if !self.search_matches.is_empty() {
@@ -84,19 +34,81 @@ impl App {
         self.search_mode = SearchMode::Normal;
     }
 
+    /// Find the search query in every row of the diff layout
+    ///
+    /// Offsets are characters into the row's text: the path, hunk header or
+    /// line content, without line numbers or prefixes.
+    fn find_search_matches(&self) -> Vec<SearchMatch> {
+        let query = self.search_query.to_lowercase();
+        let mut matches = Vec::new();
+
+        for (line_index, row) in self.diff_layout.rows().iter().enumerate() {
+            for (side, text) in self.searchable_text(*row) {
+                find_matches_in_line(&text.to_lowercase(), line_index, side, &query, &mut matches);
+            }
+        }
+        matches
+    }
+
+    /// Text shown on a row, per side (None when it spans both)
+    fn searchable_text(&self, row: DiffRow) -> Vec<(Option<Side>, &str)> {
+        let files = &self.current_files;
+        match row {
+            DiffRow::OldPath { file } => vec![(None, files[file].old_path.as_str())],
+            DiffRow::NewPath { file } => vec![(None, files[file].new_path.as_str())],
+            DiffRow::HunkHeader { file, hunk } => {
+                vec![(None, files[file].hunks[hunk].header.as_str())]
+            }
+            DiffRow::Line { file, hunk, line } => {
+                vec![(None, files[file].hunks[hunk].lines[line].content.as_str())]
+            }
+            DiffRow::Pair {
+                file,
+                hunk,
+                old,
+                new,
+            } => {
+                let lines = &files[file].hunks[hunk].lines;
+                if old == new {
+                    // Context line, the same on both sides
+                    old.map(|i| (None, lines[i].content.as_str()))
+                        .into_iter()
+                        .collect()
+                } else {
+                    [(Side::Old, old), (Side::New, new)]
+                        .into_iter()
+                        .filter_map(|(side, i)| Some((Some(side), lines[i?].content.as_str())))
+                        .collect()
+                }
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Recompute matches after the layout changed, keeping the current
+    /// match number where possible
+    pub(super) fn refresh_search_matches(&mut self) {
+        self.search_matches = self.find_search_matches();
+        self.current_match_index = match self.search_matches.len() {
+            0 => None,
+            n => self.current_match_index.map(|idx| idx.min(n - 1)),
+        };
+    }
+
     /// Scroll to a specific match
     fn scroll_to_match(&mut self, match_idx: usize) {
         if let Some(search_match) = self.search_matches.get(match_idx) {
-            // Set cursor to match line
+            // Set cursor to match line, on the side of the match
             self.cursor_line = search_match.line_index;
+            if let Some(side) = search_match.side {
+                self.cursor_side = side;
+            }
 
             // Center the match in the viewport
-            let visible_height = self.terminal_height.saturating_sub(3) as usize;
-            let target_scroll = search_match
-                .line_index
-                .saturating_sub(visible_height / 2);
+            let visible_height = self.visible_diff_height();
+            let target_scroll = search_match.line_index.saturating_sub(visible_height / 2);
 
-            let content_lines = self.calculate_content_lines_cached();
+            let content_lines = self.calculate_content_lines();
             let max_scroll = content_lines.saturating_sub(visible_height);
 
             self.scroll_offset = target_scroll.min(max_scroll);
@@ -168,57 +180,65 @@ impl App {
         self.status_message = None;
     }
 
-    /// Get matches for a specific line (for highlighting)
-    pub fn get_matches_for_line(&self, line_index: usize) -> Vec<(usize, usize)> {
+    /// Get matches for a specific row (for highlighting)
+    ///
+    /// With a side, only matches shown in that side-by-side column.
+    pub fn get_matches_for_line(
+        &self,
+        line_index: usize,
+        side: Option<Side>,
+    ) -> Vec<(usize, usize)> {
         self.search_matches
             .iter()
             .filter(|m| m.line_index == line_index)
+            .filter(|m| side.is_none() || m.side.is_none() || m.side == side)
             .map(|m| (m.char_start, m.char_end))
             .collect()
     }
-
-    /// Calculate content lines (cached helper for search)
-    fn calculate_content_lines_cached(&self) -> usize {
-        // Reuse the existing calculate_content_lines from view module
-        let mut total = 0;
-
-        for (file_idx, file) in self.current_files.iter().enumerate() {
-            if file_idx > 0 {
-                total += 3;
-            }
-            total += 3;
-
-            for hunk in &file.hunks {
-                total += 1;
-                total += hunk.lines.len();
-                total += 1;
-
-                if hunk.available_lines_above() > 0 {
-                    total += 1;
-                }
-                if let Some(file_lines) = file.new_file_lines {
-                    if hunk.can_expand_below(file_lines) {
-                        total += 1;
-                    }
-                }
-            }
-        }
-
-        total
-    }
 }
 
 /// Find all matches in a single line (helper function)
-fn find_matches_in_line(line_lower: &str, line_index: usize, query: &str, matches: &mut Vec<SearchMatch>) {
+fn find_matches_in_line(
+    line_lower: &str,
+    line_index: usize,
+    side: Option<Side>,
+    query: &str,
+    matches: &mut Vec<SearchMatch>,
+) {
     let mut start = 0;
+    let query_chars = query.chars().count();
 
     while let Some(pos) = line_lower[start..].find(query) {
         let absolute_pos = start + pos;
+        let char_start = line_lower[..absolute_pos].chars().count();
         matches.push(SearchMatch {
             line_index,
-            char_start: absolute_pos,
-            char_end: absolute_pos + query.len(),
+            side,
+            char_start,
+            char_end: char_start + query_chars,
         });
-        start = absolute_pos + 1; // Move past this match to find overlapping matches
+        // Move past this match's first character to find overlapping matches
+        start = absolute_pos
+            + line_lower[absolute_pos..]
+                .chars()
+                .next()
+                .map_or(1, char::len_utf8);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_matches_uses_character_offsets() {
+        let mut matches = Vec::new();
+        find_matches_in_line("café café", 4, Some(Side::New), "é", &mut matches);
+
+        let found: Vec<_> = matches.iter().map(|m| (m.char_start, m.char_end)).collect();
+        assert_eq!(found, vec![(3, 4), (8, 9)]);
+        assert!(matches
+            .iter()
+            .all(|m| m.line_index == 4 && m.side == Some(Side::New)));
     }
 }
@@ -0,0 +1,68 @@
+use super::{App, CommentMode};
+use crate::comments::{CommitComments, VerdictKind};
+
+impl App {
+    /// Open the verdict dialog for the selected commit
+    ///
+    /// Pre-fills the dialog with the existing verdict, if any.
+    pub fn start_verdict(&mut self) {
+        if self.selected_commit().is_none() {
+            self.status_message = Some("No commit selected".to_string());
+            return;
+        }
+
+        let existing = self
+            .current_commit_comments()
+            .and_then(|cc| cc.verdict.clone());
+        let kind = existing.as_ref().map_or(VerdictKind::Approve, |v| v.kind);
+        self.comment_draft = existing.and_then(|v| v.summary).unwrap_or_default();
+        self.comment_mode = CommentMode::Verdict { kind };
+    }
+
+    /// Cycle the verdict kind while the dialog is open
+    pub fn cycle_verdict_kind(&mut self) {
+        if let CommentMode::Verdict { kind } = &mut self.comment_mode {
+            *kind = kind.next();
+        }
+    }
+
+    /// Save the verdict from the dialog for the selected commit
+    pub fn save_verdict(&mut self) -> anyhow::Result<()> {
+        let CommentMode::Verdict { kind } = self.comment_mode else {
+            return Ok(());
+        };
+
+        if let Some(commit) = self.selected_commit() {
+            let oid = commit.id;
+            let cid = oid.to_string();
+            let branch = self.current_branch.clone();
+            self.comments_by_commit
+                .entry(cid.clone())
+                .or_insert_with(|| CommitComments::new(cid, branch))
+                .set_verdict(kind, &self.comment_draft);
+            self.persist_commit_review(oid)?;
+            self.status_message = Some(format!("Verdict saved: {}", kind.label()));
+        }
+
+        self.comment_mode = CommentMode::Normal;
+        self.comment_draft.clear();
+        Ok(())
+    }
+
+    /// Remove the verdict from the selected commit
+    pub fn clear_verdict(&mut self) -> anyhow::Result<()> {
+        if let Some(commit) = self.selected_commit() {
+            let oid = commit.id;
+            if let Some(cc) = self.comments_by_commit.get_mut(&oid.to_string()) {
+                if cc.clear_verdict().is_some() {
+                    self.persist_commit_review(oid)?;
+                    self.status_message = Some("Verdict cleared".to_string());
+                }
+            }
+        }
+
+        self.comment_mode = CommentMode::Normal;
+        self.comment_draft.clear();
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
-use super::App;
+use super::{App, DiffRow, Side};
 use crate::comments::CommentLevel;
 use crate::config::DiffMode;
-use crate::git::LineType;
+use crate::git::{FileDiff, LineType};
 
 impl App {
     /// Set diff mode
@@ -9,6 +9,9 @@ impl App {
         self.config.display.diff_mode = mode;
         // Reset horizontal scroll when switching modes
         self.reset_horizontal_scroll();
+        // The modes lay rows out differently
+        self.rebuild_layout();
+        self.scroll(0);
         // Save config - log error but don't fail
         if let Err(e) = self.config.save() {
             eprintln!("Warning: Failed to save config: {e}");
@@ -18,7 +21,7 @@ impl App {
     /// Scroll diff view vertically with bounds checking
     pub fn scroll(&mut self, amount: isize) {
         let content_lines = self.calculate_content_lines();
-        let visible_height = self.terminal_height.saturating_sub(3) as usize; // header + footer + borders
+        let visible_height = self.visible_diff_height();
         let max_scroll = content_lines.saturating_sub(visible_height);
 
         if amount < 0 {
@@ -30,11 +33,87 @@ impl App {
             self.cursor_line = self
                 .cursor_line
                 .saturating_add(amount as usize)
-                .min(content_lines);
+                .min(content_lines.saturating_sub(1));
         }
     }
 
-    /// Scroll diff view horizontally (side-by-side mode only)
+    /// Move the cursor by `amount` rows, scrolling only once it reaches
+    /// the edge of the viewport
+    pub fn move_cursor(&mut self, amount: isize) {
+        let target = self.cursor_line.saturating_add_signed(amount);
+        self.move_cursor_to(target);
+    }
+
+    /// Put the cursor on `line` (clamped to the diff) and scroll it into view
+    pub fn move_cursor_to(&mut self, line: usize) {
+        let content_lines = self.calculate_content_lines();
+        let visible_height = self.visible_diff_height();
+        self.cursor_line = line.min(content_lines.saturating_sub(1));
+
+        if self.cursor_line < self.scroll_offset {
+            self.scroll_offset = self.cursor_line;
+        } else if self.cursor_line >= self.scroll_offset + visible_height {
+            self.scroll_offset = self.cursor_line + 1 - visible_height;
+        }
+    }
+
+    /// Scroll the view and cursor by half a page, like Ctrl-d/Ctrl-u in vim
+    pub fn scroll_half_page(&mut self, direction: isize) {
+        let half = (self.visible_diff_height() / 2).max(1) as isize;
+        self.scroll(half * direction.signum());
+        // Keep the cursor moving at the end of the diff, where the view can't
+        self.move_cursor(0);
+    }
+
+    /// Move left in side-by-side mode
+    ///
+    /// Scrolls left, or moves the cursor to the old side once the start of
+    /// the line is showing.
+    pub fn move_left(&mut self, amount: usize) {
+        if self.horizontal_scroll == 0 && self.cursor_side == Side::New {
+            self.cursor_side = Side::Old;
+        } else {
+            self.scroll_horizontal(-(amount as isize));
+        }
+    }
+
+    /// Move right in side-by-side mode
+    ///
+    /// On the old side, moves the cursor to the new side once the end of the
+    /// line is showing (`visible_width` columns of content fit on screen).
+    /// Otherwise scrolls right.
+    pub fn move_right(&mut self, amount: usize, visible_width: usize) {
+        if self.cursor_side == Side::Old
+            && self.cursor_line_width(Side::Old) <= self.horizontal_scroll + visible_width
+        {
+            self.cursor_side = Side::New;
+            self.reset_horizontal_scroll();
+        } else {
+            self.scroll_horizontal(amount as isize);
+        }
+    }
+
+    /// Width of the line shown on `side` of the cursor row, with its +/- prefix
+    fn cursor_line_width(&self, side: Side) -> usize {
+        let Some(row) = self.diff_layout.row(self.cursor_line) else {
+            return 0;
+        };
+        match (row, row.hunk()) {
+            (DiffRow::Pair { old, new, .. }, Some((file, hunk))) => {
+                let index = if side == Side::Old { old } else { new };
+                index.map_or(0, |i| {
+                    self.current_files[file].hunks[hunk].lines[i]
+                        .content
+                        .chars()
+                        .count()
+                        + 1
+                })
+            }
+            _ => 0,
+        }
+    }
+
+    /// Scroll diff view horizontally
     pub fn scroll_horizontal(&mut self, amount: isize) {
         if amount < 0 {
             // Scroll left
@@ -52,7 +131,7 @@ impl App {
 
     /// Scroll by full pages (visible height)
     pub fn scroll_page(&mut self, direction: isize) {
-        let visible_height = self.terminal_height.saturating_sub(3) as usize;
+        let visible_height = self.visible_diff_height();
         let amount = if direction < 0 {
             -(visible_height as isize)
         } else {
@@ -61,38 +140,16 @@ impl App {
         self.scroll(amount);
     }
 
-    /// Calculate total number of lines in current diff view
-    fn calculate_content_lines(&self) -> usize {
-        let mut total = 0;
-
-        for (file_idx, file) in self.current_files.iter().enumerate() {
-            // File separator (except first file)
-            if file_idx > 0 {
-                total += 3; // blank + separator + blank
-            }
-
-            // File header
-            total += 3; // old path + new path + blank
-
-            // Hunks
-            for hunk in &file.hunks {
-                total += 1; // hunk header
-                total += hunk.lines.len();
-                total += 1; // blank line after hunk
-
-                // Account for expand buttons
-                if hunk.available_lines_above() > 0 {
-                    total += 1;
-                }
-                if let Some(file_lines) = file.new_file_lines {
-                    if hunk.can_expand_below(file_lines) {
-                        total += 1;
-                    }
-                }
-            }
-        }
+    /// Number of diff rows that fit in the diff view, inside its borders
+    pub(super) fn visible_diff_height(&self) -> usize {
+        crate::ui::diff_view_area(self)
+            .map_or(0, |area| area.height.saturating_sub(2) as usize)
+            .max(1)
+    }
 
-        total
+    /// Calculate total number of lines in current diff view
+    pub(super) fn calculate_content_lines(&self) -> usize {
+        self.diff_layout.len()
     }
 
     /// Handle terminal resize
@@ -101,11 +158,23 @@ impl App {
         self.terminal_height = height;
     }
 
+    /// File under the cursor
+    ///
+    /// Inline mode shows every file, so this follows the cursor rather than
+    /// the selected file.
+    pub fn cursor_file(&self) -> Option<&FileDiff> {
+        self.diff_layout
+            .file_at(self.cursor_line)
+            .and_then(|idx| self.current_files.get(idx))
+            .or_else(|| self.selected_file())
+    }
+
     /// Detect what the cursor is pointing at for comment context
     /// Returns the comment level, line number, line type, and hunk header
     ///
-    /// This function traverses the diff display to find where the cursor is positioned.
-    /// It handles edge cases like expand buttons, file separators, and empty diffs.
+    /// Looks the cursor row up in the diff layout: lines give line-level
+    /// context, expand buttons and hunk headers hunk-level context, and
+    /// anything else (paths, separators, empty diffs) file-level context.
     pub fn detect_comment_context(
         &self,
     ) -> (
@@ -114,109 +183,169 @@ impl App {
         Option<LineType>,
         Option<String>,
     ) {
-        // Edge case: no files means no valid comment context
-        if self.current_files.is_empty() {
+        // Edge case: no rows means no valid comment context
+        if self.diff_layout.is_empty() {
             return (CommentLevel::File, None, None, None);
         }
+        let Some(row) = self.diff_layout.row(self.cursor_line) else {
+            return (CommentLevel::File, None, None, None);
+        };
+        let Some((file, hunk)) = row.hunk() else {
+            return (CommentLevel::File, None, None, None);
+        };
+        let hunk = &self.current_files[file].hunks[hunk];
 
-        // Calculate which line in the diff the cursor is on
-        let mut current_line = 0;
-        let target_line = self.cursor_line;
-
-        for (file_idx, file) in self.current_files.iter().enumerate() {
-            // File separator (except first file)
-            if file_idx > 0 {
-                current_line += 3; // blank + separator + blank
-                if current_line > target_line {
-                    // Cursor is on separator - treat as file-level for previous file
-                    return (CommentLevel::File, None, None, None);
-                }
+        // Side-by-side rows with two lines comment on the cursor's side
+        if let Some(line) = row.line(self.cursor_side).map(|i| &hunk.lines[i]) {
+            if let Some(line_num) = line.comment_line_number() {
+                return (
+                    CommentLevel::Line,
+                    Some(line_num),
+                    Some(line.line_type),
+                    None,
+                );
             }
+        }
 
-            // File header
-            current_line += 3; // old path + new path + blank
-            if current_line > target_line {
-                // Cursor is on file header - file-level comment
-                return (CommentLevel::File, None, None, None);
-            }
+        (CommentLevel::Hunk, None, None, Some(hunk.header.clone()))
+    }
+}
 
-            // Hunks
-            for hunk in &file.hunks {
-                // Hunk header
-                let hunk_header_line = current_line;
-                current_line += 1;
-
-                // Expand button above if available
-                if hunk.available_lines_above() > 0 {
-                    current_line += 1;
-                    // If cursor is on expand button, treat as hunk-level
-                    if current_line - 1 == target_line {
-                        return (
-                            CommentLevel::Hunk,
-                            None,
-                            None,
-                            Some(hunk.header.clone()),
-                        );
-                    }
-                }
-
-                // Hunk lines
-                for line in hunk.lines.iter() {
-                    if current_line == target_line {
-                        // Cursor is on a specific line
-                        let line_number = match line.line_type {
-                            LineType::Added => line.new_line_num,
-                            LineType::Removed => line.old_line_num,
-                            LineType::Context => {
-                                line.new_line_num.or(line.old_line_num)
-                            }
-                        };
-
-                        if let Some(line_num) = line_number {
-                            return (
-                                CommentLevel::Line,
-                                Some(line_num),
-                                Some(line.line_type),
-                                None,
-                            );
-                        }
-                    }
-                    current_line += 1;
-                }
-
-                // Expand button below if available
-                if let Some(file_lines) = file.new_file_lines {
-                    if hunk.can_expand_below(file_lines) {
-                        current_line += 1;
-                        // If cursor is on expand button, treat as hunk-level
-                        if current_line - 1 == target_line {
-                            return (
-                                CommentLevel::Hunk,
-                                None,
-                                None,
-                                Some(hunk.header.clone()),
-                            );
-                        }
-                    }
-                }
-
-                // Blank line after hunk
-                current_line += 1;
-
-                // If cursor is within this hunk's range, return hunk context
-                if target_line >= hunk_header_line && target_line < current_line {
-                    return (
-                        CommentLevel::Hunk,
-                        None,
-                        None,
-                        Some(hunk.header.clone()),
-                    );
-                }
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::git::{Hunk, HunkLine};
+    use crate::test_repo;
+    use git2::Repository;
+
+    fn hunk_line(line_type: LineType, old: Option<usize>, new: Option<usize>) -> HunkLine {
+        HunkLine {
+            line_type,
+            old_line_num: old,
+            new_line_num: new,
+            content: "text".to_string(),
+            raw: b"text".to_vec(),
+            parent_markers: None,
+            no_newline: false,
+        }
+    }
+
+    fn app_with_lines(repo: Repository, lines: Vec<HunkLine>) -> App {
+        let mut app = App::new(repo, Vec::new(), Config::default(), "main".to_string());
+        app.terminal_height = 13; // 9 visible rows
+        app.config.display.diff_mode = DiffMode::Inline;
+        app.current_files = vec![FileDiff {
+            old_path: "a.txt".to_string(),
+            new_path: "a.txt".to_string(),
+            hunks: vec![Hunk {
+                old_start: 1,
+                old_lines: lines.len(),
+                new_start: 1,
+                new_lines: lines.len(),
+                header: "@@ -1 +1 @@".to_string(),
+                lines,
+            }],
+            old_file_lines: None,
+            new_file_lines: None,
+            old_size: None,
+            new_size: None,
+            old_blob_id: None,
+            new_blob_id: None,
+        }];
+        app.rebuild_layout();
+        app
+    }
 
+    #[test]
+    fn test_cursor_scrolls_only_at_viewport_edge() {
+        let (_dir, repo) = test_repo::init();
+        let lines = (1..=20)
+            .map(|n| hunk_line(LineType::Context, Some(n), Some(n)))
+            .collect();
+        let mut app = app_with_lines(repo, lines);
+        // Paths, blank, header, 20 lines, expand below, blank
+        assert_eq!(app.calculate_content_lines(), 26);
+
+        assert_eq!(app.visible_diff_height(), 9);
+        for _ in 0..8 {
+            app.move_cursor(1);
         }
+        assert_eq!((app.cursor_line, app.scroll_offset), (8, 0));
+        app.move_cursor(1);
+        assert_eq!((app.cursor_line, app.scroll_offset), (9, 1));
+
+        app.move_cursor_to(usize::MAX);
+        assert_eq!((app.cursor_line, app.scroll_offset), (25, 17));
+        app.move_cursor(-10);
+        assert_eq!((app.cursor_line, app.scroll_offset), (15, 15));
+        app.move_cursor_to(0);
+        assert_eq!((app.cursor_line, app.scroll_offset), (0, 0));
+
+        // A page is the diff view's height
+        app.scroll_page(1);
+        assert_eq!((app.cursor_line, app.scroll_offset), (9, 9));
+        app.scroll_page(-1);
+        assert_eq!((app.cursor_line, app.scroll_offset), (0, 0));
+
+        // Cursor on the first line of the hunk
+        app.move_cursor_to(4);
+        assert_eq!(
+            app.detect_comment_context(),
+            (CommentLevel::Line, Some(1), Some(LineType::Context), None)
+        );
+    }
+
+    #[test]
+    fn test_search_match_puts_cursor_on_its_side() {
+        let (_dir, repo) = test_repo::init();
+        let mut removed = hunk_line(LineType::Removed, Some(1), None);
+        removed.content = "gone".to_string();
+        let mut app = app_with_lines(
+            repo,
+            vec![removed, hunk_line(LineType::Added, None, Some(1))],
+        );
+        app.config.display.diff_mode = DiffMode::SideBySide;
+        app.rebuild_layout();
+        assert_eq!(app.cursor_side, Side::New);
+
+        app.search_query = "gone".to_string();
+        app.execute_search();
+        assert_eq!(app.cursor_side, Side::Old);
+        assert_eq!(
+            app.detect_comment_context(),
+            (CommentLevel::Line, Some(1), Some(LineType::Removed), None)
+        );
+    }
+
+    #[test]
+    fn test_side_by_side_cursor_switches_sides_at_line_edge() {
+        let (_dir, repo) = test_repo::init();
+        let lines = vec![
+            hunk_line(LineType::Context, Some(1), Some(1)),
+            hunk_line(LineType::Removed, Some(2), None),
+            hunk_line(LineType::Added, None, Some(2)),
+        ];
+        let mut app = app_with_lines(repo, lines);
+        app.config.display.diff_mode = DiffMode::SideBySide;
+        app.rebuild_layout();
+
+        // Header, context pair, removed/added pair, blank
+        app.move_cursor_to(2);
+        assert_eq!(app.cursor_side, Side::New);
+        assert_eq!(app.detect_comment_context().2, Some(LineType::Added));
+
+        app.move_left(4);
+        assert_eq!(app.cursor_side, Side::Old);
+        assert_eq!(
+            app.detect_comment_context(),
+            (CommentLevel::Line, Some(2), Some(LineType::Removed), None)
+        );
 
-        // Default to file level (cursor beyond all content)
-        (CommentLevel::File, None, None, None)
+        // The old line doesn't fit: scroll first, then switch
+        app.move_right(4, 2);
+        assert_eq!((app.cursor_side, app.horizontal_scroll), (Side::Old, 4));
+        app.move_right(4, 2);
+        assert_eq!((app.cursor_side, app.horizontal_scroll), (Side::New, 0));
     }
 }
@@ -0,0 +1,118 @@
+use super::App;
+use crate::comments::CommitComments;
+use crate::git::FileDiff;
+
+impl App {
+    /// Check if a file in the current commit is marked as viewed
+    ///
+    /// Marks only apply while the file's blob id matches the one recorded.
+    /// They follow the path and blob rather than the commit: after a rebase
+    /// that leaves a file alone, the mark stored on the old commit still
+    /// counts.
+    pub fn is_file_viewed(&self, file: &FileDiff) -> bool {
+        let Some(blob_id) = file.content_id() else {
+            return false;
+        };
+        self.comments_by_commit
+            .values()
+            .any(|cc| cc.is_viewed(&file.new_path, blob_id))
+    }
+
+    /// Toggle the viewed mark on the currently selected file
+    pub fn toggle_file_viewed(&mut self) -> anyhow::Result<()> {
+        let Some(file) = self.selected_file() else {
+            self.status_message = Some("No file selected".to_string());
+            return Ok(());
+        };
+        let Some(blob_id) = file.content_id().map(|s| s.to_string()) else {
+            self.status_message = Some("Cannot mark file without content as viewed".to_string());
+            return Ok(());
+        };
+        let file_path = file.new_path.clone();
+
+        if let Some(commit) = self.selected_commit() {
+            let oid = commit.id;
+            let viewed = !self.is_file_viewed(file);
+            if viewed {
+                let cid = oid.to_string();
+                let branch = self.current_branch.clone();
+                self.comments_by_commit
+                    .entry(cid.clone())
+                    .or_insert_with(|| CommitComments::new(cid, branch))
+                    .toggle_viewed(&file_path, &blob_id);
+                self.persist_commit_review(oid)?;
+            } else {
+                // Unmark it wherever it was recorded, e.g. on a commit from
+                // before a rebase
+                let marked: Vec<String> = self
+                    .comments_by_commit
+                    .iter()
+                    .filter(|(_, cc)| cc.is_viewed(&file_path, &blob_id))
+                    .map(|(cid, _)| cid.clone())
+                    .collect();
+                for cid in marked {
+                    if let Some(cc) = self.comments_by_commit.get_mut(&cid) {
+                        cc.toggle_viewed(&file_path, &blob_id);
+                    }
+                    self.persist_commit_review(git2::Oid::from_str(&cid)?)?;
+                }
+            }
+
+            self.status_message = Some(if viewed {
+                format!("Marked {file_path} as viewed")
+            } else {
+                format!("Unmarked {file_path}")
+            });
+
+            // Collapsing or expanding a file changes the line layout
+            self.rebuild_layout();
+            self.scroll_offset = 0;
+            self.cursor_line = 0;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::test_repo;
+
+    fn app_for_head(repo: git2::Repository, base: git2::Oid) -> App {
+        let commits =
+            crate::git::get_commit_log_range(&repo, &base.to_string(), "HEAD", &Default::default())
+                .unwrap();
+        let mut app = App::new(repo, commits, Config::default(), "main".to_string());
+        app.load_comments();
+        app.init_diff();
+        app.wait_for_diff();
+        app
+    }
+
+    #[test]
+    fn test_viewed_mark_survives_rebase() {
+        let (dir, repo) = test_repo::init();
+        let base = test_repo::commit(&repo, &[("a.txt", b"one\n")], "Base");
+        test_repo::commit(&repo, &[("a.txt", b"two\n")], "Change");
+
+        let mut app = app_for_head(repo, base);
+        app.toggle_file_viewed().unwrap();
+        let file = app.selected_file().unwrap().clone();
+        assert!(app.is_file_viewed(&file));
+
+        // Reworded: a new commit id, but the same file content
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        test_repo::amend(&repo, &[("a.txt", b"two\n")], "Change (reworded)");
+        let mut app = app_for_head(repo, base);
+        let file = app.selected_file().unwrap().clone();
+        assert!(app.is_file_viewed(&file));
+
+        // Unmarking clears the mark stored on the old commit
+        app.toggle_file_viewed().unwrap();
+        assert!(!app.is_file_viewed(&file));
+        let repo = git2::Repository::open(dir.path()).unwrap();
+        let app = app_for_head(repo, base);
+        assert!(!app.is_file_viewed(&file));
+    }
+}
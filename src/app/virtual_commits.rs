@@ -0,0 +1,93 @@
+use super::loader::DiffKey;
+use super::App;
+use crate::git::virtual_diff::{self, VirtualCommit};
+use crate::git::FileDiff;
+
+impl App {
+    /// Switch the app to reviewing virtual commits, like mailed patches
+    ///
+    /// Replaces the commit list. Comments and marks on them are kept in
+    /// memory instead of git notes.
+    pub fn set_virtual_commits(&mut self, commits: Vec<VirtualCommit>) {
+        self.commits = commits.iter().map(|c| c.commit.clone()).collect();
+        self.selected_commit_index = 0;
+        self.virtual_commits = Some(commits);
+    }
+
+    /// Get the virtual commit that is selected (virtual commits only)
+    pub fn current_virtual_commit(&self) -> Option<&VirtualCommit> {
+        self.virtual_commits
+            .as_ref()
+            .and_then(|commits| commits.get(self.selected_commit_index))
+    }
+
+    /// Build the diff of a virtual commit for `key`
+    pub(super) fn virtual_diff(
+        &self,
+        index: usize,
+        key: &DiffKey,
+    ) -> Result<Vec<FileDiff>, String> {
+        let commit = self
+            .virtual_commits
+            .as_ref()
+            .and_then(|commits| commits.get(index))
+            .ok_or_else(|| format!("No virtual commit {index}"))?;
+        let fallback_encoding = self
+            .config
+            .display
+            .fallback_encoding
+            .as_deref()
+            .and_then(crate::git::encoding::encoding_for_label);
+        virtual_diff::files_from_virtual(&commit.files, &key.options(fallback_encoding))
+            .map_err(|e| format!("{e:#}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, DiffMode};
+    use crate::git::mbox::parse_patch;
+    use crate::git::virtual_diff::commits_from_patches;
+    use crate::test_repo;
+
+    #[test]
+    fn test_patch_review_expands_context_and_keeps_comments_in_memory() {
+        let (_dir, repo) = test_repo::init();
+        let content: String = (1..=20).map(|i| format!("{i}\n")).collect();
+        let base = test_repo::commit(&repo, &[("a.txt", content.as_bytes())], "Base");
+
+        let patch = "From: A <a@example.com>
+Subject: [PATCH] Ten
+
+---
+diff --git a/a.txt b/a.txt
+--- a/a.txt
++++ b/a.txt
+@@ -10 +10 @@
+-10
++ten
+";
+        let patches = parse_patch(patch, "ten.patch").unwrap();
+        let commits = commits_from_patches(&repo, &patches, Some(base)).unwrap();
+        let mut config = Config::default();
+        config.display.diff_mode = DiffMode::Inline;
+        config.display.context_lines = 1;
+        let mut app = App::new(repo, Vec::new(), config, "main".to_string());
+        app.set_virtual_commits(commits);
+        app.init_diff();
+        assert_eq!(app.current_files[0].hunks[0].lines.len(), 4);
+
+        app.expand_context();
+        assert!(app.current_files[0].hunks[0].lines.len() > 4);
+        assert!(app.status_message.is_none());
+
+        app.start_comment_creation();
+        app.comment_draft = "Why?".to_string();
+        app.save_comment().unwrap();
+        let oid = app.selected_commit().unwrap().id;
+        assert_eq!(app.comments_by_commit[&oid.to_string()].comments.len(), 1);
+        let notes = crate::git::notes::read_all_for_branch(&app.repo, "main").unwrap();
+        assert!(notes.is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+use super::{App, DiffRow, Side};
+use crate::git::{HunkLine, LineType};
+use std::ops::RangeInclusive;
+
+/// What a yank copies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YankTarget {
+    /// Text of the cursor line
+    Line,
+    /// Text of the lines in the visual selection
+    Selection,
+    /// The cursor's hunk as a patch `git apply` accepts
+    Hunk,
+    /// `path:line` of the cursor line
+    Location,
+    /// Full id of the selected commit
+    CommitId,
+}
+
+impl App {
+    /// Start or cancel a visual line selection at the cursor
+    pub fn toggle_visual_selection(&mut self) {
+        self.visual_anchor = match self.visual_anchor {
+            Some(_) => None,
+            None => Some(self.cursor_line),
+        };
+    }
+
+    /// Rows covered by the visual selection, if one is active
+    pub fn selection_range(&self) -> Option<RangeInclusive<usize>> {
+        let anchor = self.visual_anchor?;
+        Some(anchor.min(self.cursor_line)..=anchor.max(self.cursor_line))
+    }
+
+    /// Copy `target` to the clipboard, reporting the result in the footer
+    pub fn yank(&mut self, target: YankTarget) {
+        let Some((bytes, description)) = self.yank_content(target) else {
+            self.status_message = Some("Nothing to copy here".to_string());
+            return;
+        };
+        self.status_message = Some(
+            match crate::clipboard::copy(&bytes, &self.config.clipboard) {
+                Ok(()) => format!("Copied {description}"),
+                Err(e) => format!("Failed to copy {description}: {e:#}"),
+            },
+        );
+        self.visual_anchor = None;
+    }
+
+    /// Bytes to copy for `target` and a description for the footer
+    pub(super) fn yank_content(&self, target: YankTarget) -> Option<(Vec<u8>, String)> {
+        match target {
+            YankTarget::Line => {
+                let (_, line) = self.line_at(self.cursor_line, self.cursor_side)?;
+                let number = line_number(line).map_or(String::new(), |n| format!(" {n}"));
+                Some((line.content.clone().into_bytes(), format!("line{number}")))
+            }
+            YankTarget::Selection => {
+                let range = self.selection_range()?;
+                let lines: Vec<String> = range
+                    .filter_map(|index| self.selected_line_text(index))
+                    .collect();
+                if lines.is_empty() {
+                    return None;
+                }
+                let description = format!("{} lines", lines.len());
+                Some((lines.join("\n").into_bytes(), description))
+            }
+            YankTarget::Hunk => {
+                let (file, hunk) = self.diff_layout.row(self.cursor_line)?.hunk()?;
+                let file = &self.current_files[file];
+                let hunk = &file.hunks[hunk];
+                let mut patch =
+                    format!("--- a/{}\n+++ b/{}\n", file.old_path, file.new_path).into_bytes();
+                patch.extend(hunk.to_patch());
+                Some((patch, format!("hunk {} as patch", hunk.header)))
+            }
+            YankTarget::Location => {
+                let file = self.cursor_file()?;
+                let location = match self.line_at(self.cursor_line, self.cursor_side) {
+                    Some((_, line)) => {
+                        // Removed lines only exist in the old version
+                        let path = if line.line_type == LineType::Removed {
+                            &file.old_path
+                        } else {
+                            &file.new_path
+                        };
+                        match line_number(line) {
+                            Some(n) => format!("{path}:{n}"),
+                            None => path.clone(),
+                        }
+                    }
+                    None => file.new_path.clone(),
+                };
+                Some((location.clone().into_bytes(), location))
+            }
+            YankTarget::CommitId => {
+                let id = self.selected_commit()?.id.to_string();
+                let description = format!("commit {}", &id[..7]);
+                Some((id.into_bytes(), description))
+            }
+        }
+    }
+
+    /// Hunk line shown on `side` of a row
+    fn line_at(&self, index: usize, side: Side) -> Option<(DiffRow, &HunkLine)> {
+        let row = self.diff_layout.row(index)?;
+        let (file, hunk) = row.hunk()?;
+        let line = row.line(side)?;
+        Some((row, &self.current_files[file].hunks[hunk].lines[line]))
+    }
+
+    /// Text a selected row contributes: inline rows keep their +/- prefix,
+    /// side-by-side rows give the cursor side's line, if it has one
+    fn selected_line_text(&self, index: usize) -> Option<String> {
+        let (row, line) = self.line_at(index, self.cursor_side)?;
+        match row {
+            DiffRow::Pair { .. } if row.cursor_side(self.cursor_side) != self.cursor_side => None,
+            DiffRow::Pair { .. } => Some(line.content.clone()),
+            _ => {
+                let prefix = match line.line_type {
+                    LineType::Added => '+',
+                    LineType::Removed => '-',
+                    LineType::Context => ' ',
+                };
+                Some(format!("{prefix}{}", line.content))
+            }
+        }
+    }
+}
+
+/// Line number of a hunk line in the version it belongs to
+fn line_number(line: &HunkLine) -> Option<usize> {
+    match line.line_type {
+        LineType::Added => line.new_line_num,
+        LineType::Removed => line.old_line_num,
+        LineType::Context => line.new_line_num.or(line.old_line_num),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Config, DiffMode};
+    use crate::test_repo;
+
+    #[test]
+    fn test_yank_content() {
+        let (_dir, repo) = test_repo::init();
+        let base = test_repo::commit(&repo, &[("a.txt", b"one\ntwo\nthree\n")], "Base");
+        let tip = test_repo::commit(&repo, &[("a.txt", b"one\n2\nthree\n")], "Change");
+
+        let commits =
+            crate::git::get_commit_log_range(&repo, &base.to_string(), "HEAD", &Default::default())
+                .unwrap();
+        let mut config = Config::default();
+        config.display.diff_mode = DiffMode::Inline;
+        let mut app = App::new(repo, commits, config, "main".to_string());
+        app.init_diff();
+
+        // Paths, blank, hunk header, " one", "-two", "+2"
+        app.cursor_line = 6;
+        let text = |app: &App, target| {
+            let (bytes, description) = app.yank_content(target).unwrap();
+            (String::from_utf8(bytes).unwrap(), description)
+        };
+        assert_eq!(
+            text(&app, YankTarget::Line),
+            ("2".to_string(), "line 2".to_string())
+        );
+        assert_eq!(text(&app, YankTarget::Location).0, "a.txt:2");
+        assert_eq!(
+            text(&app, YankTarget::CommitId),
+            (tip.to_string(), format!("commit {}", &tip.to_string()[..7]))
+        );
+
+        let (patch, description) = text(&app, YankTarget::Hunk);
+        assert!(patch.starts_with("--- a/a.txt\n+++ b/a.txt\n@@ -1,3 +1,3 @@"));
+        assert!(patch.ends_with(" one\n-two\n+2\n three\n"));
+        assert!(description.starts_with("hunk @@ -1,3 +1,3 @@"));
+
+        // Selecting from "-two" up to " one"
+        app.cursor_line = 5;
+        app.toggle_visual_selection();
+        app.cursor_line = 4;
+        assert_eq!(
+            text(&app, YankTarget::Selection),
+            (" one\n-two".to_string(), "2 lines".to_string())
+        );
+        assert_eq!(text(&app, YankTarget::Location).0, "a.txt:1");
+    }
+}
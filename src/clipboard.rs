@@ -0,0 +1,68 @@
+use crate::config::ClipboardConfig;
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Copy bytes to the system clipboard
+///
+/// Sends an OSC 52 escape sequence to the terminal, which works over SSH
+/// and inside tmux (with `set-clipboard on`), and/or pipes the bytes to the
+/// configured clipboard command.
+pub fn copy(bytes: &[u8], config: &ClipboardConfig) -> Result<()> {
+    if config.osc52 {
+        let mut stdout = std::io::stdout();
+        stdout
+            .write_all(osc52_sequence(bytes).as_bytes())
+            .and_then(|_| stdout.flush())
+            .context("Failed to write to terminal")?;
+    }
+
+    match config.command.as_deref() {
+        Some(command) => run_command(command, bytes),
+        None if config.osc52 => Ok(()),
+        None => bail!("OSC 52 is disabled and no clipboard command is configured"),
+    }
+}
+
+/// Escape sequence asking the terminal to put `bytes` on the clipboard
+pub fn osc52_sequence(bytes: &[u8]) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+    format!("\x1b]52;c;{encoded}\x07")
+}
+
+/// Run a clipboard command such as `wl-copy`, feeding it `bytes` on stdin
+fn run_command(command: &str, bytes: &[u8]) -> Result<()> {
+    let mut parts = command.split_whitespace();
+    let program = parts.next().context("Clipboard command is empty")?;
+
+    // Output would corrupt the terminal UI
+    let mut child = Command::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run {program}"))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(bytes)
+            .with_context(|| format!("Failed to write to {program}"))?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        bail!("{program} exited with {status}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence(b"hello"), "\x1b]52;c;aGVsbG8=\x07");
+    }
+}
@@ -0,0 +1,287 @@
+use anyhow::{bail, Context, Result};
+use git2::{Oid, Repository};
+use std::io::Read;
+use std::path::Path;
+
+use crate::app::Side;
+use crate::comments::{Comment, CommitComments};
+use crate::export;
+use crate::git::{self, notes, DiffOptions, FileDiff, LineType};
+
+/// Where a comment added from the command line is attached
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Anchor {
+    /// The file as a whole
+    File,
+    /// A line, numbered in the old or new version of the file
+    Line { number: usize, side: Side },
+    /// A hunk, by its `@@ -a,b +c,d @@` header
+    Hunk(String),
+}
+
+/// Add a comment to a commit's review notes and return its id
+///
+/// The anchor must be part of the commit's diff, generated with the same
+/// context as the UI, so the comment shows up where it was meant to.
+pub fn add_comment(
+    repo: &Repository,
+    branch: &str,
+    rev: &str,
+    file_path: &str,
+    anchor: Anchor,
+    text: String,
+    context_lines: u32,
+) -> Result<String> {
+    let commit_oid = resolve_commit(repo, rev)?;
+    let options = DiffOptions {
+        context_lines,
+        ..Default::default()
+    };
+    let diff = git::generate_diff(repo, commit_oid, &options)?;
+    let files = git::files_from_diff(repo, &diff, &options)?;
+    let file = files
+        .iter()
+        .find(|f| f.new_path == file_path || f.old_path == file_path)
+        .with_context(|| format!("{file_path} is not changed in commit {commit_oid:.7}"))?;
+
+    let path = file.new_path.clone();
+    let comment = match anchor {
+        Anchor::File => Comment::new_file(path, text)?,
+        Anchor::Hunk(header) => {
+            let hunk = file
+                .hunks
+                .iter()
+                .find(|h| hunk_range(&h.header) == hunk_range(&header))
+                .with_context(|| {
+                    let headers: Vec<&str> = file.hunks.iter().map(|h| h.header.as_str()).collect();
+                    format!(
+                        "No hunk {header} in {file_path}, hunks are:\n  {}",
+                        headers.join("\n  ")
+                    )
+                })?;
+            Comment::new_hunk(path, hunk.header.clone(), text)?
+        }
+        Anchor::Line { number, side } => {
+            let (number, kind) = resolve_line(file, number, side)?;
+            Comment::new_line(path, number, kind, text)?
+        }
+    };
+
+    let id = comment.id.clone();
+    let mut commit_comments = notes::read_comments(repo, branch, commit_oid)?
+        .unwrap_or_else(|| CommitComments::new(commit_oid.to_string(), branch.to_string()));
+    commit_comments.add_comment(comment);
+    notes::write_comments(repo, branch, commit_oid, &commit_comments)?;
+    Ok(id)
+}
+
+/// List the branch's comments as JSON, optionally only those on one commit
+pub fn list_comments(repo: &Repository, branch: &str, rev: Option<&str>) -> Result<String> {
+    let mut all = notes::read_all_for_branch(repo, branch)?;
+    if let Some(rev) = rev {
+        let commit_id = resolve_commit(repo, rev)?.to_string();
+        all.retain(|cc| cc.commit_id == commit_id);
+    }
+    export::to_comment_list_json(&all)
+}
+
+/// Remove a comment by id, dropping the note once nothing is left in it
+pub fn remove_comment(repo: &Repository, branch: &str, id: &str) -> Result<Comment> {
+    let (commit_oid, mut commit_comments) = find_comment(repo, branch, id)?;
+    let comment = commit_comments
+        .remove_comment_by_id(id)
+        .context("Comment disappeared while removing it")?;
+    if commit_comments.is_empty() && !commit_comments.has_review_marks() {
+        notes::delete_commit_note(repo, branch, commit_oid)?;
+    } else {
+        notes::write_comments(repo, branch, commit_oid, &commit_comments)?;
+    }
+    Ok(comment)
+}
+
+/// Replace the text of a comment, keeping its id and anchor
+pub fn edit_comment(repo: &Repository, branch: &str, id: &str, text: String) -> Result<()> {
+    let (commit_oid, mut commit_comments) = find_comment(repo, branch, id)?;
+    let comment = commit_comments
+        .comment_mut(id)
+        .context("Comment disappeared while editing it")?;
+    comment.text = text;
+    notes::write_comments(repo, branch, commit_oid, &commit_comments)
+}
+
+/// Comment text from `--text` or a file, where `-` reads standard input
+pub fn read_text(text: Option<String>, text_file: Option<&Path>) -> Result<String> {
+    let text = match (text, text_file) {
+        (Some(text), _) => text,
+        (None, Some(path)) if path == Path::new("-") => {
+            let mut text = String::new();
+            std::io::stdin()
+                .read_to_string(&mut text)
+                .context("Failed to read comment from stdin")?;
+            text
+        }
+        (None, Some(path)) => std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read comment from {}", path.display()))?,
+        (None, None) => bail!("Comment text is required (--text or --text-file)"),
+    };
+    let text = text.trim_end().to_string();
+    if text.trim().is_empty() {
+        bail!("Comment text is empty");
+    }
+    Ok(text)
+}
+
+fn resolve_commit(repo: &Repository, rev: &str) -> Result<Oid> {
+    let commit = repo
+        .revparse_single(rev)
+        .and_then(|object| object.peel_to_commit())
+        .with_context(|| format!("Failed to resolve commit '{rev}'"))?;
+    Ok(commit.id())
+}
+
+fn find_comment(repo: &Repository, branch: &str, id: &str) -> Result<(Oid, CommitComments)> {
+    notes::find_comment(repo, branch, id)?
+        .with_context(|| format!("No comment with id {id} on branch {branch}"))
+}
+
+/// The `@@ ... @@` part of a hunk header, without the function context
+fn hunk_range(header: &str) -> &str {
+    let header = header.trim();
+    match header.get(2..).and_then(|rest| rest.find("@@")) {
+        Some(end) => &header[..end + 4],
+        None => header,
+    }
+}
+
+/// Map a line number on one side to the number and type comments use
+///
+/// Lines shown in the diff keep their own type. Unchanged lines outside the
+/// hunks are context lines, which comments number in the new file.
+fn resolve_line(file: &FileDiff, number: usize, side: Side) -> Result<(usize, LineType)> {
+    let (file_lines, side_name) = match side {
+        Side::Old => (file.old_file_lines, "old"),
+        Side::New => (file.new_file_lines, "new"),
+    };
+    if number == 0 || number > file_lines.unwrap_or(0) {
+        bail!(
+            "Line {number} is outside the {side_name} version of {}",
+            file.new_path
+        );
+    }
+
+    let shown = file
+        .hunks
+        .iter()
+        .flat_map(|h| &h.lines)
+        .find(|l| match side {
+            Side::Old => l.old_line_num == Some(number),
+            Side::New => l.new_line_num == Some(number),
+        });
+    if let Some(line) = shown {
+        return Ok((line.comment_line_number().unwrap_or(number), line.line_type));
+    }
+
+    let number = match side {
+        Side::New => number,
+        Side::Old => {
+            // Shift by the lines added or removed in the hunks above
+            let shift: isize = file
+                .hunks
+                .iter()
+                .filter(|h| h.old_start + h.old_lines.max(1) <= number)
+                .map(|h| h.new_lines as isize - h.old_lines as isize)
+                .sum();
+            number.saturating_add_signed(shift)
+        }
+    };
+    Ok((number, LineType::Context))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comments::CommentLocation;
+    use crate::test_repo;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, Repository, Oid) {
+        let (dir, repo) = test_repo::init();
+        let base: String = (1..=30).map(|i| format!("line {i}\n")).collect();
+        test_repo::commit(&repo, &[("file.txt", base.as_bytes())], "Base");
+        // Drop line 3 and change line 25
+        let changed = base
+            .replace("line 3\n", "")
+            .replace("line 25\n", "line twenty-five\n");
+        let oid = test_repo::commit(&repo, &[("file.txt", changed.as_bytes())], "Change");
+        (dir, repo, oid)
+    }
+
+    #[test]
+    fn test_add_line_comments_on_either_side() {
+        let (_dir, repo, oid) = setup();
+        let old = Anchor::Line {
+            number: 3,
+            side: Side::Old,
+        };
+        add_comment(&repo, "main", "HEAD", "file.txt", old, "Why?".into(), 0).unwrap();
+        // Unchanged old line 20 is new line 19
+        let unchanged = Anchor::Line {
+            number: 20,
+            side: Side::Old,
+        };
+        add_comment(&repo, "main", "HEAD", "file.txt", unchanged, "Ok".into(), 0).unwrap();
+
+        let cc = notes::read_comments(&repo, "main", oid).unwrap().unwrap();
+        let locations: Vec<_> = cc.comments.iter().map(|c| &c.location).collect();
+        assert_eq!(
+            locations,
+            vec![
+                &CommentLocation::Line {
+                    number: 3,
+                    kind: LineType::Removed
+                },
+                &CommentLocation::Line {
+                    number: 19,
+                    kind: LineType::Context
+                },
+            ]
+        );
+
+        let past_end = Anchor::Line {
+            number: 31,
+            side: Side::New,
+        };
+        assert!(add_comment(&repo, "main", "HEAD", "file.txt", past_end, "x".into(), 0).is_err());
+        assert!(add_comment(
+            &repo,
+            "main",
+            "HEAD",
+            "other.txt",
+            Anchor::File,
+            "x".into(),
+            0
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_hunk_comment_edit_and_remove_by_id() {
+        let (_dir, repo, oid) = setup();
+        let anchor = Anchor::Hunk("@@ -25 +24 @@".into());
+        let id = add_comment(&repo, "main", "HEAD", "file.txt", anchor, "Hunk".into(), 0).unwrap();
+
+        edit_comment(&repo, "main", &id, "Edited".into()).unwrap();
+        let listed = list_comments(&repo, "main", Some("HEAD")).unwrap();
+        let listed: serde_json::Value = serde_json::from_str(&listed).unwrap();
+        assert_eq!(listed[0]["id"], id.as_str());
+        assert_eq!(listed[0]["text"], "Edited");
+        // The header is matched without its function context
+        assert_eq!(listed[0]["hunk_header"], "@@ -25 +24 @@ line 24");
+        assert_eq!(listed[0]["commit"], oid.to_string());
+
+        let removed = remove_comment(&repo, "main", &id).unwrap();
+        assert_eq!(removed.text, "Edited");
+        assert!(notes::read_comments(&repo, "main", oid).unwrap().is_none());
+        assert!(remove_comment(&repo, "main", &id).is_err());
+    }
+}
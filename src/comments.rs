@@ -89,6 +89,11 @@ pub enum CommentLocation {
 /// A single comment attached to code
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Comment {
+    /// Stable identifier for addressing the comment from scripts
+    ///
+    /// Older notes have none; `CommitComments::from_json` derives it.
+    #[serde(default)]
+    pub id: String,
     /// Granularity level of this comment
     pub level: CommentLevel,
     /// Path to the file this comment is about
@@ -112,6 +117,7 @@ impl Comment {
     ) -> Result<Self> {
         validate_file_path(&file_path).context("Invalid file path for line comment")?;
         Ok(Self {
+            id: String::new(),
             level: CommentLevel::Line,
             file_path,
             location: CommentLocation::Line {
@@ -120,13 +126,15 @@ impl Comment {
             },
             text,
             created_at: Local::now(),
-        })
+        }
+        .with_id())
     }
 
     /// Create a new hunk-level comment
     pub fn new_hunk(file_path: String, hunk_header: String, text: String) -> Result<Self> {
         validate_file_path(&file_path).context("Invalid file path for hunk comment")?;
         Ok(Self {
+            id: String::new(),
             level: CommentLevel::Hunk,
             file_path,
             location: CommentLocation::Hunk {
@@ -134,19 +142,42 @@ impl Comment {
             },
             text,
             created_at: Local::now(),
-        })
+        }
+        .with_id())
     }
 
     /// Create a new file-level comment
     pub fn new_file(file_path: String, text: String) -> Result<Self> {
         validate_file_path(&file_path).context("Invalid file path for file comment")?;
         Ok(Self {
+            id: String::new(),
             level: CommentLevel::File,
             file_path,
             location: CommentLocation::File,
             text,
             created_at: Local::now(),
-        })
+        }
+        .with_id())
+    }
+
+    /// Fill in the id from the anchor, text and creation time
+    fn with_id(mut self) -> Self {
+        self.id = self.derive_id();
+        self
+    }
+
+    /// Short hash of the fields that identify a comment when it is created
+    fn derive_id(&self) -> String {
+        let location = serde_json::to_string(&self.location).unwrap_or_default();
+        let key = format!(
+            "{}\0{location}\0{}\0{}",
+            self.file_path,
+            self.text,
+            self.created_at.to_rfc3339()
+        );
+        git2::Oid::hash_object(git2::ObjectType::Blob, key.as_bytes())
+            .map(|oid| format!("{oid:.8}"))
+            .unwrap_or_default()
     }
 
     /// Check if this comment matches a specific line location
@@ -193,6 +224,73 @@ impl Comment {
     }
 }
 
+/// A file the reviewer has marked as viewed in a commit
+///
+/// The blob id pins the mark to the exact file content that was reviewed, so
+/// the mark no longer applies once the file changes (e.g. after a rebase).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ViewedFile {
+    /// Path to the file that was viewed
+    pub file_path: String,
+    /// Blob id of the file content at the time it was marked
+    pub blob_id: String,
+    /// When the file was marked as viewed
+    pub marked_at: DateTime<Local>,
+}
+
+/// Overall review outcome for a commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerdictKind {
+    /// The commit is good to go
+    Approve,
+    /// The commit needs changes before it can be accepted
+    RequestChanges,
+    /// Reviewed with feedback but no explicit approval
+    Comment,
+}
+
+impl VerdictKind {
+    /// Human readable label for display and export
+    pub fn label(&self) -> &'static str {
+        match self {
+            VerdictKind::Approve => "Approved",
+            VerdictKind::RequestChanges => "Changes requested",
+            VerdictKind::Comment => "Commented",
+        }
+    }
+
+    /// Short badge shown next to the commit in the log pane
+    pub fn badge(&self) -> &'static str {
+        match self {
+            VerdictKind::Approve => "✓",
+            VerdictKind::RequestChanges => "✗",
+            VerdictKind::Comment => "●",
+        }
+    }
+
+    /// Cycle to the next verdict kind (used by the verdict dialog)
+    pub fn next(&self) -> Self {
+        match self {
+            VerdictKind::Approve => VerdictKind::RequestChanges,
+            VerdictKind::RequestChanges => VerdictKind::Comment,
+            VerdictKind::Comment => VerdictKind::Approve,
+        }
+    }
+}
+
+/// Reviewer's verdict on a whole commit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Verdict {
+    /// The review outcome
+    pub kind: VerdictKind,
+    /// Optional summary explaining the verdict
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// When the verdict was recorded
+    pub created_at: DateTime<Local>,
+}
+
 /// Default schema version for CommitComments serialization
 fn default_schema_version() -> u32 {
     1
@@ -212,6 +310,12 @@ pub struct CommitComments {
     pub timestamp: DateTime<Local>,
     /// All comments for this commit
     pub comments: Vec<Comment>,
+    /// Files marked as viewed in this commit
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub viewed: Vec<ViewedFile>,
+    /// Overall verdict for this commit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verdict: Option<Verdict>,
 }
 
 #[allow(dead_code)] // Used in Phase 2 (TUI integration)
@@ -224,6 +328,8 @@ impl CommitComments {
             branch,
             timestamp: Local::now(),
             comments: Vec::new(),
+            viewed: Vec::new(),
+            verdict: None,
         }
     }
 
@@ -280,6 +386,74 @@ impl CommitComments {
             .collect()
     }
 
+    /// Check if a file is marked as viewed at the given blob id
+    ///
+    /// A mark recorded against a different blob is stale and does not count.
+    pub fn is_viewed(&self, file_path: &str, blob_id: &str) -> bool {
+        self.viewed
+            .iter()
+            .any(|v| v.file_path == file_path && v.blob_id == blob_id)
+    }
+
+    /// Toggle the viewed mark for a file, returning the new state
+    ///
+    /// Any stale mark for the same path is replaced.
+    pub fn toggle_viewed(&mut self, file_path: &str, blob_id: &str) -> bool {
+        let was_viewed = self.is_viewed(file_path, blob_id);
+        self.viewed.retain(|v| v.file_path != file_path);
+        if !was_viewed {
+            self.viewed.push(ViewedFile {
+                file_path: file_path.to_string(),
+                blob_id: blob_id.to_string(),
+                marked_at: Local::now(),
+            });
+        }
+        self.timestamp = Local::now();
+        !was_viewed
+    }
+
+    /// Record a verdict for this commit, replacing any previous one
+    ///
+    /// Blank summaries are stored as no summary.
+    pub fn set_verdict(&mut self, kind: VerdictKind, summary: &str) {
+        let summary = summary.trim();
+        self.verdict = Some(Verdict {
+            kind,
+            summary: (!summary.is_empty()).then(|| summary.to_string()),
+            created_at: Local::now(),
+        });
+        self.timestamp = Local::now();
+    }
+
+    /// Remove the verdict for this commit
+    pub fn clear_verdict(&mut self) -> Option<Verdict> {
+        self.timestamp = Local::now();
+        self.verdict.take()
+    }
+
+    /// Check if this commit has been approved
+    pub fn is_approved(&self) -> bool {
+        self.verdict
+            .as_ref()
+            .is_some_and(|v| v.kind == VerdictKind::Approve)
+    }
+
+    /// Check if any review state besides comments (viewed marks, verdict) is stored
+    pub fn has_review_marks(&self) -> bool {
+        !self.viewed.is_empty() || self.verdict.is_some()
+    }
+
+    /// Find a comment by id
+    pub fn comment_mut(&mut self, id: &str) -> Option<&mut Comment> {
+        self.comments.iter_mut().find(|c| c.id == id)
+    }
+
+    /// Remove a comment by id
+    pub fn remove_comment_by_id(&mut self, id: &str) -> Option<Comment> {
+        let index = self.comments.iter().position(|c| c.id == id)?;
+        self.remove_comment(index)
+    }
+
     /// Serialize to JSON string for storage
     pub fn to_json(&self) -> anyhow::Result<String> {
         Ok(serde_json::to_string_pretty(self)?)
@@ -287,7 +461,14 @@ impl CommitComments {
 
     /// Deserialize from JSON string
     pub fn from_json(json: &str) -> anyhow::Result<Self> {
-        Ok(serde_json::from_str(json)?)
+        let mut commit_comments: Self = serde_json::from_str(json)?;
+        // Comments stored before ids existed get the same id on every load
+        for comment in commit_comments.comments.iter_mut() {
+            if comment.id.is_empty() {
+                comment.id = comment.derive_id();
+            }
+        }
+        Ok(commit_comments)
     }
 
     /// Check if there are any comments
@@ -394,4 +575,97 @@ mod tests {
         let file_comments = commit_comments.file_level_comments("src/main.rs");
         assert_eq!(file_comments.len(), 1);
     }
+
+    #[test]
+    fn test_viewed_marks() {
+        let mut commit_comments = CommitComments::new("abc123".to_string(), "main".to_string());
+
+        assert!(commit_comments.toggle_viewed("src/main.rs", "blob1"));
+        assert!(commit_comments.is_viewed("src/main.rs", "blob1"));
+        assert!(commit_comments.has_review_marks());
+        assert!(commit_comments.is_empty());
+
+        // A changed blob invalidates the mark
+        assert!(!commit_comments.is_viewed("src/main.rs", "blob2"));
+
+        // Re-marking at the new blob replaces the stale entry
+        assert!(commit_comments.toggle_viewed("src/main.rs", "blob2"));
+        assert_eq!(commit_comments.viewed.len(), 1);
+
+        assert!(!commit_comments.toggle_viewed("src/main.rs", "blob2"));
+        assert!(!commit_comments.has_review_marks());
+    }
+
+    #[test]
+    fn test_verdict() {
+        let mut commit_comments = CommitComments::new("abc123".to_string(), "main".to_string());
+        assert!(!commit_comments.is_approved());
+
+        commit_comments.set_verdict(VerdictKind::RequestChanges, "  ");
+        assert!(!commit_comments.is_approved());
+        assert_eq!(commit_comments.verdict.as_ref().unwrap().summary, None);
+
+        commit_comments.set_verdict(VerdictKind::Approve, "Looks good");
+        assert!(commit_comments.is_approved());
+        assert!(commit_comments.has_review_marks());
+
+        let json = commit_comments.to_json().unwrap();
+        assert!(json.contains("\"approve\""));
+        let deserialized = CommitComments::from_json(&json).unwrap();
+        assert_eq!(
+            deserialized.verdict.unwrap().summary.as_deref(),
+            Some("Looks good")
+        );
+
+        commit_comments.clear_verdict();
+        assert!(!commit_comments.has_review_marks());
+    }
+
+    #[test]
+    fn test_viewed_marks_backward_compatible() {
+        let json = r#"{
+            "commit_id": "abc123",
+            "branch": "main",
+            "timestamp": "2025-01-01T00:00:00+00:00",
+            "comments": []
+        }"#;
+        let commit_comments = CommitComments::from_json(json).unwrap();
+        assert!(commit_comments.viewed.is_empty());
+        assert!(commit_comments.verdict.is_none());
+    }
+
+    #[test]
+    fn test_comment_ids() {
+        let mut commit_comments = CommitComments::new("abc123".to_string(), "main".to_string());
+        let first = Comment::new_file("a.rs".to_string(), "One".to_string()).unwrap();
+        let second = Comment::new_file("a.rs".to_string(), "Two".to_string()).unwrap();
+        assert_eq!(first.id.len(), 8);
+        assert_ne!(first.id, second.id);
+        let (first_id, second_id) = (first.id.clone(), second.id.clone());
+        commit_comments.add_comment(first);
+        commit_comments.add_comment(second);
+
+        commit_comments.comment_mut(&second_id).unwrap().text = "Edited".to_string();
+        assert_eq!(
+            commit_comments
+                .remove_comment_by_id(&first_id)
+                .unwrap()
+                .text,
+            "One"
+        );
+        assert!(commit_comments.remove_comment_by_id(&first_id).is_none());
+
+        // Ids survive storage, and comments stored without one get a stable id
+        let json = commit_comments.to_json().unwrap();
+        let loaded = CommitComments::from_json(&json).unwrap();
+        assert_eq!(loaded.comments[0].id, second_id);
+        assert_eq!(loaded.comments[0].text, "Edited");
+
+        let legacy = json.replace(&format!("\"id\": \"{second_id}\","), "");
+        assert!(!legacy.contains(&second_id));
+        let first_load = CommitComments::from_json(&legacy).unwrap();
+        let second_load = CommitComments::from_json(&legacy).unwrap();
+        assert_eq!(first_load.comments[0].id.len(), 8);
+        assert_eq!(first_load.comments[0].id, second_load.comments[0].id);
+    }
 }
@@ -1,7 +1,9 @@
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+
+use crate::git::WhitespaceMode;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -28,6 +30,17 @@ pub struct DisplayConfig {
 
     #[serde(default = "default_syntax_theme")]
     pub syntax_theme: String,
+
+    #[serde(default)]
+    pub whitespace_mode: WhitespaceMode,
+
+    #[serde(default)]
+    pub highlight_whitespace_errors: bool,
+
+    /// Encoding for non-UTF-8 files without a `working-tree-encoding`
+    /// attribute, e.g. "windows-1252" or "shift_jis"
+    #[serde(default)]
+    pub fallback_encoding: Option<String>,
 }
 
 fn default_context_lines() -> u32 {
@@ -54,6 +67,9 @@ impl Default for DisplayConfig {
             context_expand_increment: default_context_expand_increment(),
             horizontal_scroll_amount: default_horizontal_scroll_amount(),
             syntax_theme: default_syntax_theme(),
+            whitespace_mode: WhitespaceMode::default(),
+            highlight_whitespace_errors: false,
+            fallback_encoding: None,
         }
     }
 }
@@ -65,6 +81,9 @@ pub struct UiConfig {
 
     #[serde(default = "default_show_line_numbers")]
     pub show_line_numbers: bool,
+
+    #[serde(default)]
+    pub skip_viewed_files: bool,
 }
 
 fn default_log_pane_width_ratio() -> f32 {
@@ -80,6 +99,45 @@ impl Default for UiConfig {
         Self {
             log_pane_width_ratio: default_log_pane_width_ratio(),
             show_line_numbers: default_show_line_numbers(),
+            skip_viewed_files: false,
+        }
+    }
+}
+
+/// Paths left out of every review
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FilterConfig {
+    /// Pathspec globs to exclude, e.g. `["Cargo.lock", "vendor/**"]`
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    /// Hide commits touching only excluded files instead of dimming them
+    #[serde(default)]
+    pub hide_excluded_commits: bool,
+}
+
+/// How yanked text reaches the system clipboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipboardConfig {
+    /// Send the OSC 52 escape sequence (works over SSH and in tmux)
+    #[serde(default = "default_osc52")]
+    pub osc52: bool,
+
+    /// Command fed the text on stdin as well, e.g. "wl-copy" or
+    /// "xclip -selection clipboard"
+    #[serde(default)]
+    pub command: Option<String>,
+}
+
+fn default_osc52() -> bool {
+    true
+}
+
+impl Default for ClipboardConfig {
+    fn default() -> Self {
+        Self {
+            osc52: default_osc52(),
+            command: None,
         }
     }
 }
@@ -91,6 +149,12 @@ pub struct Config {
 
     #[serde(default)]
     pub ui: UiConfig,
+
+    #[serde(default)]
+    pub filter: FilterConfig,
+
+    #[serde(default)]
+    pub clipboard: ClipboardConfig,
 }
 
 impl Config {
@@ -105,13 +169,15 @@ impl Config {
 
     /// Load config from file, or return default if file doesn't exist
     pub fn load_or_default() -> Result<Self> {
-        let path = Self::config_path()?;
+        Self::load_from(&Self::config_path()?)
+    }
 
+    fn load_from(path: &Path) -> Result<Self> {
         if !path.exists() {
             return Ok(Self::default());
         }
 
-        let contents = fs::read_to_string(&path)
+        let contents = fs::read_to_string(path)
             .context(format!("Failed to read config file: {}", path.display()))?;
 
         let config: Config = toml::from_str(&contents)
@@ -122,8 +188,10 @@ impl Config {
 
     /// Save config to file
     pub fn save(&self) -> Result<()> {
-        let path = Self::config_path()?;
+        self.save_to(&Self::config_path()?)
+    }
 
+    fn save_to(&self, path: &Path) -> Result<()> {
         // Create config directory if it doesn't exist
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent).context(format!(
@@ -134,11 +202,25 @@ impl Config {
 
         let contents = toml::to_string_pretty(self).context("Failed to serialize config")?;
 
-        fs::write(&path, contents)
+        fs::write(path, contents)
             .context(format!("Failed to write config file: {}", path.display()))?;
 
         Ok(())
     }
+
+    /// Change settings in the config file, leaving the rest of it as it is
+    ///
+    /// Unlike `save`, this doesn't write out command-line overrides such as
+    /// `--context` that only live in the in-memory config.
+    pub fn update_file(change: impl FnOnce(&mut Config)) -> Result<()> {
+        Self::update_file_at(&Self::config_path()?, change)
+    }
+
+    fn update_file_at(path: &Path, change: impl FnOnce(&mut Config)) -> Result<()> {
+        let mut config = Self::load_from(path)?;
+        change(&mut config);
+        config.save_to(path)
+    }
 }
 
 #[cfg(test)]
@@ -153,6 +235,69 @@ mod tests {
         assert_eq!(config.display.context_expand_increment, 8);
         assert_eq!(config.ui.log_pane_width_ratio, 0.35);
         assert!(config.ui.show_line_numbers);
+        assert!(!config.ui.skip_viewed_files);
+        assert_eq!(config.display.whitespace_mode, WhitespaceMode::ShowAll);
+        assert!(!config.display.highlight_whitespace_errors);
+        assert!(config.filter.exclude.is_empty());
+        assert!(!config.filter.hide_excluded_commits);
+    }
+
+    #[test]
+    fn test_filter_config_from_toml() {
+        let config: Config =
+            toml::from_str("[filter]\nexclude = [\"Cargo.lock\", \"vendor/**\"]\n").unwrap();
+        assert_eq!(config.filter.exclude, vec!["Cargo.lock", "vendor/**"]);
+        assert!(!config.filter.hide_excluded_commits);
+        assert!(config.clipboard.osc52);
+        assert_eq!(config.clipboard.command, None);
+    }
+
+    #[test]
+    fn test_clipboard_config_from_toml() {
+        let config: Config =
+            toml::from_str("[clipboard]\ncommand = \"xclip -selection clipboard\"\n").unwrap();
+        assert!(config.clipboard.osc52);
+        assert_eq!(
+            config.clipboard.command.as_deref(),
+            Some("xclip -selection clipboard")
+        );
+    }
+
+    #[test]
+    fn test_whitespace_mode_from_toml() {
+        let config: Config = toml::from_str(
+            "[display]\nwhitespace_mode = \"ignore-change\"\nhighlight_whitespace_errors = true\n",
+        )
+        .unwrap();
+        assert_eq!(config.display.whitespace_mode, WhitespaceMode::IgnoreChange);
+        assert!(config.display.highlight_whitespace_errors);
+        assert_eq!(config.display.fallback_encoding, None);
+
+        let config: Config =
+            toml::from_str("[display]\nfallback_encoding = \"shift_jis\"\n").unwrap();
+        assert_eq!(
+            config.display.fallback_encoding.as_deref(),
+            Some("shift_jis")
+        );
+    }
+
+    #[test]
+    fn test_update_file_leaves_overrides_out() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("git-review").join("config.toml");
+
+        // Started with --context 2, then the log pane was resized
+        let mut config = Config::default();
+        config.display.context_lines = 2;
+        config.ui.log_pane_width_ratio = 0.25;
+        Config::update_file_at(&path, |saved| {
+            saved.ui.log_pane_width_ratio = config.ui.log_pane_width_ratio;
+        })
+        .unwrap();
+
+        let saved = Config::load_from(&path).unwrap();
+        assert_eq!(saved.ui.log_pane_width_ratio, 0.25);
+        assert_eq!(saved.display.context_lines, 8);
     }
 
     #[test]
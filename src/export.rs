@@ -1,8 +1,12 @@
 use anyhow::Result;
 use chrono::Local;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
-use crate::comments::{Comment, CommentLevel, CommitComments};
+use crate::comments::{Comment, CommentLevel, CommentLocation, CommitComments, VerdictKind};
+use crate::git::diff_parser::{parse_diff, parse_hunk_header};
+use crate::git::mbox::PatchMessage;
+use crate::git::LineType;
 
 /// Format for exporting comments
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -25,9 +29,19 @@ struct ExportCommit {
     id: String,
     message: Option<String>,
     timestamp: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    verdict: Option<ExportVerdict>,
     files: Vec<ExportFile>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportVerdict {
+    kind: VerdictKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: Option<String>,
+    created_at: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct ExportFile {
     path: String,
@@ -36,6 +50,7 @@ struct ExportFile {
 
 #[derive(Debug, Serialize, Deserialize)]
 struct ExportComment {
+    id: String,
     level: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     line: Option<usize>,
@@ -47,6 +62,58 @@ struct ExportComment {
     created_at: String,
 }
 
+/// Entry of the flat comment list printed by `comment list`
+#[derive(Debug, Serialize, Deserialize)]
+struct ListedComment {
+    commit: String,
+    file: String,
+    #[serde(flatten)]
+    comment: ExportComment,
+}
+
+/// Convert a comment to its export form
+fn export_comment(comment: &Comment) -> ExportComment {
+    let (level, line, line_type, hunk_header) = match &comment.location {
+        crate::comments::CommentLocation::Line { number, kind } => {
+            let kind_str = match kind {
+                crate::git::LineType::Added => "added",
+                crate::git::LineType::Removed => "removed",
+                crate::git::LineType::Context => "context",
+            };
+            ("line", Some(*number), Some(kind_str.to_string()), None)
+        }
+        crate::comments::CommentLocation::Hunk { header } => {
+            ("hunk", None, None, Some(header.clone()))
+        }
+        crate::comments::CommentLocation::File => ("file", None, None, None),
+    };
+
+    ExportComment {
+        id: comment.id.clone(),
+        level: level.to_string(),
+        line,
+        line_type,
+        hunk_header,
+        text: comment.text.clone(),
+        created_at: comment.created_at.to_rfc3339(),
+    }
+}
+
+/// List every comment as a flat JSON array, each with its commit and file
+pub fn to_comment_list_json(comments_list: &[CommitComments]) -> Result<String> {
+    let listed: Vec<ListedComment> = comments_list
+        .iter()
+        .flat_map(|cc| {
+            cc.comments.iter().map(|comment| ListedComment {
+                commit: cc.commit_id.clone(),
+                file: comment.file_path.clone(),
+                comment: export_comment(comment),
+            })
+        })
+        .collect();
+    Ok(serde_json::to_string_pretty(&listed)?)
+}
+
 /// Export all comments for a branch to markdown format
 pub fn to_markdown(comments_list: &[CommitComments], branch: &str) -> Result<String> {
     let mut output = String::new();
@@ -83,6 +150,21 @@ pub fn to_markdown(comments_list: &[CommitComments], branch: &str) -> Result<Str
             commit_comments.timestamp.format("%Y-%m-%d %H:%M:%S")
         ));
 
+        // Verdict summary at the top of the commit section
+        if let Some(verdict) = &commit_comments.verdict {
+            output.push_str(&format!(
+                "**Verdict:** {} {}\n\n",
+                verdict.kind.badge(),
+                verdict.kind.label()
+            ));
+            if let Some(summary) = &verdict.summary {
+                for line in summary.lines() {
+                    output.push_str(&format!("> {line}\n"));
+                }
+                output.push('\n');
+            }
+        }
+
         // Group comments by file
         let mut files_map: std::collections::HashMap<String, Vec<&Comment>> =
             std::collections::HashMap::new();
@@ -169,33 +251,7 @@ pub fn to_json(comments_list: &[CommitComments]) -> Result<String> {
 
         let mut export_files = Vec::new();
         for (file_path, file_comments) in files_map {
-            let mut export_comments = Vec::new();
-
-            for comment in file_comments {
-                let (level, line, line_type, hunk_header) = match &comment.location {
-                    crate::comments::CommentLocation::Line { number, kind } => {
-                        let kind_str = match kind {
-                            crate::git::LineType::Added => "added",
-                            crate::git::LineType::Removed => "removed",
-                            crate::git::LineType::Context => "context",
-                        };
-                        ("line", Some(*number), Some(kind_str.to_string()), None)
-                    }
-                    crate::comments::CommentLocation::Hunk { header } => {
-                        ("hunk", None, None, Some(header.clone()))
-                    }
-                    crate::comments::CommentLocation::File => ("file", None, None, None),
-                };
-
-                export_comments.push(ExportComment {
-                    level: level.to_string(),
-                    line,
-                    line_type,
-                    hunk_header,
-                    text: comment.text.clone(),
-                    created_at: comment.created_at.to_rfc3339(),
-                });
-            }
+            let export_comments = file_comments.into_iter().map(export_comment).collect();
 
             export_files.push(ExportFile {
                 path: file_path,
@@ -210,6 +266,11 @@ pub fn to_json(comments_list: &[CommitComments]) -> Result<String> {
             id: commit_comments.commit_id.clone(),
             message: None, // Could be filled in if we have access to commit info
             timestamp: commit_comments.timestamp.to_rfc3339(),
+            verdict: commit_comments.verdict.as_ref().map(|v| ExportVerdict {
+                kind: v.kind,
+                summary: v.summary.clone(),
+                created_at: v.created_at.to_rfc3339(),
+            }),
             files: export_files,
         });
     }
@@ -226,13 +287,148 @@ pub fn to_json(comments_list: &[CommitComments]) -> Result<String> {
 /// Export a single commit's comments to markdown
 #[allow(dead_code)] // Used in Phase 2 (TUI integration)
 pub fn commit_to_markdown(commit_comments: &CommitComments) -> Result<String> {
-    to_markdown(&[commit_comments.clone()], &commit_comments.branch)
+    to_markdown(
+        std::slice::from_ref(commit_comments),
+        &commit_comments.branch,
+    )
 }
 
 /// Export a single commit's comments to JSON
 #[allow(dead_code)] // Used in Phase 2 (TUI integration)
 pub fn commit_to_json(commit_comments: &CommitComments) -> Result<String> {
-    to_json(&[commit_comments.clone()])
+    to_json(std::slice::from_ref(commit_comments))
+}
+
+/// Write comments on mailed patches as reply emails, one per patch
+///
+/// Each reply quotes its patch with the comments placed below the lines,
+/// hunks and files they are about. Comments on context that isn't in the
+/// patch follow the quote. The result is an mbox for a mail client or
+/// `git send-email`; patches without comments or a verdict are skipped.
+pub fn to_mail_reply(
+    patches: &[(&PatchMessage, Option<&CommitComments>)],
+    reviewer: Option<&str>,
+) -> Result<String> {
+    let mut output = String::new();
+
+    for &(patch, comments) in patches {
+        let Some(comments) = comments.filter(|cc| !cc.is_empty() || cc.verdict.is_some()) else {
+            continue;
+        };
+        let mut placed = HashSet::new();
+        let mut body = String::new();
+
+        if let Some(summary) = comments.verdict.as_ref().and_then(|v| v.summary.as_ref()) {
+            body.push_str(&format!("{summary}\n\n"));
+        }
+        if patch.author.time.timestamp() != 0 {
+            body.push_str(&format!("On {}, ", patch.author.time.to_rfc2822()));
+        }
+        body.push_str(&format!("{} wrote:\n", patch.author.name));
+        for line in patch.message.lines() {
+            body.push_str(&quote(line));
+        }
+        body.push_str(">\n");
+
+        for file in parse_diff(&patch.diff)? {
+            let path = file.new_path.as_str();
+            body.push_str(&quote(&format!("--- a/{}", file.old_path)));
+            body.push_str(&quote(&format!("+++ b/{path}")));
+            write_comments(&mut body, &mut placed, comments.file_level_comments(path));
+
+            for hunk in &file.hunks {
+                body.push_str(&quote(&hunk.header));
+                let ranges = parse_hunk_header(&hunk.header);
+                let hunk_comments = comments
+                    .comments
+                    .iter()
+                    .filter(|c| match &c.location {
+                        CommentLocation::Hunk { header } => {
+                            c.file_path == path && parse_hunk_header(header) == ranges
+                        }
+                        _ => false,
+                    })
+                    .collect();
+                write_comments(&mut body, &mut placed, hunk_comments);
+
+                for line in &hunk.lines {
+                    let prefix = match line.line_type {
+                        LineType::Added => '+',
+                        LineType::Removed => '-',
+                        LineType::Context => ' ',
+                    };
+                    body.push_str(&quote(&format!("{prefix}{}", line.content)));
+                    if let Some(number) = line.comment_line_number() {
+                        write_comments(
+                            &mut body,
+                            &mut placed,
+                            comments.comments_at_line(path, number, line.line_type),
+                        );
+                    }
+                }
+            }
+        }
+
+        let unplaced: Vec<&Comment> = comments
+            .comments
+            .iter()
+            .filter(|c| !placed.contains(&c.id))
+            .collect();
+        for comment in unplaced {
+            body.push_str(&format!(
+                "\n{} ({}):\n{}\n",
+                comment.file_path,
+                comment.location_desc(),
+                comment.text
+            ));
+        }
+        if let (Some(reviewer), Some(VerdictKind::Approve)) =
+            (reviewer, comments.verdict.as_ref().map(|v| v.kind))
+        {
+            body.push_str(&format!("\nReviewed-by: {reviewer}\n"));
+        }
+
+        output.push_str("From git-review Mon Sep 17 00:00:00 2001\n");
+        if let Some(reviewer) = reviewer {
+            output.push_str(&format!("From: {reviewer}\n"));
+        }
+        output.push_str(&format!("To: {}\n", patch.author.display()));
+        let subject = &patch.subject;
+        if subject
+            .get(..3)
+            .is_some_and(|re| re.eq_ignore_ascii_case("re:"))
+        {
+            output.push_str(&format!("Subject: {subject}\n"));
+        } else {
+            output.push_str(&format!("Subject: Re: {subject}\n"));
+        }
+        if let Some(id) = &patch.message_id {
+            output.push_str(&format!("In-Reply-To: {id}\nReferences: {id}\n"));
+        }
+        output.push('\n');
+        output.push_str(&body);
+        output.push('\n');
+    }
+
+    Ok(output)
+}
+
+/// Write comments below a quoted line, each only once
+fn write_comments(body: &mut String, placed: &mut HashSet<String>, comments: Vec<&Comment>) {
+    for comment in comments {
+        if placed.insert(comment.id.clone()) {
+            body.push_str(&format!("\n{}\n\n", comment.text));
+        }
+    }
+}
+
+/// A line of the quoted patch in a reply
+fn quote(line: &str) -> String {
+    if line.is_empty() {
+        ">\n".to_string()
+    } else {
+        format!("> {line}\n")
+    }
 }
 
 #[cfg(test)]
@@ -279,6 +475,22 @@ mod tests {
         assert!(result.contains("Consider refactoring"));
     }
 
+    #[test]
+    fn test_export_includes_verdict() {
+        let mut commit_comments = CommitComments::new("abc123".to_string(), "main".to_string());
+        commit_comments.set_verdict(VerdictKind::RequestChanges, "Needs tests");
+
+        let markdown = to_markdown(std::slice::from_ref(&commit_comments), "main").unwrap();
+        assert!(markdown.contains("**Verdict:** ✗ Changes requested"));
+        assert!(markdown.contains("> Needs tests"));
+
+        let json = to_json(&[commit_comments]).unwrap();
+        let parsed: ExportData = serde_json::from_str(&json).unwrap();
+        let verdict = parsed.commits[0].verdict.as_ref().unwrap();
+        assert_eq!(verdict.kind, VerdictKind::RequestChanges);
+        assert_eq!(verdict.summary.as_deref(), Some("Needs tests"));
+    }
+
     #[test]
     fn test_json_export_empty() {
         let result = to_json(&[]).unwrap();
@@ -311,4 +523,50 @@ mod tests {
         assert_eq!(parsed.commits[0].files[0].comments.len(), 1);
         assert_eq!(parsed.commits[0].files[0].comments[0].text, "Test comment");
     }
+
+    #[test]
+    fn test_mail_reply_places_comments() {
+        let patch = PatchMessage {
+            subject: "[PATCH] Fix greeting".to_string(),
+            message_id: Some("<1@example.com>".to_string()),
+            author: crate::git::Person {
+                name: "Jane".to_string(),
+                email: "jane@example.com".to_string(),
+                ..Default::default()
+            },
+            message: "Fix greeting".to_string(),
+            diff: "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n@@ -1,2 +1,2 @@\n-hi\n+hello\n world\n".to_string(),
+            base_commit: None,
+        };
+        let mut comments = CommitComments::new("abc123".to_string(), String::new());
+        comments.add_comment(
+            Comment::new_line(
+                "a.txt".to_string(),
+                1,
+                LineType::Added,
+                "Capitalise?".to_string(),
+            )
+            .unwrap(),
+        );
+        comments.add_comment(
+            Comment::new_line(
+                "a.txt".to_string(),
+                40,
+                LineType::Context,
+                "Outside the patch".to_string(),
+            )
+            .unwrap(),
+        );
+        comments.set_verdict(VerdictKind::Approve, "");
+
+        let reply =
+            to_mail_reply(&[(&patch, Some(&comments))], Some("Rev <rev@example.com>")).unwrap();
+        assert!(reply.contains("Subject: Re: [PATCH] Fix greeting\nIn-Reply-To: <1@example.com>\n"));
+        assert!(reply.contains("> -hi\n> +hello\n\nCapitalise?\n\n>  world\n"));
+        assert!(reply.contains("a.txt (Line 40 (context)):\nOutside the patch\n"));
+        assert!(reply.contains("\nReviewed-by: Rev <rev@example.com>\n"));
+
+        // Nothing to say about a patch without comments
+        assert_eq!(to_mail_reply(&[(&patch, None)], None).unwrap(), "");
+    }
 }
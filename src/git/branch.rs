@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use git2::Repository;
 
 /// Detect the base branch for comparison
@@ -42,6 +42,25 @@ pub fn detect_base_branch(repo: &Repository) -> Result<String> {
     )
 }
 
+/// Get the current branch name
+///
+/// A detached HEAD is named after its commit, e.g. `detached-1a2b3c4`.
+pub fn current_branch(repo: &Repository) -> Result<String> {
+    let head = repo.head().context("Failed to get HEAD reference")?;
+
+    if let Some(branch_name) = head.shorthand() {
+        // Remove "refs/heads/" prefix if present
+        let name = branch_name
+            .strip_prefix("refs/heads/")
+            .unwrap_or(branch_name);
+        Ok(name.to_string())
+    } else {
+        // Detached HEAD state - use commit SHA
+        let oid = head.target().context("HEAD has no target")?;
+        Ok(format!("detached-{}", &oid.to_string()[..7]))
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -1,18 +1,141 @@
 use anyhow::{Context, Result};
-use git2::{Oid, Repository};
+use chrono::{DateTime, FixedOffset};
+use git2::{Commit, Oid, Repository};
 
 #[derive(Debug, Clone)]
 pub struct CommitInfo {
     pub id: Oid,
     pub short_id: String,
     pub message: String,
+    pub author: Person,
+    pub committer: Person,
+    pub parent_ids: Vec<Oid>,
+    pub trailers: Vec<Trailer>,
+    pub graph: String, // Graph lanes for the log pane (empty unless requested)
+}
+
+/// Author or committer identity with timestamp
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Person {
+    pub name: String,
+    pub email: String,
+    pub time: DateTime<FixedOffset>,
+}
+
+/// A `Key: value` trailer from the end of a commit message
+/// (Signed-off-by, Fixes, Co-authored-by, ...)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trailer {
+    pub key: String,
+    pub value: String,
 }
 
 impl CommitInfo {
+    /// Build from a git2 commit
+    pub fn from_commit(commit: &Commit) -> Self {
+        let oid = commit.id();
+        let message = commit
+            .message()
+            .unwrap_or("<no message>")
+            .trim()
+            .to_string();
+
+        Self {
+            id: oid,
+            short_id: format!("{oid:.7}"),
+            trailers: parse_trailers(&message),
+            message,
+            author: Person::from_signature(&commit.author()),
+            committer: Person::from_signature(&commit.committer()),
+            parent_ids: commit.parent_ids().collect(),
+            graph: String::new(),
+        }
+    }
+
     /// Get the first line of the commit message
     pub fn summary(&self) -> &str {
         self.message.lines().next().unwrap_or("")
     }
+
+    /// Whether this is a merge commit (more than one parent)
+    pub fn is_merge(&self) -> bool {
+        self.parent_ids.len() > 1
+    }
+
+    /// Whether committer differs from author (rebased, applied or cherry-picked)
+    pub fn committed_by_other(&self) -> bool {
+        self.author != self.committer
+    }
+}
+
+impl Person {
+    pub(crate) fn from_signature(sig: &git2::Signature) -> Self {
+        let when = sig.when();
+        let offset = FixedOffset::east_opt(when.offset_minutes() * 60)
+            .unwrap_or_else(|| FixedOffset::east_opt(0).unwrap());
+        let time = DateTime::from_timestamp(when.seconds(), 0)
+            .unwrap_or_default()
+            .with_timezone(&offset);
+
+        Self {
+            name: String::from_utf8_lossy(sig.name_bytes()).into_owned(),
+            email: String::from_utf8_lossy(sig.email_bytes()).into_owned(),
+            time,
+        }
+    }
+
+    /// Format as `Name <email>`
+    pub fn display(&self) -> String {
+        format!("{} <{}>", self.name, self.email)
+    }
+}
+
+/// Parse a single trailer line, returning None if it is not `Token: value`
+fn parse_trailer_line(line: &str) -> Option<Trailer> {
+    let (key, value) = line.split_once(':')?;
+    let valid_key = !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if !valid_key || !value.starts_with(' ') {
+        return None;
+    }
+    Some(Trailer {
+        key: key.to_string(),
+        value: value.trim().to_string(),
+    })
+}
+
+/// Parse trailers from the last paragraph of a commit message
+///
+/// Like `git interpret-trailers`, the trailer block is the final paragraph
+/// (never the subject) and every line in it must be a trailer or an
+/// indented continuation of the previous one.
+pub fn parse_trailers(message: &str) -> Vec<Trailer> {
+    let paragraphs: Vec<&str> = message
+        .trim()
+        .split("\n\n")
+        .filter(|p| !p.trim().is_empty())
+        .collect();
+    if paragraphs.len() < 2 {
+        return Vec::new();
+    }
+
+    let mut trailers: Vec<Trailer> = Vec::new();
+    for line in paragraphs[paragraphs.len() - 1].lines() {
+        if line.starts_with(char::is_whitespace) {
+            match trailers.last_mut() {
+                Some(last) => {
+                    last.value.push(' ');
+                    last.value.push_str(line.trim());
+                }
+                None => return Vec::new(),
+            }
+        } else {
+            match parse_trailer_line(line) {
+                Some(trailer) => trailers.push(trailer),
+                None => return Vec::new(),
+            }
+        }
+    }
+    trailers
 }
 
 /// Parse a git range string into start and end refs
@@ -43,6 +166,17 @@ pub fn parse_range(range: &str) -> Result<(String, String)> {
     }
 }
 
+/// Options controlling which commits the log walk visits
+#[derive(Debug, Clone, Default)]
+pub struct LogOptions {
+    /// Follow only the first parent of merges, like `git log --first-parent`
+    pub first_parent: bool,
+    /// Never list a commit before all of its children, like `git log --topo-order`
+    pub topo_order: bool,
+    /// Compute the commit graph column shown in the log pane
+    pub graph: bool,
+}
+
 /// Get commit log between two arbitrary refs
 ///
 /// Returns commits in reverse chronological order (newest first)
@@ -50,6 +184,7 @@ pub fn get_commit_log_range(
     repo: &Repository,
     start_ref: &str,
     end_ref: &str,
+    options: &LogOptions,
 ) -> Result<Vec<CommitInfo>> {
     let start_obj = repo
         .revparse_single(start_ref)
@@ -69,25 +204,25 @@ pub fn get_commit_log_range(
     let mut revwalk = repo.revwalk()?;
     revwalk.push(end_oid)?;
     revwalk.hide(start_oid)?;
-    revwalk.set_sorting(git2::Sort::TIME)?;
+    if options.topo_order {
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)?;
+    } else {
+        revwalk.set_sorting(git2::Sort::TIME)?;
+    }
+    if options.first_parent {
+        revwalk.simplify_first_parent()?;
+    }
 
     let mut commits = Vec::new();
 
     for oid_result in revwalk {
         let oid = oid_result?;
         let commit = repo.find_commit(oid)?;
+        commits.push(CommitInfo::from_commit(&commit));
+    }
 
-        let commit_info = CommitInfo {
-            id: oid,
-            short_id: format!("{oid:.7}"),
-            message: commit
-                .message()
-                .unwrap_or("<no message>")
-                .trim()
-                .to_string(),
-        };
-
-        commits.push(commit_info);
+    if options.graph {
+        super::graph::assign_graph(&mut commits);
     }
 
     Ok(commits)
@@ -96,36 +231,156 @@ pub fn get_commit_log_range(
 /// Get commit log between HEAD and base branch
 ///
 /// Returns commits in reverse chronological order (newest first)
-pub fn get_commit_log(repo: &Repository, base_branch: &str) -> Result<Vec<CommitInfo>> {
-    get_commit_log_range(repo, base_branch, "HEAD")
+pub fn get_commit_log(
+    repo: &Repository,
+    base_branch: &str,
+    options: &LogOptions,
+) -> Result<Vec<CommitInfo>> {
+    get_commit_log_range(repo, base_branch, "HEAD", options)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_commit_info_summary() {
-        let commit = CommitInfo {
+    fn commit_with_message(message: &str) -> CommitInfo {
+        CommitInfo {
             id: Oid::zero(),
             short_id: "abc123".to_string(),
-            message: "First line\nSecond line\nThird line".to_string(),
-        };
+            message: message.to_string(),
+            author: Person::default(),
+            committer: Person::default(),
+            parent_ids: Vec::new(),
+            trailers: parse_trailers(message),
+            graph: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_commit_info_summary() {
+        let commit = commit_with_message("First line\nSecond line\nThird line");
 
         assert_eq!(commit.summary(), "First line");
     }
 
     #[test]
     fn test_commit_info_summary_single_line() {
-        let commit = CommitInfo {
-            id: Oid::zero(),
-            short_id: "abc123".to_string(),
-            message: "Single line message".to_string(),
-        };
+        let commit = commit_with_message("Single line message");
 
         assert_eq!(commit.summary(), "Single line message");
     }
 
+    #[test]
+    fn test_parse_trailers() {
+        let commit = commit_with_message(
+            "Fix crash on empty diff\n\nBody text: not a trailer.\n\n\
+             Fixes: 1234567 (\"Add diff view\")\n\
+             Co-authored-by: A Dev <a@example.com>\n\
+             Signed-off-by: B Dev\n  <b@example.com>",
+        );
+
+        assert_eq!(
+            commit.trailers,
+            vec![
+                Trailer {
+                    key: "Fixes".to_string(),
+                    value: "1234567 (\"Add diff view\")".to_string(),
+                },
+                Trailer {
+                    key: "Co-authored-by".to_string(),
+                    value: "A Dev <a@example.com>".to_string(),
+                },
+                Trailer {
+                    key: "Signed-off-by".to_string(),
+                    value: "B Dev <b@example.com>".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_trailers_requires_trailer_block() {
+        // Subject alone is never a trailer block
+        assert!(parse_trailers("Fixes: something").is_empty());
+        // Last paragraph mixes prose and trailers
+        assert!(parse_trailers("Subject\n\nSigned-off-by: A\nnot a trailer").is_empty());
+        // Keys cannot contain spaces
+        assert!(parse_trailers("Subject\n\nSee also: docs").is_empty());
+    }
+
+    #[test]
+    fn test_commit_info_from_commit() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let author =
+            git2::Signature::new("Author", "author@example.com", &git2::Time::new(1000, 60))
+                .unwrap();
+        let committer = git2::Signature::new(
+            "Committer",
+            "committer@example.com",
+            &git2::Time::new(2000, 0),
+        )
+        .unwrap();
+        let tree_id = repo.treebuilder(None).unwrap().write().unwrap();
+        let tree = repo.find_tree(tree_id).unwrap();
+        let oid = repo
+            .commit(
+                None,
+                &author,
+                &committer,
+                "Subject\n\nSigned-off-by: Author <author@example.com>\n",
+                &tree,
+                &[],
+            )
+            .unwrap();
+
+        let info = CommitInfo::from_commit(&repo.find_commit(oid).unwrap());
+        assert_eq!(info.author.display(), "Author <author@example.com>");
+        assert_eq!(info.author.time.to_rfc3339(), "1970-01-01T01:16:40+01:00");
+        assert_eq!(info.committer.name, "Committer");
+        assert!(info.committed_by_other());
+        assert!(info.parent_ids.is_empty());
+        assert_eq!(info.trailers.len(), 1);
+        assert_eq!(info.trailers[0].key, "Signed-off-by");
+    }
+
+    #[test]
+    fn test_get_commit_log_first_parent_and_graph() {
+        let (_dir, repo) = crate::test_repo::init();
+        let commit = |message: &str, parents: &[Oid]| {
+            crate::test_repo::commit_on(&repo, parents, &[], message)
+        };
+        let base = commit("Base", &[]);
+        let side = commit("Side", &[base]);
+        let main = commit("Main", &[base]);
+        let merge = commit("Merge side", &[main, side]);
+
+        let (base, merge) = (base.to_string(), merge.to_string());
+        let all = get_commit_log_range(&repo, &base, &merge, &LogOptions::default()).unwrap();
+        assert_eq!(all.len(), 3);
+        assert!(all[0].is_merge());
+
+        let options = LogOptions {
+            first_parent: true,
+            ..Default::default()
+        };
+        let first_parent = get_commit_log_range(&repo, &base, &merge, &options).unwrap();
+        let summaries: Vec<_> = first_parent.iter().map(|c| c.summary()).collect();
+        assert_eq!(summaries, vec!["Merge side", "Main"]);
+
+        let options = LogOptions {
+            topo_order: true,
+            graph: true,
+            ..Default::default()
+        };
+        let graph = get_commit_log_range(&repo, &base, &merge, &options).unwrap();
+        let rows: Vec<_> = graph
+            .iter()
+            .map(|c| format!("{} {}", c.graph, c.summary()))
+            .collect();
+        assert_eq!(rows, vec!["●─╮ Merge side", "● │ Main", "  ● Side"]);
+    }
+
     #[test]
     fn test_parse_range_explicit() {
         let result = parse_range("HEAD~5..HEAD").unwrap();
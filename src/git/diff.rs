@@ -1,17 +1,142 @@
 use anyhow::{Context, Result};
 use git2::{Diff, DiffOptions as Git2DiffOptions, Oid, Repository};
+use serde::{Deserialize, Serialize};
+
+use super::commits::{CommitInfo, Person};
+
+/// Which whitespace differences are hidden from diffs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WhitespaceMode {
+    /// Show every whitespace change
+    #[default]
+    ShowAll,
+    /// Ignore whitespace at end of line (`git diff --ignore-space-at-eol`)
+    IgnoreEol,
+    /// Ignore changes in amount of whitespace (`git diff -b`)
+    IgnoreChange,
+    /// Ignore all whitespace (`git diff -w`)
+    IgnoreAll,
+}
+
+impl WhitespaceMode {
+    /// Next mode in the cycle, from least to most permissive
+    pub fn next(self) -> Self {
+        match self {
+            Self::ShowAll => Self::IgnoreEol,
+            Self::IgnoreEol => Self::IgnoreChange,
+            Self::IgnoreChange => Self::IgnoreAll,
+            Self::IgnoreAll => Self::ShowAll,
+        }
+    }
+
+    /// Short label for the header
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::ShowAll => "ws:all",
+            Self::IgnoreEol => "ws:ignore-eol",
+            Self::IgnoreChange => "ws:ignore-change",
+            Self::IgnoreAll => "ws:ignore-all",
+        }
+    }
+
+    /// Set the matching git2 diff flags
+    pub fn apply(self, opts: &mut Git2DiffOptions) {
+        opts.ignore_whitespace_eol(self == Self::IgnoreEol);
+        opts.ignore_whitespace_change(self == Self::IgnoreChange);
+        opts.ignore_whitespace(self == Self::IgnoreAll);
+    }
+}
+
+/// Include and exclude patterns limiting which files appear in diffs
+///
+/// Patterns are git pathspecs relative to the repository root. Exclude
+/// patterns without a `/` match at any depth, like `.gitignore` entries.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PathFilter {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl PathFilter {
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Pathspecs in libgit2 order: the first matching pattern decides, so
+    /// negative patterns go first and a catch-all ends an exclude-only list
+    pub fn pathspecs(&self) -> Vec<String> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        let mut specs = Vec::new();
+        for pattern in &self.exclude {
+            specs.push(format!("!{pattern}"));
+            if !pattern.contains('/') {
+                specs.push(format!("!*/{pattern}"));
+            }
+        }
+        if self.include.is_empty() {
+            specs.push("*".to_string());
+        } else {
+            specs.extend(self.include.iter().cloned());
+        }
+        specs
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct DiffOptions {
     pub context_lines: u32,
+    pub whitespace: WhitespaceMode,
+    pub paths: PathFilter,
+    /// Encoding for non-UTF-8 files without a `working-tree-encoding`
+    pub fallback_encoding: Option<&'static encoding_rs::Encoding>,
 }
 
 impl Default for DiffOptions {
     fn default() -> Self {
-        Self { context_lines: 8 }
+        Self {
+            context_lines: 8,
+            whitespace: WhitespaceMode::default(),
+            paths: PathFilter::default(),
+            fallback_encoding: None,
+        }
     }
 }
 
+impl DiffOptions {
+    /// Build the git2 options for this context, whitespace and path setting
+    pub fn to_git2(&self) -> Git2DiffOptions {
+        let mut opts = Git2DiffOptions::new();
+        opts.context_lines(self.context_lines);
+        self.whitespace.apply(&mut opts);
+        for spec in self.paths.pathspecs() {
+            opts.pathspec(spec);
+        }
+        opts
+    }
+}
+
+/// Check whether a commit changes any file that passes the path filter
+pub fn commit_touches_filter(
+    repo: &Repository,
+    commit_oid: Oid,
+    filter: &PathFilter,
+) -> Result<bool> {
+    if filter.is_empty() {
+        return Ok(true);
+    }
+    let options = DiffOptions {
+        context_lines: 0,
+        paths: filter.clone(),
+        ..Default::default()
+    };
+    let diff = generate_diff(repo, commit_oid, &options)?;
+    Ok(diff.deltas().len() > 0)
+}
+
 /// Generate diff between a commit and its parent (or base branch)
 ///
 /// For single commits, generates diff vs parent.
@@ -25,63 +150,195 @@ pub fn generate_diff<'a>(
         .find_commit(commit_oid)
         .context("Failed to find commit")?;
 
-    let tree = commit.tree().context("Failed to get commit tree")?;
-
-    let parent_tree = if commit.parent_count() > 0 {
-        Some(
-            commit
-                .parent(0)
-                .context("Failed to get parent commit")?
-                .tree()
-                .context("Failed to get parent tree")?,
-        )
-    } else {
-        None
-    };
-
-    let mut diff_opts = Git2DiffOptions::new();
-    diff_opts.context_lines(options.context_lines);
-    diff_opts.ignore_whitespace(false);
-
-    let diff = repo
-        .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut diff_opts))
-        .context("Failed to generate diff")?;
+    super::merge::diff_against_parent(repo, &commit, 0, options)
+}
 
-    Ok(diff)
+/// Compute the patch id of a commit's change against its first parent
+///
+/// Stable across rebases that don't alter the change itself, like `git
+/// patch-id --stable`.
+pub fn patch_id(repo: &Repository, commit_oid: Oid) -> Result<Oid> {
+    let options = DiffOptions {
+        context_lines: 3,
+        ..Default::default()
+    };
+    let diff = generate_diff(repo, commit_oid, &options)?;
+    diff.patchid(None).context("Failed to compute patch id")
 }
 
-/// Convert git2 Diff to text (patch format)
-pub fn diff_to_text(diff: &Diff) -> Result<String> {
-    let mut patch_text = String::new();
+/// Diff two trees directly, ignoring the history between them
+pub fn diff_trees<'a>(
+    repo: &'a Repository,
+    old_tree: Oid,
+    new_tree: Oid,
+    options: &DiffOptions,
+) -> Result<Diff<'a>> {
+    let old_tree = repo.find_tree(old_tree)?;
+    let new_tree = repo.find_tree(new_tree)?;
+    let mut diff_opts = options.to_git2();
+    repo.diff_tree_to_tree(Some(&old_tree), Some(&new_tree), Some(&mut diff_opts))
+        .context("Failed to diff trees")
+}
 
-    diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
-        let origin = line.origin();
-        let content = std::str::from_utf8(line.content()).unwrap_or("<invalid utf8>");
+/// Resolve two revisions for `--diff`, returning their tree ids and a log
+/// entry standing in for the pair
+///
+/// The entry's id is a blob naming both trees, kept reachable from
+/// `refs/git-review/pairs/<id>` so `git notes prune` and gc leave the notes
+/// attached to it alone. Comments therefore belong to the pair of trees, not
+/// to the refs that happened to point at them.
+pub fn tree_pair(
+    repo: &Repository,
+    old_rev: &str,
+    new_rev: &str,
+) -> Result<(Oid, Oid, CommitInfo)> {
+    let resolve = |rev: &str| {
+        repo.revparse_single(rev)
+            .with_context(|| format!("Failed to resolve '{rev}'"))
+    };
+    let (old, new) = (resolve(old_rev)?, resolve(new_rev)?);
+    let old_tree = old
+        .peel_to_tree()
+        .with_context(|| format!("'{old_rev}' does not name a tree"))?
+        .id();
+    let new_tree = new
+        .peel_to_tree()
+        .with_context(|| format!("'{new_rev}' does not name a tree"))?
+        .id();
 
-        // Add origin prefix for added/removed lines
-        match origin {
-            '+' | '-' | ' ' => {
-                patch_text.push(origin);
-                patch_text.push_str(content);
-            }
-            _ => {
-                patch_text.push_str(content);
-            }
-        }
-        true
-    })
-    .context("Failed to print diff")?;
+    let id = repo
+        .blob(format!("git-review tree pair\n{old_tree}\n{new_tree}\n").as_bytes())
+        .context("Failed to write tree pair")?;
+    repo.reference(
+        &format!("refs/git-review/pairs/{id}"),
+        id,
+        true,
+        "git-review: tree pair",
+    )
+    .context("Failed to write tree pair ref")?;
+    // Attribute the entry to whoever made the new side, when it's a commit
+    let person = match new.peel_to_commit() {
+        Ok(commit) => CommitInfo::from_commit(&commit).committer,
+        Err(_) => repo
+            .signature()
+            .map(|sig| Person::from_signature(&sig))
+            .unwrap_or_default(),
+    };
+    let message =
+        format!("Diff {old_rev}..{new_rev}\n\nOld tree: {old_tree}\nNew tree: {new_tree}");
 
-    Ok(patch_text)
+    let entry = CommitInfo {
+        id,
+        short_id: format!("{id:.7}"),
+        message,
+        author: person.clone(),
+        committer: person,
+        parent_ids: old
+            .peel_to_commit()
+            .ok()
+            .map(|c| c.id())
+            .into_iter()
+            .collect(),
+        trailers: Vec::new(),
+        graph: String::new(),
+    };
+    Ok((old_tree, new_tree, entry))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_repo;
+    use std::path::Path;
 
     #[test]
     fn test_diff_options_default() {
         let opts = DiffOptions::default();
         assert_eq!(opts.context_lines, 8);
+        assert_eq!(opts.whitespace, WhitespaceMode::ShowAll);
+    }
+
+    #[test]
+    fn test_whitespace_mode_cycle() {
+        let mut mode = WhitespaceMode::ShowAll;
+        let mut seen = vec![mode];
+        for _ in 0..3 {
+            mode = mode.next();
+            seen.push(mode);
+        }
+        assert_eq!(
+            seen,
+            vec![
+                WhitespaceMode::ShowAll,
+                WhitespaceMode::IgnoreEol,
+                WhitespaceMode::IgnoreChange,
+                WhitespaceMode::IgnoreAll,
+            ]
+        );
+        assert_eq!(mode.next(), WhitespaceMode::ShowAll);
+    }
+
+    #[test]
+    fn test_tree_pair_is_keyed_by_trees() {
+        let (_dir, repo) = test_repo::init();
+        let first = test_repo::commit(&repo, &[("a.txt", b"one\n")], "First");
+        test_repo::commit(&repo, &[("a.txt", b"two\n")], "Second");
+        test_repo::commit(&repo, &[("a.txt", b"three\n")], "Third");
+
+        let (old_tree, new_tree, entry) = tree_pair(&repo, &first.to_string(), "HEAD").unwrap();
+        assert_eq!(entry.parent_ids, vec![first]);
+        // Kept reachable so pruning doesn't drop its notes
+        let pair_ref = format!("refs/git-review/pairs/{}", entry.id);
+        assert_eq!(repo.refname_to_id(&pair_ref).unwrap(), entry.id);
+        let comments = crate::comments::CommitComments::new(entry.id.to_string(), "main".into());
+        crate::git::notes::write_comments(&repo, "main", entry.id, &comments).unwrap();
+        assert!(crate::git::notes::read_comments(&repo, "main", entry.id)
+            .unwrap()
+            .is_some());
+        // The same trees reached through other revisions share the entry
+        let (.., again) = tree_pair(&repo, "HEAD~2", "HEAD^{tree}").unwrap();
+        assert_eq!(again.id, entry.id);
+
+        let diff = diff_trees(&repo, old_tree, new_tree, &DiffOptions::default()).unwrap();
+        let files = crate::git::files_from_diff(&repo, &diff, &DiffOptions::default()).unwrap();
+        let lines: Vec<_> = files[0].hunks[0]
+            .lines
+            .iter()
+            .map(|l| l.content.as_str())
+            .collect();
+        assert_eq!(lines, vec!["one", "three"]);
+    }
+
+    #[test]
+    fn test_path_filter() {
+        // Same evaluation libgit2 applies to diff pathspecs
+        let matches = |filter: &PathFilter, path: &str| {
+            let specs = filter.pathspecs();
+            specs.is_empty()
+                || git2::Pathspec::new(specs)
+                    .unwrap()
+                    .matches_path(Path::new(path), git2::PathspecFlags::DEFAULT)
+        };
+
+        let filter = PathFilter::default();
+        assert!(filter.pathspecs().is_empty());
+        assert!(matches(&filter, "anything.rs"));
+
+        let filter = PathFilter {
+            include: Vec::new(),
+            exclude: vec!["Cargo.lock".to_string(), "vendor/**".to_string()],
+        };
+        assert!(matches(&filter, "src/main.rs"));
+        assert!(!matches(&filter, "Cargo.lock"));
+        assert!(!matches(&filter, "crates/foo/Cargo.lock"));
+        assert!(!matches(&filter, "vendor/lib/x.c"));
+
+        let filter = PathFilter {
+            include: vec!["src".to_string()],
+            exclude: vec!["*.snap".to_string()],
+        };
+        assert!(matches(&filter, "src/lib.rs"));
+        assert!(!matches(&filter, "src/snapshots/a.snap"));
+        assert!(!matches(&filter, "README.md"));
     }
 }
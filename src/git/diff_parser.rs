@@ -1,6 +1,8 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
+use super::patch::strip_newline;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum LineType {
@@ -14,13 +16,15 @@ pub struct HunkLine {
     pub line_type: LineType,
     pub old_line_num: Option<usize>,
     pub new_line_num: Option<usize>,
-    pub content: String,
+    pub content: String, // Decoded for display, search and comments
+    pub raw: Vec<u8>,    // Bytes as stored in the file, up to the `\n` (keeps a CRLF's `\r`)
+    pub parent_markers: Option<String>, // Per-parent +/- columns in combined merge diffs
+    pub no_newline: bool, // Line has no newline at end of file
 }
 
 #[derive(Debug, Clone)]
 pub struct Hunk {
     pub old_start: usize,
-    #[allow(dead_code)]
     pub old_lines: usize,
     pub new_start: usize,
     pub new_lines: usize,
@@ -28,14 +32,49 @@ pub struct Hunk {
     pub lines: Vec<HunkLine>,
 }
 
+impl HunkLine {
+    /// Line number that comments on this line are anchored to
+    ///
+    /// Removed lines use the old file's numbering, everything else the new.
+    pub fn comment_line_number(&self) -> Option<usize> {
+        match self.line_type {
+            LineType::Added => self.new_line_num,
+            LineType::Removed => self.old_line_num,
+            LineType::Context => self.new_line_num.or(self.old_line_num),
+        }
+    }
+}
+
 impl Hunk {
     /// Check if more context is available below this hunk
     pub fn can_expand_below(&self, file_lines: usize) -> bool {
-        // Check if this hunk reaches the end of the file
-        let hunk_end_line = self.new_start + self.new_lines;
+        // Last line covered by this hunk (the line before it if it has none)
+        let hunk_end_line = self.new_start + self.new_lines.saturating_sub(1);
         hunk_end_line < file_lines
     }
 
+    /// Render this hunk back to a unified diff (header and lines)
+    ///
+    /// Lines are written as their original bytes, so the patch applies to
+    /// files in any encoding.
+    pub fn to_patch(&self) -> Vec<u8> {
+        let mut patch = format!("{}\n", self.header).into_bytes();
+        for line in &self.lines {
+            let prefix = match line.line_type {
+                LineType::Added => b'+',
+                LineType::Removed => b'-',
+                LineType::Context => b' ',
+            };
+            patch.push(prefix);
+            patch.extend_from_slice(&line.raw);
+            patch.push(b'\n');
+            if line.no_newline {
+                patch.extend_from_slice(b"\\ No newline at end of file\n");
+            }
+        }
+        patch
+    }
+
     /// Calculate how many lines can be expanded above
     pub fn available_lines_above(&self) -> usize {
         // Can expand up to the start of the file
@@ -51,109 +90,167 @@ pub struct FileDiff {
     pub old_path: String,
     pub new_path: String,
     pub hunks: Vec<Hunk>,
+    pub old_file_lines: Option<usize>, // Total lines in old version (if known)
     pub new_file_lines: Option<usize>, // Total lines in new version (if known)
+    pub old_size: Option<u64>,         // Size in bytes of old version (if known)
+    pub new_size: Option<u64>,         // Size in bytes of new version (if known)
+    pub old_blob_id: Option<String>,   // Blob id of old version (if known)
+    pub new_blob_id: Option<String>,   // Blob id of new version (if known)
 }
 
-/// Parse a unified diff format into structured hunks
-pub fn parse_diff(diff_text: &str) -> Result<Vec<FileDiff>> {
-    let mut files = Vec::new();
-    let mut current_file: Option<FileDiff> = None;
-    let mut current_hunk: Option<Hunk> = None;
+impl FileDiff {
+    /// Empty entry for a file, to be filled in by a parser
+    fn new(old_path: &str, new_path: &str) -> Self {
+        Self {
+            old_path: old_path.to_string(),
+            new_path: new_path.to_string(),
+            hunks: Vec::new(),
+            old_file_lines: None,
+            new_file_lines: None,
+            old_size: None,
+            new_size: None,
+            old_blob_id: None,
+            new_blob_id: None,
+        }
+    }
 
-    let mut old_line_num = 0;
-    let mut new_line_num = 0;
+    /// Get the blob id identifying this file's reviewed content
+    ///
+    /// Uses the new version, falling back to the old version for deleted files.
+    pub fn content_id(&self) -> Option<&str> {
+        let is_real = |id: &&String| !id.chars().all(|c| c == '0');
+        self.new_blob_id
+            .as_ref()
+            .filter(is_real)
+            .or(self.old_blob_id.as_ref().filter(is_real))
+            .map(|s| s.as_str())
+    }
 
-    for line in diff_text.lines() {
-        if line.starts_with("diff --git") {
-            // Save previous file if exists
-            if let Some(mut file) = current_file.take() {
-                if let Some(hunk) = current_hunk.take() {
-                    file.hunks.push(hunk);
-                }
-                files.push(file);
+    /// Apply this file's hunks to the old content, giving the new content
+    ///
+    /// Returns None if the hunks don't match `old`, e.g. because the patch
+    /// was made against a different version of the file.
+    pub fn apply(&self, old: &[u8]) -> Option<Vec<u8>> {
+        let old_lines: Vec<&[u8]> = old.split_inclusive(|&b| b == b'\n').collect();
+        let mut new = Vec::with_capacity(old.len());
+        let mut next = 0; // First old line not copied yet
+
+        for hunk in &self.hunks {
+            // A hunk without old lines starts after `old_start` instead of at it
+            let start = if hunk.old_lines == 0 {
+                hunk.old_start
+            } else {
+                hunk.old_start.checked_sub(1)?
+            };
+            if start < next || start > old_lines.len() {
+                return None;
             }
+            old_lines[next..start]
+                .iter()
+                .for_each(|line| new.extend_from_slice(line));
+            next = start;
 
-            // Start new file
-            current_file = Some(FileDiff {
-                old_path: String::new(),
-                new_path: String::new(),
-                hunks: Vec::new(),
-                new_file_lines: None,
-            });
-        } else if line.starts_with("--- ") {
-            if let Some(ref mut file) = current_file {
-                file.old_path = line.strip_prefix("--- ").unwrap_or("").to_string();
-                // Remove a/ or b/ prefix if present
-                if file.old_path.starts_with("a/") {
-                    file.old_path = file.old_path[2..].to_string();
+            for line in &hunk.lines {
+                if line.line_type == LineType::Added {
+                    new.extend_from_slice(&line.raw);
+                    if !line.no_newline {
+                        new.push(b'\n');
+                    }
+                    continue;
                 }
-            }
-        } else if line.starts_with("+++ ") {
-            if let Some(ref mut file) = current_file {
-                file.new_path = line.strip_prefix("+++ ").unwrap_or("").to_string();
-                if file.new_path.starts_with("b/") {
-                    file.new_path = file.new_path[2..].to_string();
+                let old_line = old_lines.get(next)?;
+                if strip_newline(old_line) != line.raw.as_slice() {
+                    return None;
                 }
-            }
-        } else if line.starts_with("@@") {
-            // Save previous hunk if exists
-            if let Some(ref mut file) = current_file {
-                if let Some(hunk) = current_hunk.take() {
-                    file.hunks.push(hunk);
+                if line.line_type == LineType::Context {
+                    new.extend_from_slice(old_line);
                 }
+                next += 1;
             }
+        }
+
+        old_lines[next..]
+            .iter()
+            .for_each(|line| new.extend_from_slice(line));
+        Some(new)
+    }
+}
 
-            // Parse hunk header: @@ -old_start,old_lines +new_start,new_lines @@
-            let hunk_info = line.trim_start_matches("@@").trim_end_matches("@@").trim();
+/// Parse the ranges of a `@@ -a,b +c,d @@ context` hunk header
+///
+/// Returns (old_start, old_lines, new_start, new_lines); omitted counts are 1.
+pub fn parse_hunk_header(header: &str) -> Option<(usize, usize, usize, usize)> {
+    let (ranges, _) = header.strip_prefix("@@ ")?.split_once(" @@")?;
+    let (old, new) = ranges.split_once(' ')?;
+    let range = |range: &str| -> Option<(usize, usize)> {
+        match range.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_lines) = range(old.strip_prefix('-')?)?;
+    let (new_start, new_lines) = range(new.strip_prefix('+')?)?;
+    Some((old_start, old_lines, new_start, new_lines))
+}
 
-            if let Some((old_part, new_part)) = hunk_info.split_once(' ') {
-                let old_parts: Vec<&str> = old_part.trim_start_matches('-').split(',').collect();
-                let new_parts: Vec<&str> = new_part.trim_start_matches('+').split(',').collect();
+/// Parse a unified diff format into structured hunks
+///
+/// Diffs generated from the repository are built from git2 patches instead
+/// (see `git::patch`); this is for patch text from elsewhere: `git diff` or
+/// `git format-patch` output and plain `diff -u`. Hunks end when their line
+/// counts are used up, so text after the last one (like a mail signature)
+/// is ignored.
+pub fn parse_diff(diff_text: &str) -> Result<Vec<FileDiff>> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    let mut old_path_seen = false; // Current file already had its `---` line
 
-                let old_start = old_parts.first().and_then(|s| s.parse().ok()).unwrap_or(0);
-                let old_lines = old_parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(1);
-                let new_start = new_parts.first().and_then(|s| s.parse().ok()).unwrap_or(0);
-                let new_lines = new_parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(1);
+    let mut old_line_num = 0;
+    let mut new_line_num = 0;
+    let mut old_remaining = 0;
+    let mut new_remaining = 0;
 
-                old_line_num = old_start;
-                new_line_num = new_start;
+    for full_line in diff_text.split_inclusive('\n') {
+        // Hunk lines keep a `\r` that belongs to the file's content
+        let body = full_line.strip_suffix('\n').unwrap_or(full_line);
+        let line = body.strip_suffix('\r').unwrap_or(body);
+        let current_hunk = files.last_mut().and_then(|f| f.hunks.last_mut());
 
-                current_hunk = Some(Hunk {
-                    old_start,
-                    old_lines,
-                    new_start,
-                    new_lines,
-                    header: line.to_string(),
-                    lines: Vec::new(),
-                });
+        if line.starts_with('\\') {
+            // "\ No newline at end of file" applies to the previous line
+            if let Some(last) = current_hunk.and_then(|h| h.lines.last_mut()) {
+                last.no_newline = true;
             }
-        } else if let Some(ref mut hunk) = current_hunk {
-            // Parse diff line
-            if let Some(first_char) = line.chars().next() {
-                let (line_type, content) = match first_char {
-                    '+' => (LineType::Added, &line[1..]),
-                    '-' => (LineType::Removed, &line[1..]),
-                    ' ' => (LineType::Context, &line[1..]),
-                    _ => continue,
-                };
+            continue;
+        }
 
+        if old_remaining > 0 || new_remaining > 0 {
+            let parsed = match line.chars().next() {
+                Some('+') => Some(LineType::Added),
+                Some('-') => Some(LineType::Removed),
+                Some(' ') => Some(LineType::Context),
+                // Mail clients may strip the space from empty context lines
+                None => Some(LineType::Context),
+                _ => None,
+            };
+            if let (Some(line_type), Some(hunk)) = (parsed, current_hunk) {
+                let (content, raw) = (line.get(1..).unwrap_or(""), body.get(1..).unwrap_or(""));
                 let (old_num, new_num) = match line_type {
                     LineType::Context => {
-                        let old = Some(old_line_num);
-                        let new = Some(new_line_num);
+                        old_remaining -= 1.min(old_remaining);
+                        new_remaining -= 1.min(new_remaining);
                         old_line_num += 1;
                         new_line_num += 1;
-                        (old, new)
+                        (Some(old_line_num - 1), Some(new_line_num - 1))
                     }
                     LineType::Added => {
-                        let new = Some(new_line_num);
+                        new_remaining -= 1.min(new_remaining);
                         new_line_num += 1;
-                        (None, new)
+                        (None, Some(new_line_num - 1))
                     }
                     LineType::Removed => {
-                        let old = Some(old_line_num);
+                        old_remaining -= 1.min(old_remaining);
                         old_line_num += 1;
-                        (old, None)
+                        (Some(old_line_num - 1), None)
                     }
                 };
 
@@ -162,35 +259,127 @@ pub fn parse_diff(diff_text: &str) -> Result<Vec<FileDiff>> {
                     old_line_num: old_num,
                     new_line_num: new_num,
                     content: content.to_string(),
+                    raw: raw.as_bytes().to_vec(),
+                    parent_markers: None,
+                    no_newline: false,
                 });
+                continue;
             }
+            // A truncated hunk, carry on with the headers
+            old_remaining = 0;
+            new_remaining = 0;
         }
-    }
 
-    // Save final file and hunk
-    if let Some(mut file) = current_file {
-        if let Some(hunk) = current_hunk {
-            file.hunks.push(hunk);
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            // Paths from `--- `/`+++ ` lines take precedence; binary and
+            // rename-only changes have none
+            let (old_path, new_path) = match paths.rsplit_once(" b/") {
+                Some((old, new)) => (old.strip_prefix("a/").unwrap_or(old), new),
+                None => (paths, paths),
+            };
+            files.push(FileDiff::new(old_path, new_path));
+            old_path_seen = false;
+        } else if let Some(ids) = line.strip_prefix("index ") {
+            // index <old>..<new> [mode]
+            if let Some(file) = files.last_mut() {
+                let ids = ids.split(' ').next().unwrap_or("");
+                if let Some((old_id, new_id)) = ids.split_once("..") {
+                    file.old_blob_id = Some(old_id.to_string());
+                    file.new_blob_id = Some(new_id.to_string());
+                }
+            }
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            // Plain unified diffs start each file here
+            let new_file = match files.last() {
+                Some(file) => old_path_seen || !file.hunks.is_empty(),
+                None => true,
+            };
+            if new_file {
+                files.push(FileDiff::new("", ""));
+            }
+            if let Some(file) = files.last_mut() {
+                file.old_path = patch_path(path, "a/").unwrap_or_default();
+            }
+            old_path_seen = true;
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            if let Some(file) = files.last_mut() {
+                file.new_path = patch_path(path, "b/").unwrap_or_default();
+            }
+        } else if let Some(path) = line
+            .strip_prefix("rename from ")
+            .or(line.strip_prefix("copy from "))
+        {
+            if let Some(file) = files.last_mut() {
+                file.old_path = path.to_string();
+            }
+        } else if let Some(path) = line
+            .strip_prefix("rename to ")
+            .or(line.strip_prefix("copy to "))
+        {
+            if let Some(file) = files.last_mut() {
+                file.new_path = path.to_string();
+            }
+        } else if line.starts_with("@@") {
+            let (Some(file), Some((old_start, old_lines, new_start, new_lines))) =
+                (files.last_mut(), parse_hunk_header(line))
+            else {
+                continue;
+            };
+
+            old_line_num = old_start;
+            new_line_num = new_start;
+            old_remaining = old_lines;
+            new_remaining = new_lines;
+
+            file.hunks.push(Hunk {
+                old_start,
+                old_lines,
+                new_start,
+                new_lines,
+                header: line.to_string(),
+                lines: Vec::new(),
+            });
         }
+    }
 
-        // Calculate file line count from maximum line number seen
-        let mut max_line = 0;
-        for hunk in &file.hunks {
-            // Get the highest line number from this hunk
-            let hunk_end = hunk.new_start + hunk.new_lines.saturating_sub(1);
-            max_line = max_line.max(hunk_end);
+    for file in &mut files {
+        // Added and deleted files only have a path on one side
+        if file.old_path.is_empty() {
+            file.old_path = file.new_path.clone();
+        } else if file.new_path.is_empty() {
+            file.new_path = file.old_path.clone();
         }
 
+        // Calculate file line count from maximum line number seen
+        let max_line = file
+            .hunks
+            .iter()
+            .map(|hunk| hunk.new_start + hunk.new_lines.saturating_sub(1))
+            .max()
+            .unwrap_or(0);
         if max_line > 0 {
             file.new_file_lines = Some(max_line);
         }
-
-        files.push(file);
     }
 
     Ok(files)
 }
 
+/// Path from a `--- ` or `+++ ` line, None for /dev/null
+///
+/// Drops the `a/` or `b/` prefix and the timestamp plain `diff -u` appends.
+fn patch_path(field: &str, prefix: &str) -> Option<String> {
+    let path = field.split('\t').next().unwrap_or(field).trim_end();
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path
+        .strip_prefix('"')
+        .and_then(|p| p.strip_suffix('"'))
+        .unwrap_or(path);
+    Some(path.strip_prefix(prefix).unwrap_or(path).to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -227,4 +416,104 @@ mod tests {
         assert_eq!(hunk.lines[2].line_type, LineType::Added);
         assert_eq!(hunk.lines[3].line_type, LineType::Context);
     }
+
+    #[test]
+    fn test_parse_blob_ids() {
+        let diff_text = r#"diff --git a/gone.txt b/gone.txt
+deleted file mode 100644
+index 1234567..0000000
+--- a/gone.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+"#;
+
+        let files = parse_diff(diff_text).unwrap();
+        let file = &files[0];
+        assert_eq!(file.old_blob_id.as_deref(), Some("1234567"));
+        assert_eq!(file.new_blob_id.as_deref(), Some("0000000"));
+        // Deleted files are identified by their old content
+        assert_eq!(file.content_id(), Some("1234567"));
+    }
+
+    #[test]
+    fn test_parse_format_patch_body() {
+        // A removed "-- x" line looks like a `---` header, the signature like
+        // removed lines; the hunk counts tell them apart
+        let diff_text = "diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -2,3 +2,3 @@ fn main() {
+ a
+--- x
++b
+ c
+diff --git a/new.txt b/new.txt
+new file mode 100644
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1 @@
++hello
+\\ No newline at end of file
+-- 
+2.43.0
+";
+
+        let files = parse_diff(diff_text).unwrap();
+        assert_eq!(files.len(), 2);
+        let hunk = &files[0].hunks[0];
+        assert_eq!((hunk.old_start, hunk.new_start), (2, 2));
+        assert_eq!(hunk.lines.len(), 4);
+        assert_eq!(hunk.lines[1].line_type, LineType::Removed);
+        assert_eq!(hunk.lines[1].content, "-- x");
+        assert_eq!(hunk.lines[3].new_line_num, Some(4));
+
+        let added = &files[1];
+        assert_eq!(added.old_path, "new.txt");
+        assert_eq!(added.hunks[0].lines.len(), 1);
+        assert!(added.hunks[0].lines[0].no_newline);
+    }
+
+    #[test]
+    fn test_parse_plain_unified_diff() {
+        let diff_text = "--- old/a.txt\t2024-01-01 10:00:00
++++ new/a.txt\t2024-01-02 10:00:00
+@@ -1 +1 @@
+-one
++two
+--- b.txt
++++ b.txt
+@@ -1,2 +1,2 @@
+ keep
+
+";
+
+        let files = parse_diff(diff_text).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].old_path, "old/a.txt");
+        assert_eq!(files[0].new_path, "new/a.txt");
+        // The empty line is a context line whose space was stripped
+        assert_eq!(files[1].hunks[0].lines.len(), 2);
+    }
+
+    #[test]
+    fn test_apply_hunks() {
+        let diff_text = "--- a/f
++++ b/f
+@@ -2 +2,2 @@
+-b
++B
++B2
+@@ -4,0 +6 @@ d
++e
+";
+        let file = &parse_diff(diff_text).unwrap()[0];
+        assert_eq!(
+            file.apply(b"a\nb\nc\nd\n").as_deref(),
+            Some(&b"a\nB\nB2\nc\nd\ne\n"[..])
+        );
+        // Made against a different version
+        assert_eq!(file.apply(b"a\nx\nc\nd\n"), None);
+    }
 }
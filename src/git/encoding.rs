@@ -0,0 +1,115 @@
+use encoding_rs::Encoding;
+use git2::{AttrCheckFlags, Repository};
+use std::path::Path;
+
+/// Look up an encoding by its label, e.g. "latin1" or "Shift_JIS"
+pub fn encoding_for_label(label: &str) -> Option<&'static Encoding> {
+    Encoding::for_label(label.trim().as_bytes())
+}
+
+/// Turns the raw bytes of one file's lines into display text
+///
+/// Valid UTF-8 is used as-is. Anything else is decoded with the file's
+/// `working-tree-encoding` attribute, then the configured fallback encoding,
+/// and otherwise shown with the invalid bytes escaped as `\xNN`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextDecoder {
+    attribute: Option<&'static Encoding>,
+    fallback: Option<&'static Encoding>,
+}
+
+impl TextDecoder {
+    pub fn new(fallback: Option<&'static Encoding>) -> Self {
+        Self {
+            attribute: None,
+            fallback,
+        }
+    }
+
+    /// Decoder for a path, honouring its `.gitattributes` encoding
+    pub fn for_path(repo: &Repository, path: &str, fallback: Option<&'static Encoding>) -> Self {
+        let attribute = repo
+            .get_attr(
+                Path::new(path),
+                "working-tree-encoding",
+                AttrCheckFlags::FILE_THEN_INDEX,
+            )
+            .ok()
+            .flatten()
+            .and_then(encoding_for_label);
+        Self {
+            attribute,
+            fallback,
+        }
+    }
+
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        if let Ok(text) = std::str::from_utf8(bytes) {
+            return text.to_string();
+        }
+        [self.attribute, self.fallback]
+            .into_iter()
+            .flatten()
+            .find_map(|encoding| {
+                encoding
+                    .decode_without_bom_handling_and_without_replacement(bytes)
+                    .map(|text| text.into_owned())
+            })
+            .unwrap_or_else(|| escape_invalid(bytes))
+    }
+}
+
+/// Replace bytes that aren't valid UTF-8 with visible `\xNN` escapes
+pub fn escape_invalid(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len());
+    for chunk in bytes.utf8_chunks() {
+        text.push_str(chunk.valid());
+        for byte in chunk.invalid() {
+            text.push_str(&format!("\\x{byte:02X}"));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_decode_fallbacks() {
+        let latin1 = b"caf\xe9";
+        let sjis = b"\x93\xfa\x96\x7b"; // "日本" in Shift-JIS
+
+        // UTF-8 passes through untouched whatever the fallback
+        let decoder = TextDecoder::new(encoding_for_label("latin1"));
+        assert_eq!(decoder.decode("café".as_bytes()), "café");
+        assert_eq!(decoder.decode(latin1), "café");
+
+        let decoder = TextDecoder::new(encoding_for_label("shift_jis"));
+        assert_eq!(decoder.decode(sjis), "日本");
+
+        // No usable encoding: invalid bytes are escaped
+        let decoder = TextDecoder::default();
+        assert_eq!(decoder.decode(latin1), "caf\\xE9");
+        assert_eq!(escape_invalid(b"a\xff\xfeb"), "a\\xFF\\xFEb");
+    }
+
+    #[test]
+    fn test_decoder_for_path_uses_gitattributes() {
+        let dir = TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        std::fs::write(
+            dir.path().join(".gitattributes"),
+            "*.sjis working-tree-encoding=Shift_JIS\n",
+        )
+        .unwrap();
+
+        let decoder = TextDecoder::for_path(&repo, "legacy/app.sjis", encoding_for_label("latin1"));
+        assert_eq!(decoder.decode(b"\x93\xfa\x96\x7b"), "日本");
+
+        // Other files only get the fallback
+        let decoder = TextDecoder::for_path(&repo, "app.c", encoding_for_label("latin1"));
+        assert_eq!(decoder.decode(b"\x93\xfa"), "\u{201c}ú");
+    }
+}
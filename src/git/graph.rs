@@ -0,0 +1,245 @@
+use std::collections::{HashMap, HashSet};
+
+use git2::Oid;
+
+use super::commits::CommitInfo;
+
+/// Fill in the graph column of commits listed newest first
+///
+/// Each commit gets one row of lane glyphs, like a compact `git log --graph`.
+/// Parents outside the list end their lane at the child.
+pub fn assign_graph(commits: &mut [CommitInfo]) {
+    let ids: Vec<Oid> = commits.iter().map(|c| c.id).collect();
+    let parents: Vec<Vec<Oid>> = commits.iter().map(|c| c.parent_ids.clone()).collect();
+    for (commit, row) in commits.iter_mut().zip(layout(&ids, &parents)) {
+        commit.graph = row;
+    }
+}
+
+/// Drop hidden commits and redraw the graph through them
+///
+/// A visible commit whose parent is hidden is connected to the hidden
+/// commit's nearest visible ancestors instead, so lanes stay continuous.
+pub fn hide_commits(commits: Vec<CommitInfo>, hidden: &HashSet<Oid>) -> Vec<CommitInfo> {
+    let all_parents: HashMap<Oid, &[Oid]> = commits
+        .iter()
+        .map(|c| (c.id, c.parent_ids.as_slice()))
+        .collect();
+
+    let mut ids = Vec::new();
+    let mut parents = Vec::new();
+    for commit in commits.iter().filter(|c| !hidden.contains(&c.id)) {
+        let mut visible = Vec::new();
+        let mut pending: Vec<Oid> = commit.parent_ids.iter().rev().copied().collect();
+        let mut seen = HashSet::new();
+        while let Some(parent) = pending.pop() {
+            if !seen.insert(parent) {
+                continue;
+            }
+            match all_parents.get(&parent) {
+                Some(grandparents) if hidden.contains(&parent) => {
+                    pending.extend(grandparents.iter().rev().copied());
+                }
+                _ => visible.push(parent),
+            }
+        }
+        ids.push(commit.id);
+        parents.push(visible);
+    }
+
+    let mut rows = layout(&ids, &parents).into_iter();
+    commits
+        .into_iter()
+        .filter(|c| !hidden.contains(&c.id))
+        .map(|mut commit| {
+            commit.graph = rows.next().unwrap_or_default();
+            commit
+        })
+        .collect()
+}
+
+/// Lay out one graph row per commit from its parent links
+///
+/// Lanes hold the commit they are waiting for. A commit takes the leftmost
+/// lane waiting for it, other lanes waiting for it join with `╯`, and extra
+/// parents of a merge branch off with `╮` into a free lane (or the lane
+/// already waiting for that parent).
+fn layout(ids: &[Oid], parents: &[Vec<Oid>]) -> Vec<String> {
+    let listed: HashSet<Oid> = ids.iter().copied().collect();
+    let mut lanes: Vec<Option<Oid>> = Vec::new();
+    let mut rows = Vec::with_capacity(ids.len());
+
+    for (&id, parents) in ids.iter().zip(parents) {
+        let parents: Vec<Oid> = parents
+            .iter()
+            .copied()
+            .filter(|p| listed.contains(p))
+            .collect();
+
+        let passing: Vec<bool> = lanes.iter().map(|l| l.is_some_and(|o| o != id)).collect();
+        let col = match lanes.iter().position(|l| *l == Some(id)) {
+            Some(col) => col,
+            None => free_lane(&mut lanes, 0, &[]),
+        };
+        let joining: Vec<usize> = (0..lanes.len())
+            .filter(|&i| i != col && lanes[i] == Some(id))
+            .collect();
+        for &i in &joining {
+            lanes[i] = None;
+        }
+
+        lanes[col] = parents.first().copied();
+        let mut forks = Vec::new();
+        for &parent in parents.iter().skip(1) {
+            let lane = match lanes.iter().position(|l| *l == Some(parent)) {
+                Some(lane) => lane,
+                None => {
+                    let lane = free_lane(&mut lanes, col + 1, &joining);
+                    lanes[lane] = Some(parent);
+                    lane
+                }
+            };
+            forks.push(lane);
+        }
+
+        rows.push(draw_row(lanes.len(), col, &passing, &joining, &forks));
+
+        while lanes.last() == Some(&None) {
+            lanes.pop();
+        }
+    }
+
+    rows
+}
+
+/// First empty lane at or after `from` that isn't ending on this row
+fn free_lane(lanes: &mut Vec<Option<Oid>>, from: usize, ending: &[usize]) -> usize {
+    let free = (from..lanes.len()).find(|&i| lanes[i].is_none() && !ending.contains(&i));
+    free.unwrap_or_else(|| {
+        lanes.push(None);
+        lanes.len() - 1
+    })
+}
+
+/// Render one row: two columns per lane, the glyph and a spacer
+fn draw_row(
+    width: usize,
+    col: usize,
+    passing: &[bool],
+    joining: &[usize],
+    forks: &[usize],
+) -> String {
+    let targets = joining.iter().chain(forks);
+    let span_start = targets.clone().copied().chain([col]).min().unwrap_or(col);
+    let span_end = targets.copied().chain([col]).max().unwrap_or(col);
+    let in_span = |i: usize| span_start <= i && i <= span_end;
+    let was_passing = |i: usize| passing.get(i).copied().unwrap_or(false);
+
+    let mut row = String::new();
+    for i in 0..width {
+        let glyph = if i == col {
+            '●'
+        } else if joining.contains(&i) {
+            if i > col {
+                '╯'
+            } else {
+                '╰'
+            }
+        } else if forks.contains(&i) && was_passing(i) {
+            if i > col {
+                '┤'
+            } else {
+                '├'
+            }
+        } else if forks.contains(&i) {
+            if i > col {
+                '╮'
+            } else {
+                '╭'
+            }
+        } else if was_passing(i) {
+            if in_span(i) {
+                '┼'
+            } else {
+                '│'
+            }
+        } else if in_span(i) {
+            '─'
+        } else {
+            ' '
+        };
+        // The spacer continues a horizontal line to the next lane
+        let spacer = if in_span(i) && in_span(i + 1) {
+            '─'
+        } else {
+            ' '
+        };
+        row.push(glyph);
+        row.push(spacer);
+    }
+    row.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oid(n: u8) -> Oid {
+        Oid::from_bytes(&[n; 20]).unwrap()
+    }
+
+    #[test]
+    fn test_layout_branch_and_merge() {
+        // merge(main, side), main -> base, side -> base, base -> (outside)
+        let ids = [oid(4), oid(3), oid(2), oid(1)];
+        let parents = [
+            vec![oid(3), oid(2)],
+            vec![oid(1)],
+            vec![oid(1)],
+            vec![oid(0)],
+        ];
+        assert_eq!(layout(&ids, &parents), vec!["●─╮", "● │", "│ ●", "●─╯"]);
+    }
+
+    #[test]
+    fn test_layout_side_lane_passes_through() {
+        // The side lane stays open while the main line continues below the merge
+        let ids = [oid(5), oid(4), oid(3), oid(2), oid(1)];
+        let parents = [
+            vec![oid(4), oid(2)],
+            vec![oid(3)],
+            vec![oid(1)],
+            vec![oid(1)],
+            vec![],
+        ];
+        assert_eq!(
+            layout(&ids, &parents),
+            vec!["●─╮", "● │", "● │", "│ ●", "●─╯"]
+        );
+    }
+
+    #[test]
+    fn test_hide_commits_keeps_lanes_connected() {
+        let commit = |n: u8, parents: &[u8]| CommitInfo {
+            id: oid(n),
+            short_id: format!("{:.7}", oid(n)),
+            message: "Commit".to_string(),
+            author: Default::default(),
+            committer: Default::default(),
+            parent_ids: parents.iter().map(|&p| oid(p)).collect(),
+            trailers: Vec::new(),
+            graph: String::new(),
+        };
+        let commits = vec![
+            commit(4, &[3, 2]),
+            commit(3, &[1]),
+            commit(2, &[1]),
+            commit(1, &[]),
+        ];
+
+        let hidden = HashSet::from([oid(3)]);
+        let visible = hide_commits(commits, &hidden);
+        let rows: Vec<_> = visible.iter().map(|c| c.graph.as_str()).collect();
+        assert_eq!(rows, vec!["●─╮", "│ ●", "●─╯"]);
+    }
+}
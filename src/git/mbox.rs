@@ -0,0 +1,421 @@
+use anyhow::{bail, Result};
+use base64::Engine;
+use chrono::DateTime;
+
+use super::commits::Person;
+use super::encoding::encoding_for_label;
+
+/// One patch from a mailbox or patch file
+#[derive(Debug, Clone, Default)]
+pub struct PatchMessage {
+    pub subject: String,            // Subject as sent, e.g. "[PATCH 1/2] Fix it"
+    pub message_id: Option<String>, // Message-Id, for threading a reply
+    pub author: Person,
+    pub message: String,             // Commit message: summary, blank line, body
+    pub diff: String,                // The diff, from its first header on
+    pub base_commit: Option<String>, // `base-commit:` from `format-patch --base`
+}
+
+/// Parse the messages of an mbox file, skipping those without a diff
+/// (like a series' cover letter)
+pub fn parse_mbox(text: &str) -> Result<Vec<PatchMessage>> {
+    let patches: Vec<PatchMessage> = split_mbox(text)
+        .into_iter()
+        .map(parse_message)
+        .filter(|patch| !patch.diff.is_empty())
+        .collect();
+    if patches.is_empty() {
+        bail!("No patches found in mailbox");
+    }
+    Ok(patches)
+}
+
+/// Parse a patch file: `git format-patch` output (one or more messages),
+/// a single email, or a bare diff named after `name`
+pub fn parse_patch(text: &str, name: &str) -> Result<Vec<PatchMessage>> {
+    let first_line = text.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+    if first_line.starts_with("From ") || is_header_line(first_line) {
+        return parse_mbox(text);
+    }
+
+    let diff = diff_start(text).map(|start| &text[start..]).unwrap_or("");
+    if diff.is_empty() {
+        bail!("No diff found in {name}");
+    }
+    Ok(vec![PatchMessage {
+        subject: name.to_string(),
+        message: text[..text.len() - diff.len()]
+            .trim()
+            .lines()
+            .next()
+            .map_or_else(|| name.to_string(), str::to_string),
+        diff: diff.to_string(),
+        ..Default::default()
+    }])
+}
+
+/// Split an mbox into messages at its `From ` separator lines
+fn split_mbox(text: &str) -> Vec<String> {
+    let mut messages = Vec::new();
+    let mut current: Option<String> = None;
+    let mut previous_blank = true;
+
+    for line in text.lines() {
+        if line.starts_with("From ") && previous_blank {
+            messages.extend(current.take());
+            current = Some(String::new());
+        } else {
+            // mboxrd escapes body lines starting with "From " as ">From "
+            let unescaped = match line.trim_start_matches('>').starts_with("From ") {
+                true if line.starts_with('>') => &line[1..],
+                _ => line,
+            };
+            let message = current.get_or_insert_with(String::new);
+            message.push_str(unescaped);
+            message.push('\n');
+        }
+        previous_blank = line.trim().is_empty();
+    }
+    messages.extend(current);
+    messages
+}
+
+/// Parse one message: headers, then the commit message and the diff
+fn parse_message(raw: String) -> PatchMessage {
+    let (header_text, body) = match raw.split_once("\n\n") {
+        Some((headers, body)) => (headers, body),
+        None => (raw.as_str(), ""),
+    };
+    let headers = unfold_headers(header_text);
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    };
+
+    let charset = header("Content-Type").and_then(|value| {
+        value
+            .split(';')
+            .find_map(|param| param.trim().strip_prefix("charset="))
+            .map(|charset| charset.trim_matches('"').to_string())
+    });
+    let body = decode_body(
+        body,
+        header("Content-Transfer-Encoding"),
+        charset.as_deref(),
+    );
+
+    let mut from = header("From").map(decode_header);
+    let mut date = header("Date").map(str::to_string);
+    let mut subject = header("Subject").map(decode_header).unwrap_or_default();
+
+    // `format-patch` puts the author in the body when someone else sends it
+    let mut body = body.as_str();
+    if let Some((in_body, rest)) = body.split_once("\n\n") {
+        let in_body_headers = unfold_headers(in_body);
+        let is_author_block = in_body.lines().all(is_header_line)
+            && in_body_headers.iter().all(|(key, _)| {
+                ["from", "date", "subject"].contains(&key.to_ascii_lowercase().as_str())
+            });
+        if is_author_block {
+            for (key, value) in in_body_headers {
+                match key.to_ascii_lowercase().as_str() {
+                    "from" => from = Some(decode_header(&value)),
+                    "date" => date = Some(value),
+                    "subject" => subject = decode_header(&value),
+                    _ => {}
+                }
+            }
+            body = rest;
+        }
+    }
+
+    let diff_at = diff_start(body).unwrap_or(body.len());
+    // The commit message ends at the `---` line above the diffstat
+    let message_end = body[..diff_at]
+        .find("\n---\n")
+        .map(|i| i + 1)
+        .or_else(|| body.starts_with("---\n").then_some(0))
+        .unwrap_or(diff_at);
+    let description = body[..message_end].trim();
+    let summary = strip_subject_prefixes(&subject);
+    let message = if description.is_empty() {
+        summary.to_string()
+    } else {
+        format!("{summary}\n\n{description}")
+    };
+
+    PatchMessage {
+        message_id: header("Message-Id").map(|id| id.trim().to_string()),
+        author: parse_author(from.as_deref().unwrap_or(""), date.as_deref()),
+        message,
+        diff: body[diff_at..].to_string(),
+        base_commit: body[diff_at..]
+            .lines()
+            .find_map(|line| line.strip_prefix("base-commit: "))
+            .map(|id| id.trim().to_string()),
+        subject,
+    }
+}
+
+/// Byte offset of the first diff header line
+fn diff_start(text: &str) -> Option<usize> {
+    let mut offset = 0;
+    let mut lines = text.split_inclusive('\n').peekable();
+    while let Some(line) = lines.next() {
+        let is_git_diff = line.starts_with("diff --git ");
+        let is_plain_diff =
+            line.starts_with("--- ") && lines.peek().is_some_and(|next| next.starts_with("+++ "));
+        if is_git_diff || is_plain_diff {
+            return Some(offset);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+fn is_header_line(line: &str) -> bool {
+    line.split_once(": ").is_some_and(|(key, _)| {
+        !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+/// Header names and values, with folded lines joined
+fn unfold_headers(text: &str) -> Vec<(String, String)> {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in text.lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((key, value)) = line.split_once(':') {
+            headers.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    headers
+}
+
+/// Drop `Re:` and bracketed tags like `[PATCH v2 1/3]` from a subject
+fn strip_subject_prefixes(subject: &str) -> &str {
+    let mut subject = subject.trim();
+    loop {
+        if let Some(rest) = subject.strip_prefix('[').and_then(|s| s.split_once(']')) {
+            subject = rest.1.trim_start();
+        } else if subject
+            .get(..3)
+            .is_some_and(|re| re.eq_ignore_ascii_case("re:"))
+        {
+            subject = subject[3..].trim_start();
+        } else {
+            return subject;
+        }
+    }
+}
+
+/// Author from a `Name <email>` header and an RFC 2822 date
+fn parse_author(from: &str, date: Option<&str>) -> Person {
+    let (name, email) = match from.rsplit_once('<') {
+        Some((name, email)) => (name, email.trim_end().trim_end_matches('>')),
+        None => ("", from),
+    };
+    let name = name.trim().trim_matches('"');
+    Person {
+        name: if name.is_empty() { email } else { name }.to_string(),
+        email: email.trim().to_string(),
+        time: date
+            .and_then(|date| DateTime::parse_from_rfc2822(date.trim()).ok())
+            .unwrap_or_default(),
+    }
+}
+
+/// Decode a quoted-printable or base64 body in the given charset
+fn decode_body(body: &str, transfer_encoding: Option<&str>, charset: Option<&str>) -> String {
+    let bytes = match transfer_encoding.map(|e| e.trim().to_ascii_lowercase()) {
+        Some(e) if e == "quoted-printable" => decode_quoted_printable(body, false),
+        Some(e) if e == "base64" => {
+            let compact: String = body.split_whitespace().collect();
+            match base64::engine::general_purpose::STANDARD.decode(compact) {
+                Ok(bytes) => bytes,
+                Err(_) => return body.to_string(),
+            }
+        }
+        _ => return body.to_string(),
+    };
+    decode_charset(&bytes, charset)
+}
+
+fn decode_charset(bytes: &[u8], charset: Option<&str>) -> String {
+    match charset.and_then(encoding_for_label) {
+        Some(encoding) => encoding.decode(bytes).0.into_owned(),
+        None => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+/// Decode quoted-printable text; headers (RFC 2047 `Q`) also use `_` for spaces
+fn decode_quoted_printable(text: &str, header: bool) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'=' if bytes.get(i + 1) == Some(&b'\n') => i += 2, // Soft line break
+            b'=' if bytes.get(i + 1..i + 3) == Some(b"\r\n") => i += 3,
+            b'=' => match text
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                Some(byte) => {
+                    out.push(byte);
+                    i += 3;
+                }
+                None => {
+                    out.push(b'=');
+                    i += 1;
+                }
+            },
+            b'_' if header => {
+                out.push(b' ');
+                i += 1;
+            }
+            byte => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Decode RFC 2047 encoded words (`=?UTF-8?q?J=C3=B6rg?=`) in a header value
+fn decode_header(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    let mut after_encoded_word = false;
+
+    while let Some(start) = rest.find("=?") {
+        let decoded = rest[start + 2..].split_once("?=").and_then(|(word, _)| {
+            let mut parts = word.splitn(3, '?');
+            let (charset, encoding, text) = (parts.next()?, parts.next()?, parts.next()?);
+            let bytes = match encoding {
+                "Q" | "q" => decode_quoted_printable(text, true),
+                "B" | "b" => base64::engine::general_purpose::STANDARD
+                    .decode(text)
+                    .ok()?,
+                _ => return None,
+            };
+            Some((decode_charset(&bytes, Some(charset)), word.len() + 4))
+        });
+        let Some((text, word_len)) = decoded else {
+            out.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            after_encoded_word = false;
+            continue;
+        };
+        // Whitespace between two encoded words is dropped
+        let between = &rest[..start];
+        if !(after_encoded_word && between.trim().is_empty()) {
+            out.push_str(between);
+        }
+        out.push_str(&text);
+        rest = &rest[start + word_len..];
+        after_encoded_word = true;
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERIES: &str = "From 1111111111111111111111111111111111111111 Mon Sep 17 00:00:00 2001
+From: Jane Doe <jane@example.com>
+Date: Tue, 2 Jan 2024 10:00:00 +0100
+Subject: [PATCH 0/2] Cover letter
+
+Two small fixes.
+
+From 2222222222222222222222222222222222222222 Mon Sep 17 00:00:00 2001
+From: =?UTF-8?q?J=C3=B6rg?= Example <jorg@example.com>
+Date: Tue, 2 Jan 2024 10:00:00 +0100
+Message-Id: <patch-1@example.com>
+Subject: [PATCH v2 1/2] Fix the
+ first thing
+
+Longer explanation.
+
+Signed-off-by: J\u{f6}rg Example <jorg@example.com>
+---
+ a.txt | 2 +-
+ 1 file changed, 1 insertion(+), 1 deletion(-)
+
+diff --git a/a.txt b/a.txt
+index 1111111..2222222 100644
+--- a/a.txt
++++ b/a.txt
+@@ -1 +1 @@
+-one
++two
+--
+2.43.0
+
+From 3333333333333333333333333333333333333333 Mon Sep 17 00:00:00 2001
+From: Sender <sender@example.com>
+Subject: [PATCH v2 2/2] Second
+Content-Transfer-Encoding: quoted-printable
+
+From: Author <author@example.com>
+
+---
+diff --git a/b.txt b/b.txt
+--- a/b.txt
++++ b/b.txt
+@@ -1 +1 @@
+-caf=C3=A9
++coffee
+base-commit: 4444444444444444444444444444444444444444
+";
+
+    #[test]
+    fn test_parse_mbox_series() {
+        let patches = parse_mbox(SERIES).unwrap();
+        // The cover letter has no diff
+        assert_eq!(patches.len(), 2);
+
+        let first = &patches[0];
+        assert_eq!(first.subject, "[PATCH v2 1/2] Fix the first thing");
+        assert!(first.message.starts_with("Fix the first thing\n\nLonger"));
+        assert!(first
+            .message
+            .ends_with("Signed-off-by: Jörg Example <jorg@example.com>"));
+        assert_eq!(first.author.name, "Jörg Example");
+        assert_eq!(first.author.email, "jorg@example.com");
+        assert_eq!(first.author.time.to_rfc3339(), "2024-01-02T10:00:00+01:00");
+        assert_eq!(first.message_id.as_deref(), Some("<patch-1@example.com>"));
+        assert!(first.diff.starts_with("diff --git a/a.txt b/a.txt\n"));
+
+        let second = &patches[1];
+        // In-body From wins over the sender
+        assert_eq!(second.author.name, "Author");
+        assert_eq!(second.message, "Second");
+        assert!(second.diff.contains("-café\n"));
+        assert_eq!(
+            second.base_commit.as_deref(),
+            Some("4444444444444444444444444444444444444444")
+        );
+    }
+
+    #[test]
+    fn test_parse_bare_diff() {
+        let text = "Some notes\n\n--- a.txt\n+++ a.txt\n@@ -1 +1 @@\n-a\n+b\n";
+        let patches = parse_patch(text, "fix.diff").unwrap();
+        assert_eq!(patches.len(), 1);
+        assert_eq!(patches[0].message, "Some notes");
+        assert_eq!(patches[0].subject, "fix.diff");
+        assert!(patches[0].diff.starts_with("--- a.txt\n"));
+
+        assert!(parse_patch("nothing here\n", "x").is_err());
+    }
+}
@@ -0,0 +1,608 @@
+use anyhow::{Context, Result};
+use git2::{Commit, Diff, IndexEntry, MergeFileOptions, Oid, Repository, Tree};
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::diff::DiffOptions;
+use super::diff_parser::{FileDiff, Hunk, HunkLine, LineType};
+use super::encoding::TextDecoder;
+use super::patch::{split_lines, strip_line_ending};
+
+/// How a merge commit's changes are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MergeDiffMode {
+    /// Dense combined diff against all parents, like `git show --cc`
+    #[default]
+    Combined,
+    /// Plain diff against one parent (zero-based index)
+    Parent(usize),
+    /// Diff from a re-run of the merge to the recorded result, showing only
+    /// how conflicts were resolved (and any evil changes)
+    Remerge,
+}
+
+impl MergeDiffMode {
+    /// Next mode in the cycle: combined, each parent, remerge
+    pub fn next(self, parent_count: usize) -> Self {
+        match self {
+            Self::Combined => Self::Parent(0),
+            Self::Parent(n) if n + 1 < parent_count => Self::Parent(n + 1),
+            Self::Parent(_) if parent_count == 2 => Self::Remerge,
+            Self::Parent(_) | Self::Remerge => Self::Combined,
+        }
+    }
+
+    /// Short label for the header
+    pub fn label(self) -> String {
+        match self {
+            Self::Combined => "--cc".to_string(),
+            Self::Parent(n) => format!("vs parent {}", n + 1),
+            Self::Remerge => "remerge".to_string(),
+        }
+    }
+}
+
+/// Diff a commit's tree against one of its parents
+pub fn diff_against_parent<'a>(
+    repo: &'a Repository,
+    commit: &Commit,
+    parent_index: usize,
+    options: &DiffOptions,
+) -> Result<Diff<'a>> {
+    let tree = commit.tree().context("Failed to get commit tree")?;
+    let parent_tree = if commit.parent_count() > 0 {
+        let parent = commit
+            .parent(parent_index)
+            .with_context(|| format!("Commit has no parent {}", parent_index + 1))?;
+        Some(parent.tree().context("Failed to get parent tree")?)
+    } else {
+        None
+    };
+
+    let mut diff_opts = options.to_git2();
+    repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut diff_opts))
+        .context("Failed to generate diff")
+}
+
+/// Diff the recorded merge result against an automatic re-merge of its parents
+///
+/// Conflicted files in the re-merge keep their conflict markers, so the diff
+/// shows exactly how the author resolved them.
+pub fn remerge_diff<'a>(
+    repo: &'a Repository,
+    commit_oid: Oid,
+    options: &DiffOptions,
+) -> Result<Diff<'a>> {
+    let commit = repo
+        .find_commit(commit_oid)
+        .context("Failed to find commit")?;
+    if commit.parent_count() != 2 {
+        anyhow::bail!("Remerge diff needs a merge with exactly two parents");
+    }
+    let ours = commit.parent(0)?;
+    let theirs = commit.parent(1)?;
+
+    let mut index = repo
+        .merge_commits(&ours, &theirs, None)
+        .context("Failed to re-run merge")?;
+
+    // Replace each conflict with the file content including conflict markers
+    let conflicts: Vec<_> = index.conflicts()?.collect::<Result<_, _>>()?;
+    for conflict in conflicts {
+        let content = match (&conflict.ancestor, &conflict.our, &conflict.their) {
+            (ancestor, Some(our), Some(their)) => {
+                // Add/add conflicts merge against an empty file, so both
+                // sides show between conflict markers
+                let empty;
+                let base = match ancestor {
+                    Some(base) => base,
+                    None => {
+                        empty = empty_entry(repo, our)?;
+                        &empty
+                    }
+                };
+                let mut opts = MergeFileOptions::new();
+                opts.our_label(format!("{:.7}", ours.id()))
+                    .their_label(format!("{:.7}", theirs.id()));
+                repo.merge_file_from_index(base, our, their, Some(&mut opts))?
+                    .content()
+                    .to_vec()
+            }
+            // Modify/delete: keep the side that still exists
+            (_, Some(side), None) | (_, None, Some(side)) => {
+                repo.find_blob(side.id)?.content().to_vec()
+            }
+            (_, None, None) => continue,
+        };
+        let Some(mut resolved) = conflict.our.or(conflict.their) else {
+            continue;
+        };
+
+        let path = String::from_utf8_lossy(&resolved.path).into_owned();
+        resolved.id = repo.blob(&content)?;
+        resolved.file_size = content.len() as u32;
+        resolved.flags &= !0x3000; // Clear the stage bits
+        index.conflict_remove(Path::new(&path))?;
+        index.add(&resolved)?;
+    }
+
+    let remerged = repo.find_tree(index.write_tree_to(repo)?)?;
+    let tree = commit.tree()?;
+
+    let mut diff_opts = options.to_git2();
+    repo.diff_tree_to_tree(Some(&remerged), Some(&tree), Some(&mut diff_opts))
+        .context("Failed to generate remerge diff")
+}
+
+/// An empty file at the same path as `entry`, standing in for the missing
+/// common ancestor of an add/add conflict
+fn empty_entry(repo: &Repository, entry: &IndexEntry) -> Result<IndexEntry> {
+    Ok(IndexEntry {
+        ctime: entry.ctime,
+        mtime: entry.mtime,
+        dev: entry.dev,
+        ino: entry.ino,
+        mode: entry.mode,
+        uid: entry.uid,
+        gid: entry.gid,
+        file_size: 0,
+        id: repo.blob(b"")?,
+        flags: entry.flags,
+        flags_extended: entry.flags_extended,
+        path: entry.path.clone(),
+    })
+}
+
+/// A line in the combined diff with one marker column per parent
+struct CombinedLine {
+    markers: Vec<char>,
+    parent_lines: Vec<Option<usize>>, // Line number in each parent, if present there
+    new_line_num: Option<usize>,
+    content: Vec<u8>,
+}
+
+impl CombinedLine {
+    /// Dense combined diffs only show changes made relative to every parent
+    fn is_interesting(&self) -> bool {
+        self.markers.iter().all(|&m| m != ' ')
+    }
+}
+
+/// How one parent's version of a file maps onto the merge result
+struct ParentMapping {
+    added: HashSet<usize>,                 // Result lines not in this parent
+    removed: Vec<(usize, usize, Vec<u8>)>, // (anchor result line, parent line, text)
+    line_of: Vec<Option<usize>>,           // Result line -> parent line
+}
+
+/// Build a dense combined diff (`git show --cc`) for a merge commit
+///
+/// Only files that differ from every parent are included, and only hunks
+/// containing lines changed relative to all parents are kept.
+pub fn combined_diff(
+    repo: &Repository,
+    commit_oid: Oid,
+    options: &DiffOptions,
+) -> Result<Vec<FileDiff>> {
+    let commit = repo
+        .find_commit(commit_oid)
+        .context("Failed to find commit")?;
+    let tree = commit.tree()?;
+    let parent_trees: Vec<Tree> = commit
+        .parents()
+        .map(|p| p.tree())
+        .collect::<Result<_, _>>()?;
+
+    // Paths changed relative to every parent
+    let mut paths: Option<Vec<String>> = None;
+    for parent_tree in &parent_trees {
+        let mut diff_opts = options.to_git2();
+        let diff = repo.diff_tree_to_tree(Some(parent_tree), Some(&tree), Some(&mut diff_opts))?;
+        let changed: Vec<String> = diff
+            .deltas()
+            .filter_map(|d| d.new_file().path().or(d.old_file().path()))
+            .filter_map(|p| p.to_str().map(str::to_string))
+            .collect();
+        paths = Some(match paths {
+            None => changed,
+            Some(prev) => prev.into_iter().filter(|p| changed.contains(p)).collect(),
+        });
+    }
+
+    let mut files = Vec::new();
+    for path in paths.unwrap_or_default() {
+        let result = blob_content(repo, &tree, &path)?;
+        let parents: Vec<Vec<u8>> = parent_trees
+            .iter()
+            .map(|t| blob_content(repo, t, &path))
+            .collect::<Result<_>>()?;
+
+        let mut file = FileDiff {
+            old_path: path.clone(),
+            new_path: path.clone(),
+            hunks: Vec::new(),
+            old_file_lines: None,
+            new_file_lines: None,
+            old_size: None,
+            new_size: Some(result.len() as u64),
+            old_blob_id: None,
+            new_blob_id: tree
+                .get_path(Path::new(&path))
+                .ok()
+                .map(|entry| entry.id().to_string()),
+        };
+
+        let is_binary = |data: &[u8]| data.contains(&0);
+        if !is_binary(&result) && !parents.iter().any(|p| is_binary(p)) {
+            let result_lines = split_lines(&result);
+            let mappings: Vec<ParentMapping> = parents
+                .iter()
+                .map(|p| map_parent(p, &result, result_lines.len(), options))
+                .collect::<Result<_>>()?;
+            let lines = combine_lines(&result_lines, &mappings);
+            let decoder = TextDecoder::for_path(repo, &path, options.fallback_encoding);
+            file.hunks = group_hunks(
+                lines,
+                options.context_lines as usize,
+                mappings.len(),
+                &decoder,
+            );
+            file.new_file_lines = Some(result_lines.len());
+        }
+
+        if !file.hunks.is_empty() {
+            files.push(file);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Read a file's content from a tree, empty when the path does not exist
+fn blob_content(repo: &Repository, tree: &Tree, path: &str) -> Result<Vec<u8>> {
+    match tree.get_path(Path::new(path)) {
+        Ok(entry) => match entry.to_object(repo)?.into_blob() {
+            Ok(blob) => Ok(blob.content().to_vec()),
+            Err(_) => Ok(Vec::new()),
+        },
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+/// Diff one parent against the result with no context to map lines
+fn map_parent(
+    parent: &[u8],
+    result: &[u8],
+    result_len: usize,
+    options: &DiffOptions,
+) -> Result<ParentMapping> {
+    let mut opts = DiffOptions {
+        context_lines: 0,
+        whitespace: options.whitespace,
+        ..Default::default()
+    }
+    .to_git2();
+    let patch = git2::Patch::from_buffers(parent, None, result, None, Some(&mut opts))?;
+
+    let mut mapping = ParentMapping {
+        added: HashSet::new(),
+        removed: Vec::new(),
+        line_of: vec![None; result_len + 2],
+    };
+
+    let (mut old, mut new) = (1, 1);
+    for hunk_idx in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(hunk_idx)?;
+        let new_count = hunk.new_lines() as usize;
+        let old_count = hunk.old_lines() as usize;
+        // With no new lines, new_start is the line the removal follows
+        let anchor = if new_count == 0 {
+            hunk.new_start() as usize + 1
+        } else {
+            hunk.new_start() as usize
+        };
+
+        // Unchanged lines before this hunk map one to one
+        while new < anchor {
+            mapping.line_of[new] = Some(old);
+            new += 1;
+            old += 1;
+        }
+
+        for line_idx in 0..line_count {
+            let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+            let text = strip_line_ending(line.content()).to_vec();
+            match line.origin() {
+                '+' => {
+                    if let Some(n) = line.new_lineno() {
+                        mapping.added.insert(n as usize);
+                    }
+                }
+                '-' => {
+                    if let Some(n) = line.old_lineno() {
+                        mapping.removed.push((anchor, n as usize, text));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        new += new_count;
+        old += old_count;
+    }
+    while new <= result_len {
+        mapping.line_of[new] = Some(old);
+        new += 1;
+        old += 1;
+    }
+
+    Ok(mapping)
+}
+
+/// Interleave removed parent lines with result lines, one marker per parent
+fn combine_lines(result_lines: &[&[u8]], mappings: &[ParentMapping]) -> Vec<CombinedLine> {
+    let parent_count = mappings.len();
+    let mut lines = Vec::new();
+
+    for result_line in 1..=result_lines.len() + 1 {
+        // Lines removed before this result line, merging identical removals
+        let mut removed: Vec<CombinedLine> = Vec::new();
+        for (parent, mapping) in mappings.iter().enumerate() {
+            for (_, old_num, text) in mapping.removed.iter().filter(|r| r.0 == result_line) {
+                match removed
+                    .iter_mut()
+                    .find(|r| r.content == *text && r.markers[parent] == ' ')
+                {
+                    Some(existing) => {
+                        existing.markers[parent] = '-';
+                        existing.parent_lines[parent] = Some(*old_num);
+                    }
+                    None => {
+                        let mut markers = vec![' '; parent_count];
+                        let mut parent_lines = vec![None; parent_count];
+                        markers[parent] = '-';
+                        parent_lines[parent] = Some(*old_num);
+                        removed.push(CombinedLine {
+                            markers,
+                            parent_lines,
+                            new_line_num: None,
+                            content: text.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        lines.extend(removed);
+
+        if let Some(text) = result_lines.get(result_line - 1) {
+            let added = |m: &ParentMapping| m.added.contains(&result_line);
+            lines.push(CombinedLine {
+                markers: mappings
+                    .iter()
+                    .map(|m| if added(m) { '+' } else { ' ' })
+                    .collect(),
+                parent_lines: mappings
+                    .iter()
+                    .map(|m| {
+                        if added(m) {
+                            None
+                        } else {
+                            m.line_of[result_line]
+                        }
+                    })
+                    .collect(),
+                new_line_num: Some(result_line),
+                content: text.to_vec(),
+            });
+        }
+    }
+
+    lines
+}
+
+/// Group combined lines into hunks around interesting lines
+fn group_hunks(
+    lines: Vec<CombinedLine>,
+    context: usize,
+    parent_count: usize,
+    decoder: &TextDecoder,
+) -> Vec<Hunk> {
+    // Ranges of line indices to show, merged when their context overlaps
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for (idx, _) in lines.iter().enumerate().filter(|(_, l)| l.is_interesting()) {
+        let start = idx.saturating_sub(context);
+        let end = (idx + context + 1).min(lines.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|(start, end)| {
+            let slice = &lines[start..end];
+            let new_nums: Vec<usize> = slice.iter().filter_map(|l| l.new_line_num).collect();
+            let new_start = new_nums.first().copied().unwrap_or(0);
+
+            // One old range per parent, like `@@@ -a,b -c,d +e,f @@@`
+            let parent_ranges: Vec<(usize, usize)> = (0..parent_count)
+                .map(|parent| {
+                    let nums: Vec<usize> = slice
+                        .iter()
+                        .filter_map(|l| l.parent_lines[parent])
+                        .collect();
+                    (nums.first().copied().unwrap_or(0), nums.len())
+                })
+                .collect();
+            let at = "@".repeat(parent_count + 1);
+            let old_ranges: Vec<String> = parent_ranges
+                .iter()
+                .map(|(start, count)| format!("-{start},{count}"))
+                .collect();
+            let header = format!(
+                "{at} {} +{new_start},{} {at}",
+                old_ranges.join(" "),
+                new_nums.len()
+            );
+
+            Hunk {
+                old_start: parent_ranges[0].0,
+                old_lines: parent_ranges[0].1,
+                new_start,
+                new_lines: new_nums.len(),
+                header,
+                lines: slice
+                    .iter()
+                    .map(|l| HunkLine {
+                        line_type: if l.markers.contains(&'-') {
+                            LineType::Removed
+                        } else if l.markers.contains(&'+') {
+                            LineType::Added
+                        } else {
+                            LineType::Context
+                        },
+                        old_line_num: l.parent_lines.iter().find_map(|n| *n),
+                        new_line_num: l.new_line_num,
+                        content: decoder.decode(&l.content),
+                        raw: l.content.clone(),
+                        parent_markers: Some(l.markers.iter().collect()),
+                        no_newline: false,
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_repo;
+
+    /// Merge of two branches that conflict on line 2 of conflict.txt and
+    /// cleanly change other.txt on one side
+    fn conflicted_merge(repo: &Repository) -> Oid {
+        let base = test_repo::commit_on(
+            repo,
+            &[],
+            &[("conflict.txt", b"1\n2\n3\n"), ("other.txt", b"x\n")],
+            "Base",
+        );
+        let ours = test_repo::commit_on(
+            repo,
+            &[base],
+            &[("conflict.txt", b"1\nours\n3\n"), ("other.txt", b"x\n")],
+            "Ours",
+        );
+        let theirs = test_repo::commit_on(
+            repo,
+            &[base],
+            &[("conflict.txt", b"1\ntheirs\n3\n"), ("other.txt", b"y\n")],
+            "Theirs",
+        );
+        test_repo::commit_on(
+            repo,
+            &[ours, theirs],
+            &[("conflict.txt", b"1\nresolved\n3\n"), ("other.txt", b"y\n")],
+            "Merge",
+        )
+    }
+
+    #[test]
+    fn test_merge_diff_mode_cycle() {
+        let mode = MergeDiffMode::default();
+        assert_eq!(mode, MergeDiffMode::Combined);
+        let mode = mode.next(2);
+        assert_eq!(mode, MergeDiffMode::Parent(0));
+        let mode = mode.next(2);
+        assert_eq!(mode, MergeDiffMode::Parent(1));
+        let mode = mode.next(2);
+        assert_eq!(mode, MergeDiffMode::Remerge);
+        assert_eq!(mode.next(2), MergeDiffMode::Combined);
+
+        // Octopus merges skip remerge
+        assert_eq!(MergeDiffMode::Parent(2).next(3), MergeDiffMode::Combined);
+    }
+
+    #[test]
+    fn test_combined_diff() {
+        let (_dir, repo) = test_repo::init();
+        let merge = conflicted_merge(&repo);
+
+        let files = combined_diff(&repo, merge, &DiffOptions::default()).unwrap();
+
+        // other.txt matches the second parent, so it is not shown
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].new_path, "conflict.txt");
+
+        let hunk = &files[0].hunks[0];
+        assert_eq!(hunk.header, "@@@ -1,3 -1,3 +1,3 @@@");
+        let lines: Vec<(String, &str)> = hunk
+            .lines
+            .iter()
+            .map(|l| (l.parent_markers.clone().unwrap(), l.content.as_str()))
+            .collect();
+        let expected = [
+            ("  ", "1"),
+            ("- ", "ours"),
+            (" -", "theirs"),
+            ("++", "resolved"),
+            ("  ", "3"),
+        ];
+        let expected: Vec<(String, &str)> =
+            expected.iter().map(|(m, c)| (m.to_string(), *c)).collect();
+        assert_eq!(lines, expected);
+        assert_eq!(hunk.lines[3].line_type, LineType::Added);
+        assert_eq!(hunk.lines[3].new_line_num, Some(2));
+    }
+
+    #[test]
+    fn test_remerge_diff_shows_both_sides_of_add_add() {
+        let (_dir, repo) = test_repo::init();
+        let base = test_repo::commit_on(&repo, &[], &[("other.txt", b"x\n")], "Base");
+        let ours = test_repo::commit_on(&repo, &[base], &[("new.txt", b"ours\n")], "Ours");
+        let theirs = test_repo::commit_on(&repo, &[base], &[("new.txt", b"theirs\n")], "Theirs");
+        let merge = test_repo::commit_on(
+            &repo,
+            &[ours, theirs],
+            &[("new.txt", b"resolved\n")],
+            "Merge",
+        );
+
+        let diff = remerge_diff(&repo, merge, &DiffOptions::default()).unwrap();
+        let files =
+            super::super::patch::files_from_diff(&repo, &diff, &DiffOptions::default()).unwrap();
+        let text: String = files
+            .iter()
+            .flat_map(|f| &f.hunks)
+            .map(|h| String::from_utf8_lossy(&h.to_patch()).into_owned())
+            .collect();
+        assert!(text.contains("-<<<<<<< "));
+        assert!(text.contains("-ours"));
+        assert!(text.contains("-theirs"));
+        assert!(text.contains("+resolved"));
+    }
+
+    #[test]
+    fn test_remerge_diff() {
+        let (_dir, repo) = test_repo::init();
+        let merge = conflicted_merge(&repo);
+
+        let diff = remerge_diff(&repo, merge, &DiffOptions::default()).unwrap();
+        let files =
+            super::super::patch::files_from_diff(&repo, &diff, &DiffOptions::default()).unwrap();
+
+        // Only the conflict resolution shows up
+        assert!(files.iter().all(|f| f.new_path != "other.txt"));
+        let text: String = files
+            .iter()
+            .flat_map(|f| &f.hunks)
+            .map(|h| String::from_utf8_lossy(&h.to_patch()).into_owned())
+            .collect();
+        assert!(text.contains("-<<<<<<< "));
+        assert!(text.contains("-ours"));
+        assert!(text.contains("-theirs"));
+        assert!(text.contains("+resolved"));
+    }
+}
@@ -2,9 +2,23 @@ pub mod branch;
 pub mod commits;
 pub mod diff;
 pub mod diff_parser;
+pub mod encoding;
+pub mod graph;
+pub mod mbox;
+pub mod merge;
 pub mod notes;
+pub mod patch;
+pub mod range_diff;
+pub mod refs;
+pub mod virtual_diff;
 
-pub use branch::detect_base_branch;
-pub use commits::{get_commit_log, get_commit_log_range, parse_range, CommitInfo};
-pub use diff::{diff_to_text, generate_diff, DiffOptions};
-pub use diff_parser::{parse_diff, FileDiff, Hunk, HunkLine, LineType};
+pub use branch::{current_branch, detect_base_branch};
+pub use commits::{
+    get_commit_log, get_commit_log_range, parse_range, CommitInfo, LogOptions, Person,
+};
+pub use diff::{
+    commit_touches_filter, generate_diff, patch_id, tree_pair, DiffOptions, PathFilter,
+    WhitespaceMode,
+};
+pub use diff_parser::{FileDiff, Hunk, HunkLine, LineType};
+pub use patch::files_from_diff;
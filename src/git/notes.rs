@@ -120,7 +120,8 @@ pub fn read_all_for_branch(repo: &Repository, branch: &str) -> Result<Vec<Commit
                 .context("Failed to list git notes")?;
 
             for note_id in notes {
-                let (commit_oid, _note_oid) = note_id.context("Failed to read note ID")?;
+                // The iterator yields (note blob, annotated commit)
+                let (_note_oid, commit_oid) = note_id.context("Failed to read note ID")?;
 
                 if let Some(comments) = read_comments(repo, branch, commit_oid)? {
                     all_comments.push(comments);
@@ -139,6 +140,24 @@ pub fn read_all_for_branch(repo: &Repository, branch: &str) -> Result<Vec<Commit
     Ok(all_comments)
 }
 
+/// Find the commit whose notes hold the comment with the given id
+///
+/// Returns the commit and its review state, or None if no comment on the
+/// branch has that id.
+pub fn find_comment(
+    repo: &Repository,
+    branch: &str,
+    id: &str,
+) -> Result<Option<(Oid, CommitComments)>> {
+    for comments in read_all_for_branch(repo, branch)? {
+        if comments.comments.iter().any(|c| c.id == id) {
+            let oid = Oid::from_str(&comments.commit_id).context("Invalid commit id in note")?;
+            return Ok(Some((oid, comments)));
+        }
+    }
+    Ok(None)
+}
+
 /// Delete a specific comment from a commit's notes
 ///
 /// Removes the comment at the given index and rewrites the note.
@@ -173,8 +192,7 @@ pub fn delete_comment(
 /// Delete all notes (comments) for a specific commit
 ///
 /// Returns Ok(true) if deleted, Ok(false) if note didn't exist, or Err on other errors
-#[allow(dead_code)] // Used by delete_comment in Phase 2 (TUI integration)
-fn delete_commit_note(repo: &Repository, branch: &str, commit_oid: Oid) -> Result<bool> {
+pub fn delete_commit_note(repo: &Repository, branch: &str, commit_oid: Oid) -> Result<bool> {
     let ref_name = branch_ref_name(branch)?;
 
     // Get signature for the operation
@@ -232,6 +250,19 @@ pub fn clear_branch_notes(repo: &Repository, branch: &str) -> Result<usize> {
     Ok(note_count)
 }
 
+/// Get the commit the branch's notes ref currently points at
+///
+/// Returns None if no notes have been written for the branch yet. Used to
+/// detect notes written by another git-review instance.
+pub fn notes_ref_target(repo: &Repository, branch: &str) -> Result<Option<Oid>> {
+    let ref_name = branch_ref_name(branch)?;
+    match repo.find_reference(&ref_name) {
+        Ok(reference) => Ok(reference.target()),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(e).context("Failed to find notes reference"),
+    }
+}
+
 /// List all commit OIDs that have comments for a branch
 #[allow(dead_code)] // Used in Phase 2 (TUI integration)
 pub fn list_commits_with_comments(repo: &Repository, branch: &str) -> Result<Vec<Oid>> {
@@ -245,7 +276,8 @@ pub fn list_commits_with_comments(repo: &Repository, branch: &str) -> Result<Vec
                 .context("Failed to list git notes")?;
 
             for note_id in notes {
-                let (commit_oid, _note_oid) = note_id.context("Failed to read note ID")?;
+                // The iterator yields (note blob, annotated commit)
+                let (_note_oid, commit_oid) = note_id.context("Failed to read note ID")?;
                 commit_oids.push(commit_oid);
             }
         }
@@ -342,6 +374,26 @@ mod tests {
         assert_eq!(read_comments.comments[0].text, "Test comment");
     }
 
+    #[test]
+    fn test_list_notes_by_annotated_commit() {
+        let (_dir, repo) = create_test_repo();
+        let commit_oid = create_test_commit(&repo);
+
+        let mut comments = CommitComments::new(commit_oid.to_string(), "main".to_string());
+        comments
+            .add_comment(Comment::new_file("test.txt".to_string(), "Test".to_string()).unwrap());
+        write_comments(&repo, "main", commit_oid, &comments).unwrap();
+
+        // Keyed by the commit, not by the note blob holding the comments
+        let all = read_all_for_branch(&repo, "main").unwrap();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].commit_id, commit_oid.to_string());
+        assert_eq!(
+            list_commits_with_comments(&repo, "main").unwrap(),
+            vec![commit_oid]
+        );
+    }
+
     #[test]
     fn test_read_nonexistent_comments() {
         let (_dir, repo) = create_test_repo();
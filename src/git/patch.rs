@@ -0,0 +1,297 @@
+use anyhow::{Context, Result};
+use git2::{Blob, Diff, DiffDelta, DiffLineType, Oid, Patch, Repository};
+
+use super::diff::DiffOptions;
+use super::diff_parser::{FileDiff, Hunk, HunkLine, LineType};
+use super::encoding::TextDecoder;
+
+/// Build the file list for a diff straight from its patches
+///
+/// Blob contents are looked up in `repo` to record file sizes and the total
+/// line count of each version. Binary files are included without hunks.
+pub fn files_from_diff(
+    repo: &Repository,
+    diff: &Diff,
+    options: &DiffOptions,
+) -> Result<Vec<FileDiff>> {
+    let mut files = Vec::new();
+
+    for (idx, delta) in diff.deltas().enumerate() {
+        if delta.status() == git2::Delta::Unmodified {
+            continue;
+        }
+
+        let old = find_blob(repo, delta.old_file().id());
+        let new = find_blob(repo, delta.new_file().id());
+        let (old, new) = (
+            old.as_ref().map(Blob::content),
+            new.as_ref().map(Blob::content),
+        );
+        let path = delta.new_file().path().or(delta.old_file().path());
+        let decoder = match path.and_then(|p| p.to_str()) {
+            Some(path) => TextDecoder::for_path(repo, path, options.fallback_encoding),
+            None => TextDecoder::new(options.fallback_encoding),
+        };
+        let mut file = match Patch::from_diff(diff, idx).context("Failed to load patch")? {
+            Some(patch) => file_from_patch(&patch, old, new, &decoder)?,
+            None => file_from_delta(&delta, old, new),
+        };
+        file.old_blob_id = Some(delta.old_file().id().to_string());
+        file.new_blob_id = Some(delta.new_file().id().to_string());
+        files.push(file);
+    }
+
+    Ok(files)
+}
+
+/// Build a single file's diff from a patch
+///
+/// `old` and `new` are the full contents of each side, when available, and
+/// provide the file sizes and line counts. Line bytes are kept as-is and
+/// decoded for display with `decoder`.
+pub fn file_from_patch(
+    patch: &Patch,
+    old: Option<&[u8]>,
+    new: Option<&[u8]>,
+    decoder: &TextDecoder,
+) -> Result<FileDiff> {
+    let mut file = file_from_delta(&patch.delta(), old, new);
+
+    for hunk_idx in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(hunk_idx).context("Failed to read hunk")?;
+        let mut lines: Vec<HunkLine> = Vec::with_capacity(line_count);
+
+        for line_idx in 0..line_count {
+            let line = patch
+                .line_in_hunk(hunk_idx, line_idx)
+                .context("Failed to read hunk line")?;
+            let line_type = match line.origin_value() {
+                DiffLineType::Context => LineType::Context,
+                DiffLineType::Addition => LineType::Added,
+                DiffLineType::Deletion => LineType::Removed,
+                // "\ No newline at end of file" applies to the line before it
+                DiffLineType::ContextEOFNL | DiffLineType::AddEOFNL | DiffLineType::DeleteEOFNL => {
+                    if let Some(last) = lines.last_mut() {
+                        last.no_newline = true;
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+
+            let raw = strip_newline(line.content());
+            lines.push(HunkLine {
+                line_type,
+                old_line_num: line.old_lineno().map(|n| n as usize),
+                new_line_num: line.new_lineno().map(|n| n as usize),
+                content: decoder.decode(strip_line_ending(raw)),
+                raw: raw.to_vec(),
+                parent_markers: None,
+                no_newline: false,
+            });
+        }
+
+        file.hunks.push(Hunk {
+            old_start: hunk.old_start() as usize,
+            old_lines: hunk.old_lines() as usize,
+            new_start: hunk.new_start() as usize,
+            new_lines: hunk.new_lines() as usize,
+            header: decoder.decode(strip_line_ending(hunk.header())),
+            lines,
+        });
+    }
+
+    Ok(file)
+}
+
+/// File entry with paths, sizes and line counts but no hunks
+fn file_from_delta(delta: &DiffDelta, old: Option<&[u8]>, new: Option<&[u8]>) -> FileDiff {
+    let path = |file: git2::DiffFile| {
+        file.path()
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    let old_path = path(delta.old_file());
+    let new_path = path(delta.new_file());
+
+    FileDiff {
+        // Added and deleted files only have a path on one side
+        old_path: if old_path.is_empty() {
+            new_path.clone()
+        } else {
+            old_path.clone()
+        },
+        new_path: if new_path.is_empty() {
+            old_path
+        } else {
+            new_path
+        },
+        hunks: Vec::new(),
+        old_file_lines: old.map(count_lines),
+        new_file_lines: new.map(count_lines),
+        old_size: old.map(|data| data.len() as u64),
+        new_size: new.map(|data| data.len() as u64),
+        old_blob_id: None,
+        new_blob_id: None,
+    }
+}
+
+/// Look up a blob, or None for missing (all-zero) ids
+///
+/// The blob is kept so its content can be borrowed rather than copied.
+fn find_blob(repo: &Repository, id: Oid) -> Option<Blob<'_>> {
+    if id.is_zero() {
+        return None;
+    }
+    repo.find_blob(id).ok()
+}
+
+/// Number of lines in a file, counting a final line without a newline
+pub fn count_lines(data: &[u8]) -> usize {
+    let newlines = data.iter().filter(|&&b| b == b'\n').count();
+    match data.last() {
+        Some(&last) if last != b'\n' => newlines + 1,
+        _ => newlines,
+    }
+}
+
+/// Split file content into lines without their line endings (like
+/// `str::lines`, for bytes)
+pub fn split_lines(data: &[u8]) -> Vec<&[u8]> {
+    let data = data.strip_suffix(b"\n").unwrap_or(data);
+    if data.is_empty() {
+        return Vec::new();
+    }
+    data.split(|&b| b == b'\n').map(strip_line_ending).collect()
+}
+
+/// Line content without its `\n`, keeping any `\r` of a CRLF ending
+pub fn strip_newline(content: &[u8]) -> &[u8] {
+    content.strip_suffix(b"\n").unwrap_or(content)
+}
+
+/// Line content without its line ending
+pub fn strip_line_ending(content: &[u8]) -> &[u8] {
+    let content = content.strip_suffix(b"\n").unwrap_or(content);
+    content.strip_suffix(b"\r").unwrap_or(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{generate_diff, DiffOptions};
+    use crate::test_repo;
+    use std::path::Path;
+
+    #[test]
+    fn test_count_lines() {
+        assert_eq!(count_lines(b""), 0);
+        assert_eq!(count_lines(b"a\n"), 1);
+        assert_eq!(count_lines(b"a\nb"), 2);
+        assert_eq!(count_lines(b"a\nb\n"), 2);
+        assert_eq!(split_lines(b"a\r\n\nb"), vec![&b"a"[..], b"", b"b"]);
+        assert!(split_lines(b"").is_empty());
+    }
+
+    #[test]
+    fn test_files_from_diff() {
+        let (_dir, repo) = test_repo::init();
+        let long: String = (1..=30).map(|n| format!("line {n}\n")).collect();
+        let files: &[(&str, &[u8])] = &[("my file.txt", long.as_bytes()), ("tail.txt", b"a\nb")];
+        test_repo::commit(&repo, files, "Base");
+
+        let changed = long.replace("line 5\n", "line five\n");
+        let files: &[(&str, &[u8])] = &[("my file.txt", changed.as_bytes()), ("tail.txt", b"a\nc")];
+        let head = test_repo::commit(&repo, files, "Change");
+
+        let options = DiffOptions {
+            context_lines: 2,
+            ..Default::default()
+        };
+        let diff = generate_diff(&repo, head, &options).unwrap();
+        let files = files_from_diff(&repo, &diff, &options).unwrap();
+        assert_eq!(files.len(), 2);
+
+        // Paths with spaces are kept as-is, line counts cover the whole blob
+        let file = &files[0];
+        assert_eq!(file.new_path, "my file.txt");
+        assert_eq!(file.new_file_lines, Some(30));
+        assert_eq!(file.old_file_lines, Some(30));
+        assert_eq!(file.new_size, Some(changed.len() as u64));
+        assert_eq!(file.new_blob_id.as_ref().unwrap().len(), 40);
+
+        let hunk = &file.hunks[0];
+        assert_eq!((hunk.old_start, hunk.old_lines), (3, 5));
+        assert_eq!(hunk.header, "@@ -3,5 +3,5 @@ line 2");
+        let removed = &hunk.lines[2];
+        assert_eq!(removed.line_type, LineType::Removed);
+        assert_eq!(removed.content, "line 5");
+        assert_eq!(removed.old_line_num, Some(5));
+        assert!(hunk.can_expand_below(30));
+
+        // Missing newline at end of file is recorded on the affected lines
+        let tail = &files[1];
+        let lines: Vec<(LineType, &str, bool)> = tail.hunks[0]
+            .lines
+            .iter()
+            .map(|l| (l.line_type, l.content.as_str(), l.no_newline))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (LineType::Context, "a", false),
+                (LineType::Removed, "b", true),
+                (LineType::Added, "c", true),
+            ]
+        );
+        assert_eq!(tail.new_file_lines, Some(2));
+        assert!(!tail.hunks[0].can_expand_below(2));
+    }
+
+    #[test]
+    fn test_files_from_diff_keeps_raw_bytes() {
+        let (_dir, repo) = test_repo::init();
+        test_repo::commit(&repo, &[("legacy.c", b"/* caf\xe9 */\n")], "Base");
+        let head = test_repo::commit(&repo, &[("legacy.c", b"/* caf\xe9s */\n")], "Change");
+
+        let mut options = DiffOptions::default();
+        let diff = generate_diff(&repo, head, &options).unwrap();
+        let files = files_from_diff(&repo, &diff, &options).unwrap();
+        let added = &files[0].hunks[0].lines[1];
+        assert_eq!(added.raw, b"/* caf\xe9s */");
+        assert_eq!(added.content, "/* caf\\xE9s */");
+
+        options.fallback_encoding = crate::git::encoding::encoding_for_label("latin1");
+        let files = files_from_diff(&repo, &diff, &options).unwrap();
+        let hunk = &files[0].hunks[0];
+        assert_eq!(hunk.lines[1].content, "/* cafés */");
+        assert!(hunk.to_patch().ends_with(b"+/* caf\xe9s */\n"));
+    }
+
+    #[test]
+    fn test_crlf_hunk_round_trips_through_git_apply() {
+        let (_dir, repo) = test_repo::init();
+        let old: &[u8] = b"one\r\ntwo\r\nthree\r\n";
+        let new: &[u8] = b"one\r\n2\r\nthree\r\n";
+        let base = test_repo::commit(&repo, &[("dos.txt", old)], "Base");
+        let head = test_repo::commit(&repo, &[("dos.txt", new)], "Change");
+
+        let options = DiffOptions::default();
+        let diff = generate_diff(&repo, head, &options).unwrap();
+        let files = files_from_diff(&repo, &diff, &options).unwrap();
+        let hunk = &files[0].hunks[0];
+        assert_eq!(hunk.lines[1].content, "two");
+        assert_eq!(hunk.lines[1].raw, b"two\r");
+
+        let mut patch = b"diff --git a/dos.txt b/dos.txt\n--- a/dos.txt\n+++ b/dos.txt\n".to_vec();
+        patch.extend(hunk.to_patch());
+        let patch = git2::Diff::from_buffer(&patch).unwrap();
+        let base_tree = repo.find_commit(base).unwrap().tree().unwrap();
+        let mut index = repo.apply_to_tree(&base_tree, &patch, None).unwrap();
+        let tree = repo.find_tree(index.write_tree_to(&repo).unwrap()).unwrap();
+        let entry = tree.get_path(Path::new("dos.txt")).unwrap();
+        assert_eq!(repo.find_blob(entry.id()).unwrap().content(), new);
+
+        assert_eq!(files[0].apply(old).as_deref(), Some(new));
+    }
+}
@@ -0,0 +1,421 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use git2::{Diff, Oid, Repository};
+
+use super::commits::{get_commit_log_range, CommitInfo, LogOptions};
+use super::diff::{generate_diff, DiffOptions};
+use super::diff_parser::{FileDiff, Hunk};
+use super::encoding::TextDecoder;
+use super::patch::{file_from_patch, files_from_diff};
+
+/// Minimum share of changed lines two commits must have in common to be
+/// considered versions of the same patch
+const SIMILARITY_THRESHOLD: f64 = 0.4;
+
+/// How a commit changed between two versions of a patch series
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairStatus {
+    /// Same patch in both versions
+    Unchanged,
+    /// Patch exists in both versions but its change differs
+    Modified,
+    /// Patch only exists in the new version
+    Added,
+    /// Patch only exists in the old version
+    Dropped,
+}
+
+impl PairStatus {
+    /// Single character marker shown in the log pane (like `git range-diff`)
+    pub fn marker(&self) -> &'static str {
+        match self {
+            PairStatus::Unchanged => "=",
+            PairStatus::Modified => "!",
+            PairStatus::Added => "+",
+            PairStatus::Dropped => "-",
+        }
+    }
+}
+
+/// A commit from the old series paired with its counterpart in the new series
+#[derive(Debug, Clone)]
+pub struct RangeDiffPair {
+    pub old: Option<CommitInfo>,
+    pub new: Option<CommitInfo>,
+    pub status: PairStatus,
+}
+
+impl RangeDiffPair {
+    /// The commit representing this pair (new version, or old if dropped)
+    pub fn commit(&self) -> &CommitInfo {
+        self.new
+            .as_ref()
+            .or(self.old.as_ref())
+            .expect("range-diff pair has at least one commit")
+    }
+}
+
+/// Parse a `--range-diff` argument, which must be an explicit `base..tip`
+pub fn parse_series_range(range: &str) -> Result<(String, String)> {
+    match range.split_once("..") {
+        Some((base, tip)) if !base.is_empty() && !tip.is_empty() && !tip.starts_with('.') => {
+            Ok((base.to_string(), tip.to_string()))
+        }
+        _ => anyhow::bail!("Invalid range-diff range '{range}'. Use <base>..<tip>"),
+    }
+}
+
+/// Pair the commits of two versions of a patch series by patch similarity
+///
+/// Returns pairs newest first, matching the commit log order. Dropped commits
+/// are placed next to the commits they preceded in the old series.
+pub fn compute_range_diff(
+    repo: &Repository,
+    old_range: (&str, &str),
+    new_range: (&str, &str),
+) -> Result<Vec<RangeDiffPair>> {
+    // Work oldest first, like `git range-diff`
+    let log_options = LogOptions::default();
+    let mut old_commits = get_commit_log_range(repo, old_range.0, old_range.1, &log_options)?;
+    let mut new_commits = get_commit_log_range(repo, new_range.0, new_range.1, &log_options)?;
+    old_commits.reverse();
+    new_commits.reverse();
+
+    let old_patches = old_commits
+        .iter()
+        .map(|c| CommitPatch::load(repo, c.id))
+        .collect::<Result<Vec<_>>>()?;
+    let new_patches = new_commits
+        .iter()
+        .map(|c| CommitPatch::load(repo, c.id))
+        .collect::<Result<Vec<_>>>()?;
+
+    // new index -> (old index, status)
+    let mut matches: Vec<Option<(usize, PairStatus)>> = vec![None; new_commits.len()];
+    let mut old_used = vec![false; old_commits.len()];
+
+    // Exact matches first
+    for (n, new_patch) in new_patches.iter().enumerate() {
+        if let Some(o) = (0..old_patches.len())
+            .find(|&o| !old_used[o] && old_patches[o].patch_id == new_patch.patch_id)
+        {
+            old_used[o] = true;
+            matches[n] = Some((o, PairStatus::Unchanged));
+        }
+    }
+
+    // Then the most similar remaining patches, best scores first
+    let mut candidates = Vec::new();
+    for (n, new_patch) in new_patches.iter().enumerate() {
+        if matches[n].is_some() {
+            continue;
+        }
+        for (o, old_patch) in old_patches.iter().enumerate() {
+            if old_used[o] {
+                continue;
+            }
+            let same_subject = old_commits[o].summary() == new_commits[n].summary();
+            let score = old_patch.similarity(new_patch);
+            if same_subject || score >= SIMILARITY_THRESHOLD {
+                // Matching subjects win ties
+                candidates.push((score + if same_subject { 1.0 } else { 0.0 }, n, o));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+    for (_, n, o) in candidates {
+        if matches[n].is_none() && !old_used[o] {
+            old_used[o] = true;
+            matches[n] = Some((o, PairStatus::Modified));
+        }
+    }
+
+    // Emit pairs in new-series order, slotting in dropped commits
+    let mut pairs = Vec::new();
+    let mut next_old = 0;
+    let mut emit_dropped_before = |limit: usize, pairs: &mut Vec<RangeDiffPair>| {
+        while next_old < limit {
+            if !old_used[next_old] {
+                pairs.push(RangeDiffPair {
+                    old: Some(old_commits[next_old].clone()),
+                    new: None,
+                    status: PairStatus::Dropped,
+                });
+            }
+            next_old += 1;
+        }
+    };
+
+    for (n, new_commit) in new_commits.iter().enumerate() {
+        match matches[n] {
+            Some((o, status)) => {
+                emit_dropped_before(o, &mut pairs);
+                pairs.push(RangeDiffPair {
+                    old: Some(old_commits[o].clone()),
+                    new: Some(new_commit.clone()),
+                    status,
+                });
+            }
+            None => pairs.push(RangeDiffPair {
+                old: None,
+                new: Some(new_commit.clone()),
+                status: PairStatus::Added,
+            }),
+        }
+    }
+    emit_dropped_before(old_commits.len(), &mut pairs);
+
+    pairs.reverse();
+    Ok(pairs)
+}
+
+/// Diff the patches of two commits file by file
+///
+/// Each returned file holds the diff between the old and new version of that
+/// file's hunks. Lines are prefixed twice: the outer marker shows how the
+/// patch changed, the inner one is the original patch line.
+pub fn diff_of_diffs(
+    repo: &Repository,
+    old_oid: Oid,
+    new_oid: Oid,
+    options: &DiffOptions,
+) -> Result<Vec<FileDiff>> {
+    let old_files = commit_files(repo, old_oid, options)?;
+    let new_files = commit_files(repo, new_oid, options)?;
+
+    let mut paths: Vec<&str> = old_files
+        .iter()
+        .chain(new_files.iter())
+        .map(|f| f.new_path.as_str())
+        .collect();
+    paths.sort_unstable();
+    paths.dedup();
+
+    let patch_text = |files: &[FileDiff], path: &str| -> Vec<u8> {
+        files
+            .iter()
+            .find(|f| f.new_path == path)
+            .map(|f| f.hunks.iter().flat_map(hunk_text).collect())
+            .unwrap_or_default()
+    };
+
+    let mut git_opts = options.to_git2();
+
+    let mut result = Vec::new();
+    for path in paths {
+        let old_text = patch_text(&old_files, path);
+        let new_text = patch_text(&new_files, path);
+        if old_text == new_text {
+            continue;
+        }
+
+        let patch = git2::Patch::from_buffers(
+            &old_text,
+            Some(std::path::Path::new(path)),
+            &new_text,
+            Some(std::path::Path::new(path)),
+            Some(&mut git_opts),
+        )
+        .context("Failed to diff patches")?;
+        // Line numbers refer to patch text, not the file; no expansion
+        let decoder = TextDecoder::for_path(repo, path, options.fallback_encoding);
+        let file = file_from_patch(&patch, None, None, &decoder)?;
+        if !file.hunks.is_empty() {
+            result.push(file);
+        }
+    }
+
+    Ok(result)
+}
+
+/// A hunk as patch text without the line numbers in its header
+///
+/// Like `git range-diff`, only the function context after `@@` is kept, so
+/// a hunk that merely moved because of upstream changes compares equal.
+fn hunk_text(hunk: &Hunk) -> Vec<u8> {
+    let patch = hunk.to_patch();
+    let context = hunk
+        .header
+        .strip_prefix("@@")
+        .and_then(|header| header.split_once("@@"))
+        .map_or("", |(_, context)| context);
+    let mut text = format!("@@{context}\n").into_bytes();
+    text.extend_from_slice(&patch[hunk.header.len() + 1..]);
+    text
+}
+
+/// Diff the trees of two commits directly (the interdiff)
+pub fn interdiff<'a>(
+    repo: &'a Repository,
+    old_oid: Oid,
+    new_oid: Oid,
+    options: &DiffOptions,
+) -> Result<Diff<'a>> {
+    let old_tree = repo.find_commit(old_oid)?.tree()?;
+    let new_tree = repo.find_commit(new_oid)?.tree()?;
+
+    let mut diff_opts = options.to_git2();
+    repo.diff_tree_to_tree(Some(&old_tree), Some(&new_tree), Some(&mut diff_opts))
+        .context("Failed to generate interdiff")
+}
+
+/// Parsed diff of a single commit against its parent
+fn commit_files(repo: &Repository, oid: Oid, options: &DiffOptions) -> Result<Vec<FileDiff>> {
+    let diff = generate_diff(repo, oid, options)?;
+    files_from_diff(repo, &diff, options)
+}
+
+/// Data used to compare one commit's patch with another's
+struct CommitPatch {
+    patch_id: Oid,
+    changed_lines: HashMap<String, usize>,
+    total: usize,
+}
+
+impl CommitPatch {
+    fn load(repo: &Repository, oid: Oid) -> Result<Self> {
+        let options = DiffOptions {
+            context_lines: 3,
+            ..Default::default()
+        };
+        let diff = generate_diff(repo, oid, &options)?;
+        let patch_id = diff.patchid(None).context("Failed to compute patch id")?;
+
+        let mut changed_lines: HashMap<String, usize> = HashMap::new();
+        let mut total = 0;
+        for file in files_from_diff(repo, &diff, &options)? {
+            for line in file.hunks.iter().flat_map(|h| &h.lines) {
+                if line.line_type != super::LineType::Context {
+                    let key = format!("{:?}{}", line.line_type, line.content.trim_end());
+                    *changed_lines.entry(key).or_default() += 1;
+                    total += 1;
+                }
+            }
+        }
+
+        Ok(Self {
+            patch_id,
+            changed_lines,
+            total,
+        })
+    }
+
+    /// Fraction of changed lines shared with another patch (0.0 to 1.0)
+    fn similarity(&self, other: &CommitPatch) -> f64 {
+        let larger = self.total.max(other.total);
+        if larger == 0 {
+            return 0.0;
+        }
+        let shared: usize = self
+            .changed_lines
+            .iter()
+            .map(|(line, count)| (*count).min(other.changed_lines.get(line).copied().unwrap_or(0)))
+            .sum();
+        shared as f64 / larger as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_repo;
+
+    #[test]
+    fn test_compute_range_diff() {
+        let (_dir, repo) = test_repo::init();
+
+        let base = test_repo::commit_on(&repo, &[], &[("base.txt", b"base\n")], "Base");
+
+        // v1: A, B, C
+        let a1 = test_repo::commit_on(&repo, &[base], &[("a.txt", b"a\n")], "Add a");
+        let b1 = test_repo::commit_on(&repo, &[a1], &[("b.txt", b"b1\nb2\nb3\n")], "Add b");
+        let c1 = test_repo::commit_on(&repo, &[b1], &[("c.txt", b"c\n")], "Add c");
+
+        // v2: A (same change), B (reworked and retitled), D (new); C dropped
+        let a2 = test_repo::commit_on(&repo, &[base], &[("a.txt", b"a\n")], "Add a");
+        let b2 = test_repo::commit_on(
+            &repo,
+            &[a2],
+            &[("b.txt", b"b1\nb2 changed\nb3\n")],
+            "Introduce b",
+        );
+        let d2 = test_repo::commit_on(&repo, &[b2], &[("d.txt", b"d\n")], "Add d");
+
+        let (base, c1, d2) = (base.to_string(), c1.to_string(), d2.to_string());
+        let pairs = compute_range_diff(&repo, (&base, &c1), (&base, &d2)).unwrap();
+
+        let statuses: Vec<_> = pairs.iter().map(|p| p.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                PairStatus::Dropped,
+                PairStatus::Added,
+                PairStatus::Modified,
+                PairStatus::Unchanged,
+            ]
+        );
+        assert_eq!(pairs[0].commit().summary(), "Add c");
+        assert_eq!(pairs[2].old.as_ref().unwrap().summary(), "Add b");
+        assert_eq!(pairs[2].new.as_ref().unwrap().summary(), "Introduce b");
+
+        // The diff of diffs shows only the reworked line
+        let modified = &pairs[2];
+        let files = diff_of_diffs(
+            &repo,
+            modified.old.as_ref().unwrap().id,
+            modified.new.as_ref().unwrap().id,
+            &DiffOptions {
+                context_lines: 3,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].new_path, "b.txt");
+        let changed: Vec<_> = files[0].hunks[0]
+            .lines
+            .iter()
+            .filter(|l| l.line_type != super::super::LineType::Context)
+            .map(|l| l.content.as_str())
+            .collect();
+        assert_eq!(changed, vec!["+b2", "+b2 changed"]);
+    }
+
+    #[test]
+    fn test_diff_of_diffs_ignores_shifted_hunks() {
+        let (_dir, repo) = test_repo::init();
+        let lines: Vec<String> = (1..=20).map(|n| format!("{n}\n")).collect();
+        let file = |lines: &[String]| lines.concat().into_bytes();
+
+        let base = test_repo::commit_on(&repo, &[], &[("a.txt", &file(&lines))], "Base");
+        let mut changed = lines.clone();
+        changed[9] = "ten\n".to_string();
+        let v1 = test_repo::commit_on(&repo, &[base], &[("a.txt", &file(&changed))], "Ten");
+
+        // Upstream adds lines at the top; the same change rebased onto it
+        let upstream_lines = [vec!["0\n".to_string(); 3], lines].concat();
+        let upstream = test_repo::commit_on(
+            &repo,
+            &[base],
+            &[("a.txt", &file(&upstream_lines))],
+            "Upstream",
+        );
+        let rebased = [vec!["0\n".to_string(); 3], changed].concat();
+        let v2 = test_repo::commit_on(&repo, &[upstream], &[("a.txt", &file(&rebased))], "Ten");
+
+        let files = diff_of_diffs(&repo, v1, v2, &DiffOptions::default()).unwrap();
+        assert!(files.is_empty());
+    }
+
+    #[test]
+    fn test_parse_series_range() {
+        let (base, tip) = parse_series_range("v1-base..v1").unwrap();
+        assert_eq!(base, "v1-base");
+        assert_eq!(tip, "v1");
+
+        assert!(parse_series_range("v1").is_err());
+        assert!(parse_series_range("..v1").is_err());
+        assert!(parse_series_range("a...b").is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use anyhow::Result;
+use git2::{BranchType, Oid, Repository};
+use std::collections::{HashMap, HashSet};
+
+/// Collect ref names pointing at each commit, formatted like `git log --decorate`
+///
+/// Local branches are listed first (the checked-out one as `HEAD -> name`),
+/// then remote-tracking branches, then tags as `tag: name`.
+pub fn ref_decorations(repo: &Repository) -> Result<HashMap<Oid, Vec<String>>> {
+    let mut decorations: HashMap<Oid, Vec<(u8, String)>> = HashMap::new();
+
+    let head = repo.head().ok();
+    let head_branch = head
+        .as_ref()
+        .filter(|h| h.is_branch())
+        .and_then(|h| h.name().map(str::to_string));
+    if let Some(oid) = head
+        .as_ref()
+        .filter(|h| !h.is_branch())
+        .and_then(|h| h.target())
+    {
+        decorations
+            .entry(oid)
+            .or_default()
+            .push((0, "HEAD".to_string()));
+    }
+
+    for reference in repo.references()? {
+        let reference = reference?;
+        let Some(name) = reference.name() else {
+            continue;
+        };
+        let Ok(commit) = reference.peel_to_commit() else {
+            continue;
+        };
+
+        let label = if let Some(branch) = name.strip_prefix("refs/heads/") {
+            if head_branch.as_deref() == Some(name) {
+                (0, format!("HEAD -> {branch}"))
+            } else {
+                (1, branch.to_string())
+            }
+        } else if let Some(remote) = name.strip_prefix("refs/remotes/") {
+            if remote.ends_with("/HEAD") {
+                continue;
+            }
+            (2, remote.to_string())
+        } else if let Some(tag) = name.strip_prefix("refs/tags/") {
+            (3, format!("tag: {tag}"))
+        } else {
+            continue;
+        };
+
+        decorations.entry(commit.id()).or_default().push(label);
+    }
+
+    Ok(decorations
+        .into_iter()
+        .map(|(oid, mut labels)| {
+            labels.sort();
+            (oid, labels.into_iter().map(|(_, label)| label).collect())
+        })
+        .collect())
+}
+
+/// Commits on the checked-out branch that its upstream does not contain
+///
+/// Returns None when HEAD is detached or the branch has no upstream, since
+/// there is nothing to compare against.
+pub fn unpushed_commits(repo: &Repository) -> Result<Option<HashSet<Oid>>> {
+    let head = repo.head()?;
+    if !head.is_branch() {
+        return Ok(None);
+    }
+    let Some(name) = head.shorthand() else {
+        return Ok(None);
+    };
+    let Ok(upstream) = repo.find_branch(name, BranchType::Local)?.upstream() else {
+        return Ok(None);
+    };
+    let (Some(head_oid), Some(upstream_oid)) = (head.target(), upstream.get().target()) else {
+        return Ok(None);
+    };
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head_oid)?;
+    revwalk.hide(upstream_oid)?;
+    Ok(Some(revwalk.collect::<Result<_, _>>()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_repo;
+
+    #[test]
+    fn test_decorations_and_unpushed() {
+        let (_dir, repo) = test_repo::init();
+
+        let pushed = test_repo::commit_on(&repo, &[], &[], "Pushed");
+        let local = test_repo::commit_on(&repo, &[pushed], &[], "Local only");
+
+        repo.reference("refs/heads/main", local, true, "").unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        repo.reference("refs/remotes/origin/main", pushed, true, "")
+            .unwrap();
+        repo.reference("refs/tags/v1.0", pushed, true, "").unwrap();
+
+        // No upstream configured yet
+        assert_eq!(unpushed_commits(&repo).unwrap(), None);
+
+        let mut config = repo.config().unwrap();
+        config.set_str("remote.origin.url", "/dev/null").unwrap();
+        config
+            .set_str("remote.origin.fetch", "+refs/heads/*:refs/remotes/origin/*")
+            .unwrap();
+        config.set_str("branch.main.remote", "origin").unwrap();
+        config
+            .set_str("branch.main.merge", "refs/heads/main")
+            .unwrap();
+
+        let unpushed = unpushed_commits(&repo).unwrap().unwrap();
+        assert!(unpushed.contains(&local));
+        assert!(!unpushed.contains(&pushed));
+
+        let decorations = ref_decorations(&repo).unwrap();
+        assert_eq!(decorations[&local], vec!["HEAD -> main"]);
+        assert_eq!(decorations[&pushed], vec!["origin/main", "tag: v1.0"]);
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use git2::{ObjectType, Oid, Patch, Pathspec, PathspecFlags, Repository};
+
+use super::commits::{parse_trailers, CommitInfo};
+use super::diff::DiffOptions;
+use super::diff_parser::{parse_diff, FileDiff};
+use super::encoding::TextDecoder;
+use super::mbox::PatchMessage;
+use super::patch::file_from_patch;
+
+/// Where the diff of a file outside the repository's history comes from
+#[derive(Debug, Clone)]
+pub enum FileSource {
+    /// Full contents of both versions (None where the file doesn't exist),
+    /// so the diff can be regenerated with any context
+    Contents {
+        old: Option<Vec<u8>>,
+        new: Option<Vec<u8>>,
+    },
+    /// Only the hunks of a patch, shown as they are
+    Hunks(FileDiff),
+}
+
+/// One file of a virtual commit
+#[derive(Debug, Clone)]
+pub struct VirtualFile {
+    pub old_path: String,
+    pub new_path: String,
+    pub source: FileSource,
+}
+
+/// A reviewable change that isn't a commit in the repository, like a
+/// patch read from an email
+#[derive(Debug, Clone)]
+pub struct VirtualCommit {
+    pub commit: CommitInfo,
+    pub files: Vec<VirtualFile>,
+}
+
+impl VirtualCommit {
+    /// Whether every file's contents are known, so context can be expanded
+    pub fn has_contents(&self) -> bool {
+        self.files
+            .iter()
+            .all(|f| matches!(f.source, FileSource::Contents { .. }))
+    }
+}
+
+/// Build the diff of a virtual commit's files with the given options
+///
+/// Files with known contents are diffed by git2 like any commit; files
+/// only known from their hunks ignore the context and whitespace settings.
+pub fn files_from_virtual(files: &[VirtualFile], options: &DiffOptions) -> Result<Vec<FileDiff>> {
+    let pathspec = match options.paths.is_empty() {
+        true => None,
+        false => Some(Pathspec::new(options.paths.pathspecs()).context("Invalid pathspec")?),
+    };
+    let decoder = TextDecoder::new(options.fallback_encoding);
+
+    let mut diffs = Vec::new();
+    for file in files {
+        if let Some(pathspec) = &pathspec {
+            let matches =
+                |path: &str| pathspec.matches_path(Path::new(path), PathspecFlags::DEFAULT);
+            if !matches(&file.new_path) && !matches(&file.old_path) {
+                continue;
+            }
+        }
+
+        let diff = match &file.source {
+            FileSource::Hunks(diff) => diff.clone(),
+            FileSource::Contents { old, new } => {
+                let mut git2_options = options.to_git2();
+                let patch = Patch::from_buffers(
+                    old.as_deref().unwrap_or_default(),
+                    Some(Path::new(&file.old_path)),
+                    new.as_deref().unwrap_or_default(),
+                    Some(Path::new(&file.new_path)),
+                    Some(&mut git2_options),
+                )
+                .context("Failed to diff file contents")?;
+                let mut diff = file_from_patch(&patch, old.as_deref(), new.as_deref(), &decoder)?;
+                diff.old_blob_id = old
+                    .as_deref()
+                    .map(blob_oid)
+                    .transpose()?
+                    .map(|id| id.to_string());
+                diff.new_blob_id = new
+                    .as_deref()
+                    .map(blob_oid)
+                    .transpose()?
+                    .map(|id| id.to_string());
+                diff
+            }
+        };
+        diffs.push(diff);
+    }
+    Ok(diffs)
+}
+
+/// Turn a patch series into virtual commits, newest first like the log
+///
+/// Each file's old version is taken from an earlier patch in the series,
+/// the `base` commit, or the blob named on the patch's `index` line. When
+/// one is found and the hunks apply to it, the file's full contents are
+/// kept so context can be expanded; otherwise only its hunks are.
+pub fn commits_from_patches(
+    repo: &Repository,
+    patches: &[PatchMessage],
+    base: Option<Oid>,
+) -> Result<Vec<VirtualCommit>> {
+    let base_tree = match base {
+        Some(oid) => Some(repo.find_commit(oid)?.tree()?),
+        None => None,
+    };
+    // Contents left by the patches so far (None once deleted)
+    let mut series_contents: HashMap<String, Option<Vec<u8>>> = HashMap::new();
+    let mut parent = base;
+    let mut commits = Vec::new();
+
+    for patch in patches {
+        let mut files = Vec::new();
+        for diff in parse_diff(&patch.diff)? {
+            let old = match series_contents.get(&diff.old_path) {
+                Some(content) => content.clone(),
+                None => base_tree
+                    .as_ref()
+                    .and_then(|tree| tree.get_path(Path::new(&diff.old_path)).ok())
+                    .and_then(|entry| repo.find_blob(entry.id()).ok())
+                    .or_else(|| find_blob(repo, diff.old_blob_id.as_deref()))
+                    .map(|blob| blob.content().to_vec()),
+            };
+            // Hunks starting at line 0 add the whole file
+            let is_added = is_zero_id(diff.old_blob_id.as_deref())
+                || (!diff.hunks.is_empty()
+                    && diff
+                        .hunks
+                        .iter()
+                        .all(|h| h.old_start == 0 && h.old_lines == 0));
+            let is_deleted = is_zero_id(diff.new_blob_id.as_deref());
+            let old = if is_added { Some(Vec::new()) } else { old };
+            // Binary patches change the content without any hunks
+            let is_binary = diff.hunks.is_empty() && diff.old_blob_id != diff.new_blob_id;
+
+            let new = match is_binary {
+                true => None,
+                false => old.as_deref().and_then(|old| diff.apply(old)),
+            };
+            let source = match (old, new) {
+                (Some(old), Some(new)) => FileSource::Contents {
+                    old: (!is_added).then_some(old),
+                    new: (!is_deleted).then_some(new),
+                },
+                _ => FileSource::Hunks(diff.clone()),
+            };
+
+            if diff.old_path != diff.new_path {
+                series_contents.insert(diff.old_path.clone(), None);
+            }
+            match &source {
+                FileSource::Contents { new, .. } => {
+                    series_contents.insert(diff.new_path.clone(), new.clone());
+                }
+                FileSource::Hunks(_) => {
+                    series_contents.remove(&diff.new_path);
+                }
+            }
+            files.push(VirtualFile {
+                old_path: diff.old_path,
+                new_path: diff.new_path,
+                source,
+            });
+        }
+
+        let id = blob_oid(format!("{}\n{}", patch.message, patch.diff).as_bytes())?;
+        commits.push(VirtualCommit {
+            commit: CommitInfo {
+                id,
+                short_id: format!("{id:.7}"),
+                message: patch.message.clone(),
+                author: patch.author.clone(),
+                committer: patch.author.clone(),
+                parent_ids: parent.into_iter().collect(),
+                trailers: parse_trailers(&patch.message),
+                graph: String::new(),
+            },
+            files,
+        });
+        parent = Some(id);
+    }
+
+    commits.reverse();
+    Ok(commits)
+}
+
+fn blob_oid(content: &[u8]) -> Result<Oid> {
+    Ok(Oid::hash_object(ObjectType::Blob, content)?)
+}
+
+fn is_zero_id(id: Option<&str>) -> bool {
+    id.is_some_and(|id| id.chars().all(|c| c == '0'))
+}
+
+/// Look up a blob by a possibly abbreviated id
+fn find_blob<'a>(repo: &'a Repository, id: Option<&str>) -> Option<git2::Blob<'a>> {
+    let id = id.filter(|id| !is_zero_id(Some(id)))?;
+    let short = Oid::from_str(id).ok()?;
+    let oid = repo.odb().ok()?.exists_prefix(short, id.len()).ok()?;
+    repo.find_blob(oid).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::mbox::parse_patch;
+    use crate::test_repo;
+
+    #[test]
+    fn test_series_expands_from_base_and_earlier_patches() {
+        let base: String = (1..=20).map(|i| format!("{i}\n")).collect();
+        let (_dir, repo) = test_repo::init();
+        let base_oid = test_repo::commit(&repo, &[("a.txt", base.as_bytes())], "Base");
+        let text = "From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001
+From: A <a@example.com>
+Subject: [PATCH 1/2] One
+
+---
+diff --git a/a.txt b/a.txt
+--- a/a.txt
++++ b/a.txt
+@@ -10 +10 @@
+-10
++ten
+
+From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001
+From: A <a@example.com>
+Subject: [PATCH 2/2] Two
+
+---
+diff --git a/a.txt b/a.txt
+--- a/a.txt
++++ b/a.txt
+@@ -10,2 +10,2 @@
+ ten
+-11
++eleven
+diff --git a/b.txt b/b.txt
+--- a/b.txt
++++ b/b.txt
+@@ -1 +1 @@
+-unknown
++file
+";
+        let patches = parse_patch(text, "series").unwrap();
+        let commits = commits_from_patches(&repo, &patches, Some(base_oid)).unwrap();
+        assert_eq!(commits.len(), 2);
+        // Newest first, chained onto the base
+        assert_eq!(commits[0].commit.summary(), "Two");
+        assert_eq!(commits[0].commit.parent_ids, vec![commits[1].commit.id]);
+        assert_eq!(commits[1].commit.parent_ids, vec![base_oid]);
+        assert!(commits[1].has_contents());
+        // b.txt isn't in the base
+        assert!(!commits[0].has_contents());
+
+        let options = DiffOptions {
+            context_lines: 3,
+            ..Default::default()
+        };
+        let files = files_from_virtual(&commits[0].files, &options).unwrap();
+        let hunk = &files[0].hunks[0];
+        assert_eq!(hunk.old_start, 8);
+        assert_eq!(hunk.lines.len(), 8);
+        assert_eq!(files[0].new_file_lines, Some(20));
+        assert_eq!(files[1].hunks[0].lines.len(), 2);
+    }
+}
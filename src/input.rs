@@ -1,7 +1,7 @@
-use crate::app::{App, CommentMode, SearchMode};
+use crate::app::{App, CommentMode, FilterMode, Pane, SearchMode, YankTarget};
 use crate::config::DiffMode;
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 
 /// Handle keyboard input
 ///
@@ -12,11 +12,21 @@ pub fn handle_key_event(key: KeyEvent, app: &mut App) -> Result<bool> {
         return handle_search_input(key, app);
     }
 
+    // Handle file filter input
+    if app.file_filter_mode == FilterMode::Entering {
+        return handle_filter_input(key, app);
+    }
+
     // Handle comment dialog input
     if matches!(app.comment_mode, CommentMode::Creating { .. }) {
         return crate::ui::comment_dialog::handle_key(key, app);
     }
 
+    // Handle verdict dialog input
+    if matches!(app.comment_mode, CommentMode::Verdict { .. }) {
+        return crate::ui::comment_dialog::handle_verdict_key(key, app);
+    }
+
     // When viewing comments, only allow ESC to close
     if matches!(app.comment_mode, CommentMode::ViewingComments(_)) {
         if matches!(key.code, KeyCode::Esc) {
@@ -40,17 +50,57 @@ pub fn handle_key_event(key: KeyEvent, app: &mut App) -> Result<bool> {
         }
     }
 
+    // Second key of a yank: what to copy
+    if app.yank_pending {
+        app.yank_pending = false;
+        let target = match key.code {
+            KeyCode::Char('y') | KeyCode::Char('l') => YankTarget::Line,
+            KeyCode::Char('h') => YankTarget::Hunk,
+            KeyCode::Char('p') => YankTarget::Location,
+            KeyCode::Char('c') => YankTarget::CommitId,
+            _ => return Ok(false), // Anything else cancels
+        };
+        app.yank(target);
+        return Ok(false);
+    }
+
+    // Messages last until the next key
+    app.clear_status_message();
+
     match (key.code, key.modifiers) {
         // Quit
         (KeyCode::Char('q'), KeyModifiers::NONE) | (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
             return Ok(true);
         }
+        // Quit, skipping any files `git difftool` would open next
+        (KeyCode::Char('Q'), KeyModifiers::SHIFT) => {
+            app.stop_requested = true;
+            return Ok(true);
+        }
 
         // Toggle log pane
         (KeyCode::Char(' '), KeyModifiers::NONE) => {
             app.toggle_log_pane();
         }
 
+        // Toggle commit details pane
+        (KeyCode::Char('m'), KeyModifiers::NONE) => {
+            app.toggle_details();
+        }
+
+        // Whitespace: cycle ignore mode, toggle error highlighting
+        (KeyCode::Char('w'), KeyModifiers::NONE) => {
+            app.cycle_whitespace_mode();
+        }
+        (KeyCode::Char('W'), KeyModifiers::SHIFT) => {
+            app.toggle_whitespace_errors();
+        }
+
+        // Cycle merge diff mode (combined, per parent, remerge)
+        (KeyCode::Char('M'), KeyModifiers::SHIFT) => {
+            app.cycle_merge_diff_mode();
+        }
+
         // Diff mode switching
         (KeyCode::Char('s'), KeyModifiers::NONE) | (KeyCode::Char('S'), KeyModifiers::SHIFT) => {
             app.set_diff_mode(DiffMode::SideBySide);
@@ -59,25 +109,76 @@ pub fn handle_key_event(key: KeyEvent, app: &mut App) -> Result<bool> {
             app.set_diff_mode(DiffMode::Inline);
         }
 
-        // Navigation - Vertical
+        // Pane focus and log pane size
+        (KeyCode::Tab, KeyModifiers::NONE) => {
+            app.cycle_focus();
+        }
+        (KeyCode::Char('<'), _) => {
+            app.resize_log_pane(-0.05);
+        }
+        (KeyCode::Char('>'), _) => {
+            app.resize_log_pane(0.05);
+        }
+
+        // Navigation - Commit list (log pane focused)
+        (KeyCode::Char('j'), KeyModifiers::NONE) | (KeyCode::Down, KeyModifiers::NONE)
+            if app.focus == Pane::Log =>
+        {
+            app.next_commit();
+        }
+        (KeyCode::Char('k'), KeyModifiers::NONE) | (KeyCode::Up, KeyModifiers::NONE)
+            if app.focus == Pane::Log =>
+        {
+            app.previous_commit();
+        }
+        (KeyCode::Char('g'), KeyModifiers::NONE) | (KeyCode::Home, KeyModifiers::NONE)
+            if app.focus == Pane::Log =>
+        {
+            app.select_commit_edge(false);
+        }
+        (KeyCode::Char('G'), KeyModifiers::SHIFT) | (KeyCode::End, KeyModifiers::NONE)
+            if app.focus == Pane::Log =>
+        {
+            app.select_commit_edge(true);
+        }
+        (KeyCode::Enter, KeyModifiers::NONE) if app.focus == Pane::Log => {
+            app.focus = Pane::Diff;
+        }
+
+        // Navigation - Vertical (moves the cursor, scrolling at the edges)
         (KeyCode::Char('j'), KeyModifiers::NONE) | (KeyCode::Down, KeyModifiers::NONE) => {
-            app.scroll(1);
+            app.move_cursor(1);
         }
         (KeyCode::Char('k'), KeyModifiers::NONE) | (KeyCode::Up, KeyModifiers::NONE) => {
-            app.scroll(-1);
+            app.move_cursor(-1);
+        }
+        (KeyCode::Char('g'), KeyModifiers::NONE) | (KeyCode::Home, KeyModifiers::NONE) => {
+            app.move_cursor_to(0);
+        }
+        (KeyCode::Char('G'), KeyModifiers::SHIFT) | (KeyCode::End, KeyModifiers::NONE) => {
+            app.move_cursor_to(usize::MAX);
+        }
+        (KeyCode::Char('d'), KeyModifiers::CONTROL) => {
+            app.scroll_half_page(1);
+        }
+        (KeyCode::Char('u'), KeyModifiers::CONTROL) => {
+            app.scroll_half_page(-1);
         }
 
-        // Navigation - Horizontal (side-by-side mode only)
+        // Navigation - Horizontal
+        // Side by side, the cursor switches sides at the edge of a line
         (KeyCode::Char('h'), KeyModifiers::NONE) | (KeyCode::Left, KeyModifiers::NONE) => {
-            if app.config.display.diff_mode == DiffMode::SideBySide {
-                let amount = app.config.display.horizontal_scroll_amount as isize;
-                app.scroll_horizontal(-amount);
+            let amount = app.config.display.horizontal_scroll_amount as usize;
+            match app.config.display.diff_mode {
+                DiffMode::SideBySide => app.move_left(amount),
+                DiffMode::Inline => app.scroll_horizontal(-(amount as isize)),
             }
         }
         (KeyCode::Char('l'), KeyModifiers::NONE) | (KeyCode::Right, KeyModifiers::NONE) => {
-            if app.config.display.diff_mode == DiffMode::SideBySide {
-                let amount = app.config.display.horizontal_scroll_amount as isize;
-                app.scroll_horizontal(amount);
+            let amount = app.config.display.horizontal_scroll_amount as usize;
+            match app.config.display.diff_mode {
+                DiffMode::SideBySide => app.move_right(amount, side_content_width(app)),
+                DiffMode::Inline => app.scroll_horizontal(amount as isize),
             }
         }
 
@@ -128,6 +229,40 @@ pub fn handle_key_event(key: KeyEvent, app: &mut App) -> Result<bool> {
             }
         }
 
+        // Record a verdict for the current commit
+        (KeyCode::Char('a'), KeyModifiers::NONE) => {
+            app.start_verdict();
+        }
+
+        // Range-diff: toggle diff-of-diffs / interdiff
+        (KeyCode::Char('t'), KeyModifiers::NONE) if app.range_diff.is_some() => {
+            app.toggle_interdiff();
+        }
+
+        // Mark current file as viewed
+        (KeyCode::Char('x'), KeyModifiers::NONE) => {
+            if let Err(e) = app.toggle_file_viewed() {
+                app.status_message = Some(format!("Failed to save viewed mark: {e}"));
+            }
+        }
+
+        // Yank: copies the visual selection, or waits for what to copy
+        (KeyCode::Char('y'), KeyModifiers::NONE) => {
+            if app.visual_anchor.is_some() {
+                app.yank(YankTarget::Selection);
+            } else {
+                app.yank_pending = true;
+            }
+        }
+        (KeyCode::Char('V'), KeyModifiers::SHIFT) => {
+            app.toggle_visual_selection();
+        }
+
+        // File filter
+        (KeyCode::Char('f'), KeyModifiers::NONE) => {
+            app.start_file_filter();
+        }
+
         // Search
         (KeyCode::Char('/'), KeyModifiers::NONE) => {
             app.start_search();
@@ -143,11 +278,12 @@ pub fn handle_key_event(key: KeyEvent, app: &mut App) -> Result<bool> {
         (KeyCode::Char('?'), _) => {
             app.toggle_help();
         }
-        (KeyCode::Esc, KeyModifiers::NONE) => {
-            // ESC clears search if active, otherwise does nothing
-            if !app.search_matches.is_empty() {
-                app.clear_search();
-            }
+        // ESC cancels a visual selection, then clears search if active
+        (KeyCode::Esc, KeyModifiers::NONE) if app.visual_anchor.is_some() => {
+            app.toggle_visual_selection();
+        }
+        (KeyCode::Esc, KeyModifiers::NONE) if !app.search_matches.is_empty() => {
+            app.clear_search();
         }
 
         _ => {}
@@ -156,6 +292,22 @@ pub fn handle_key_event(key: KeyEvent, app: &mut App) -> Result<bool> {
     Ok(false)
 }
 
+/// Columns of line content visible in one side-by-side column
+fn side_content_width(app: &App) -> usize {
+    let layout_info = crate::ui::layout::calculate_layout(
+        app.terminal_width,
+        app.terminal_height,
+        app.log_pane_visible,
+        app.config.ui.log_pane_width_ratio,
+    );
+    let diff_width = layout_info
+        .diff_area
+        .or(layout_info.content)
+        .map_or(0, |area| area.width);
+    // Half the diff area, less borders and the line number column
+    (diff_width / 2).saturating_sub(2).saturating_sub(5) as usize
+}
+
 /// Handle keyboard input during search entry
 fn handle_search_input(key: KeyEvent, app: &mut App) -> Result<bool> {
     match key.code {
@@ -176,6 +328,28 @@ fn handle_search_input(key: KeyEvent, app: &mut App) -> Result<bool> {
     Ok(false)
 }
 
+/// Handle file filter prompt input (the file list narrows as you type)
+fn handle_filter_input(key: KeyEvent, app: &mut App) -> Result<bool> {
+    match key.code {
+        KeyCode::Enter => {
+            app.confirm_file_filter();
+        }
+        KeyCode::Esc => {
+            app.clear_file_filter();
+        }
+        KeyCode::Char(c) => {
+            app.file_filter.push(c);
+            app.update_file_filter();
+        }
+        KeyCode::Backspace => {
+            app.file_filter.pop();
+            app.update_file_filter();
+        }
+        _ => {}
+    }
+    Ok(false)
+}
+
 /// Handle mouse input
 pub fn handle_mouse_event(mouse: MouseEvent, app: &mut App) -> Result<()> {
     // Ignore all mouse events when help is visible
@@ -183,7 +357,23 @@ pub fn handle_mouse_event(mouse: MouseEvent, app: &mut App) -> Result<()> {
         return Ok(());
     }
 
+    let horizontal_amount = app.config.display.horizontal_scroll_amount as isize;
+    let shift = mouse.modifiers.contains(KeyModifiers::SHIFT);
+
     match mouse.kind {
+        // Shift+wheel and horizontal wheels scroll sideways
+        MouseEventKind::ScrollDown if shift => {
+            app.scroll_horizontal(horizontal_amount);
+        }
+        MouseEventKind::ScrollUp if shift => {
+            app.scroll_horizontal(-horizontal_amount);
+        }
+        MouseEventKind::ScrollRight => {
+            app.scroll_horizontal(horizontal_amount);
+        }
+        MouseEventKind::ScrollLeft => {
+            app.scroll_horizontal(-horizontal_amount);
+        }
         MouseEventKind::ScrollDown => {
             app.scroll(3);
         }
@@ -193,6 +383,12 @@ pub fn handle_mouse_event(mouse: MouseEvent, app: &mut App) -> Result<()> {
         MouseEventKind::Down(_button) => {
             handle_mouse_click(mouse, app)?;
         }
+        MouseEventKind::Drag(MouseButton::Left) if app.dragging_log_border => {
+            app.drag_log_border(mouse.column);
+        }
+        MouseEventKind::Up(MouseButton::Left) if app.dragging_log_border => {
+            app.end_log_border_drag();
+        }
         _ => {}
     }
 
@@ -216,26 +412,39 @@ fn handle_mouse_click(mouse: MouseEvent, app: &mut App) -> Result<()> {
     // Check if click is in log pane
     if app.log_pane_visible {
         if let Some(log_area) = layout_info.log_pane {
+            // Grabbing the border between the panes starts a resize
+            app.start_log_border_drag(mouse.column, log_area.x + log_area.width);
+            if app.dragging_log_border {
+                return Ok(());
+            }
+
             if mouse.column >= log_area.x
                 && mouse.column < log_area.x + log_area.width
                 && mouse.row >= log_area.y
                 && mouse.row < log_area.y + log_area.height
             {
                 // Click is in log pane - calculate which commit
-                // Account for borders (top border = 1 line) and title
-                let relative_row = mouse.row.saturating_sub(log_area.y + 1);
+                // Account for borders (top border = 1 line), title and scrolling
+                app.focus = Pane::Log;
+                let relative_row = mouse.row.saturating_sub(log_area.y + 1) as usize;
+                let index = app.log_list_state.offset() + relative_row;
 
-                if relative_row < app.commits.len() as u16 {
-                    app.select_commit(relative_row as usize);
+                if index < app.commits.len() {
+                    app.select_commit(index);
                 }
                 return Ok(());
             }
         }
     }
 
-    // TODO: Handle clicks in diff view for:
-    // - Expand button clicks (need to track which lines are expand buttons)
-    // - Comment indicators (when implemented)
+    // Clicks in the diff view: cursor, expand rows, comment indicators
+    if let Some(diff_area) = crate::ui::diff_view_area(app) {
+        if let Some(click) = crate::ui::diff_hit::hit_test(app, diff_area, mouse.column, mouse.row)
+        {
+            app.focus = Pane::Diff;
+            app.click_diff(click);
+        }
+    }
 
     Ok(())
 }
@@ -1,8 +1,13 @@
 // Library exports for testing
 pub mod app;
+pub mod clipboard;
+pub mod comment_cli;
 pub mod comments;
 pub mod config;
 pub mod export;
 pub mod git;
 pub mod input;
+pub mod no_index;
+#[cfg(test)]
+mod test_repo;
 pub mod ui;
@@ -1,16 +1,21 @@
 mod app;
+mod clipboard;
+mod comment_cli;
 mod comments;
 mod config;
 mod export;
 mod git;
 mod input;
+mod no_index;
+#[cfg(test)]
+mod test_repo;
 mod ui;
 
 use anyhow::{Context, Result};
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
 use git2::Repository;
-use std::io::{self, Write};
-use std::path::PathBuf;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 enum ExportFormatArg {
@@ -18,6 +23,110 @@ enum ExportFormatArg {
     Json,
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ColorArg {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SideArg {
+    Old,
+    New,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Add, list, edit or remove review comments from scripts
+    Comment {
+        #[command(subcommand)]
+        action: CommentAction,
+    },
+
+    /// Show the files `git difftool` passes, one file or a directory pair
+    /// (`git difftool -d`) at a time
+    ///
+    /// Configure with:
+    ///   git config difftool.git-review.cmd 'git-review difftool "$LOCAL" "$REMOTE" "$MERGED"'
+    /// Press q for the next file, or Q to stop (needs difftool.trustExitCode).
+    Difftool {
+        /// Old version of the file, or of the tree with `git difftool -d`
+        local: PathBuf,
+
+        /// New version of the file, or of the tree with `git difftool -d`
+        remote: PathBuf,
+
+        /// Path of the file in the repository, shown instead of the temporary
+        /// files' names
+        merged: Option<String>,
+
+        /// Append comments to this Markdown file instead of printing them
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum CommentAction {
+    /// Add a comment to a file, line or hunk of a commit and print its id
+    Add {
+        /// Commit to comment on
+        #[arg(long, value_name = "REV", default_value = "HEAD")]
+        commit: String,
+
+        /// File the comment is about (as changed by the commit)
+        #[arg(long, value_name = "PATH")]
+        file: String,
+
+        /// Line to comment on (without --line or --hunk the comment is on the file)
+        #[arg(long, value_name = "N", conflicts_with = "hunk")]
+        line: Option<usize>,
+
+        /// Which version of the file --line counts in
+        #[arg(long, value_enum, default_value = "new", requires = "line")]
+        side: SideArg,
+
+        /// Hunk to comment on, by its header, e.g. "@@ -10,6 +10,8 @@"
+        #[arg(long, value_name = "HEADER")]
+        hunk: Option<String>,
+
+        #[command(flatten)]
+        text: CommentText,
+    },
+    /// List comments on the current branch as JSON
+    List {
+        /// Only list comments on this commit
+        #[arg(long, value_name = "REV")]
+        commit: Option<String>,
+    },
+    /// Remove a comment
+    Rm {
+        /// Comment id, as printed by `add` or `list`
+        id: String,
+    },
+    /// Replace the text of a comment
+    Edit {
+        /// Comment id, as printed by `add` or `list`
+        id: String,
+
+        #[command(flatten)]
+        text: CommentText,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+#[group(required = true, multiple = false)]
+struct CommentText {
+    /// Comment text
+    #[arg(short, long)]
+    text: Option<String>,
+
+    /// Read the comment text from a file (`-` for stdin)
+    #[arg(short = 'F', long, value_name = "FILE")]
+    text_file: Option<PathBuf>,
+}
+
 #[derive(Parser, Debug)]
 #[command(name = "git-review")]
 #[command(version, about = "Terminal-based code review tool for git branches", long_about = None)]
@@ -27,6 +136,9 @@ struct Args {
     path: Option<PathBuf>,
 
     /// Base branch to compare against
+    ///
+    /// With --patch or --mbox, the commit the patches apply to (defaults to
+    /// their `base-commit:` line).
     #[arg(short, long, value_name = "BRANCH", conflicts_with = "range")]
     base: Option<String>,
 
@@ -43,6 +155,21 @@ struct Args {
     #[arg(short, long, value_name = "LINES", default_value = "8")]
     context: u32,
 
+    /// Follow only the first parent of merge commits
+    ///
+    /// Lists each merge once instead of every commit it brought in, like
+    /// `git log --first-parent`.
+    #[arg(long)]
+    first_parent: bool,
+
+    /// Show no commit before all of its children, like `git log --topo-order`
+    #[arg(long)]
+    topo_order: bool,
+
+    /// Draw the commit graph in the log pane (implies --topo-order)
+    #[arg(long, conflicts_with = "range_diff")]
+    graph: bool,
+
     /// Export all comments for current branch to stdout
     #[arg(long, conflicts_with = "clear_comments")]
     export_comments: bool,
@@ -63,20 +190,233 @@ struct Args {
     /// Skip confirmation prompts (for automation)
     #[arg(short = 'y', long)]
     yes: bool,
+
+    /// Exit with an error if any commit in the range is not approved
+    ///
+    /// Checks verdicts recorded for the current branch without starting the
+    /// UI, for use in pre-push hooks.
+    #[arg(long, conflicts_with_all = ["export_comments", "clear_comments"])]
+    check_approved: bool,
+
+    /// Reload commits, diffs and comments when the repository changes
+    ///
+    /// Polls HEAD, the range refs and the notes ref, so amends, rebases and
+    /// new commits made in another terminal show up without restarting.
+    #[arg(short, long, conflicts_with = "range_diff")]
+    watch: bool,
+
+    /// Limit the review to these paths (git pathspecs, after `--`)
+    ///
+    /// Combined with `exclude` patterns from the `[filter]` config section.
+    #[arg(last = true, value_name = "PATHSPEC")]
+    pathspecs: Vec<String>,
+
+    /// Compare two versions of a patch series, like `git range-diff`
+    ///
+    /// Takes the old and new series as <base>..<tip> ranges, e.g.
+    ///   --range-diff main..feature@{1} main..feature
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["OLD", "NEW"],
+        conflicts_with_all = ["base", "range", "check_approved"]
+    )]
+    range_diff: Option<Vec<String>>,
+
+    /// Review the diff between two revisions' trees as a single entry,
+    /// without walking the commits between them, e.g. --diff v1.2 v1.3
+    ///
+    /// Comments belong to the pair of trees, so they show up again whenever
+    /// the same two trees are compared.
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["OLD", "NEW"],
+        conflicts_with_all = ["base", "range", "range_diff", "patch_input", "watch", "graph", "check_approved"]
+    )]
+    diff: Option<Vec<String>>,
+
+    /// Show diffs inline (overrides the configured diff mode)
+    #[arg(long, conflicts_with = "side_by_side")]
+    inline: bool,
+
+    /// Show diffs side by side (overrides the configured diff mode)
+    #[arg(long)]
+    side_by_side: bool,
+
+    /// Print the commits and diffs with their comments to stdout instead of
+    /// starting the UI
+    #[arg(long, conflicts_with_all = ["export_comments", "clear_comments", "check_approved", "watch"])]
+    print: bool,
+
+    /// Output width for --print (defaults to the terminal width, or 120)
+    #[arg(long, value_name = "COLS", requires = "print")]
+    width: Option<u16>,
+
+    /// When to colour --print output
+    #[arg(long, value_enum, default_value = "auto", requires = "print")]
+    color: ColorArg,
+
+    /// Review a patch without applying it: `git format-patch` output, an
+    /// email or a plain diff (`-` or no value reads stdin)
+    ///
+    /// Each message becomes an entry in the commit list. Context can be
+    /// expanded when the patch's base is in the repository (see --base).
+    #[arg(
+        long,
+        value_name = "FILE",
+        num_args = 0..=1,
+        default_missing_value = "-",
+        group = "patch_input",
+        conflicts_with_all = ["range", "range_diff", "mbox", "watch", "graph", "check_approved"]
+    )]
+    patch: Option<PathBuf>,
+
+    /// Review the patches in an mbox file, one entry per message
+    #[arg(
+        long,
+        value_name = "FILE",
+        group = "patch_input",
+        conflicts_with_all = ["range", "range_diff", "watch", "graph", "check_approved"]
+    )]
+    mbox: Option<PathBuf>,
+
+    /// Write comments on --patch/--mbox patches to this file as reply
+    /// emails (defaults to stdout after quitting)
+    #[arg(long, value_name = "FILE", requires = "patch_input")]
+    reply: Option<PathBuf>,
+
+    /// Compare two files or directories that aren't in a repository
+    ///
+    /// Comments are kept in memory and written as Markdown on quit.
+    #[arg(
+        long,
+        num_args = 2,
+        value_names = ["A", "B"],
+        conflicts_with_all = ["base", "range", "range_diff", "diff", "patch_input", "watch", "graph", "check_approved"]
+    )]
+    no_index: Option<Vec<PathBuf>>,
+
+    /// Write comments from --no-index to this Markdown file (defaults to
+    /// stdout after quitting)
+    #[arg(short, long, value_name = "FILE", requires = "no_index")]
+    output: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
+    let diff_mode = if args.inline {
+        Some(config::DiffMode::Inline)
+    } else if args.side_by_side {
+        Some(config::DiffMode::SideBySide)
+    } else {
+        None
+    };
+    let print_options = args.print.then(|| ui::print::PrintOptions {
+        width: args
+            .width
+            .or_else(|| crossterm::terminal::size().ok().map(|(w, _)| w))
+            .unwrap_or(120) as usize,
+        color: match args.color {
+            ColorArg::Always => true,
+            ColorArg::Never => false,
+            ColorArg::Auto => io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+        },
+    });
+
+    // Files outside git are compared without looking for a repository
+    if let Some(paths) = &args.no_index {
+        let commit = no_index::compare(&paths[0], &paths[1], false)?;
+        if commit.files.is_empty() {
+            println!(
+                "No differences between {} and {}",
+                paths[0].display(),
+                paths[1].display()
+            );
+            return Ok(());
+        }
+
+        let mut config = config::Config::load_or_default()?;
+        config.display.context_lines = args.context;
+        if let Some(mode) = diff_mode {
+            config.display.diff_mode = mode;
+        }
+        let label = commit.commit.summary().to_string();
+        let (_scratch, repo) = no_index::ScratchRepo::create()?;
+        let Some(app) = review_files(repo, commit, config, &args.pathspecs, print_options)? else {
+            return Ok(());
+        };
+        return write_markdown(&app, &label, args.output.as_deref(), false);
+    }
+
+    // Called by `git difftool` with temporary copies of each side
+    if let Some(Command::Difftool {
+        local,
+        remote,
+        merged,
+        output,
+    }) = &args.command
+    {
+        // Dir-diff mode leaves $MERGED empty; sides may link to the worktree
+        let mut commit = no_index::compare(local, remote, true)?;
+        if let Some(merged) = merged.as_deref().filter(|m| !m.is_empty()) {
+            for file in &mut commit.files {
+                file.old_path = merged.to_string();
+                file.new_path = merged.to_string();
+            }
+            commit.commit.message = merged.to_string();
+        }
+        if commit.files.is_empty() {
+            return Ok(());
+        }
+        // git tells each invocation where it is in the list of files
+        let progress = std::env::var("GIT_DIFF_PATH_COUNTER")
+            .ok()
+            .zip(std::env::var("GIT_DIFF_PATH_TOTAL").ok());
+        if let Some((counter, total)) = &progress {
+            commit.commit.message = format!("{} ({counter}/{total})", commit.commit.message);
+        }
+
+        let mut config = config::Config::load_or_default()?;
+        config.display.context_lines = args.context;
+        config.display.diff_mode = diff_mode.unwrap_or(config::DiffMode::SideBySide);
+        let label = commit.commit.summary().to_string();
+        let (scratch, repo) = no_index::ScratchRepo::create()?;
+        let Some(app) = review_files(repo, commit, config, &args.pathspecs, print_options)? else {
+            return Ok(());
+        };
+        write_markdown(&app, &label, output.as_deref(), true)?;
+        let stop_requested = app.stop_requested;
+        // process::exit skips destructors, so remove the scratch repo first
+        drop(app);
+        drop(scratch);
+        if stop_requested {
+            // With difftool.trustExitCode, git skips the remaining files
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     // Open git repository
     let repo_path = args.path.unwrap_or_else(|| PathBuf::from("."));
     let repo = Repository::discover(&repo_path)
         .context("Failed to find git repository. Make sure you're in a git directory.")?;
 
+    if let Some(Command::Comment { action }) = args.command {
+        let branch = git::current_branch(&repo)?;
+        return run_comment_command(&repo, &branch, action, args.context);
+    }
+
     // Handle export comments command
     if args.export_comments {
-        let branch = get_current_branch(&repo)?;
-        let comments = git::notes::read_all_for_branch(&repo, &branch)?;
+        let branch = git::current_branch(&repo)?;
+        let mut comments = git::notes::read_all_for_branch(&repo, &branch)?;
+        // Notes holding only viewed marks have nothing to export
+        comments.retain(|c| !c.is_empty() || c.verdict.is_some());
 
         if comments.is_empty() {
             eprintln!("No comments found for branch '{branch}'");
@@ -94,7 +434,7 @@ fn main() -> Result<()> {
 
     // Handle clear comments command
     if args.clear_comments {
-        let branch = get_current_branch(&repo)?;
+        let branch = git::current_branch(&repo)?;
 
         let confirmed = if args.yes {
             true
@@ -116,13 +456,139 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Patch review lists mailed patches instead of walking a range
+    if args.patch.is_some() || args.mbox.is_some() {
+        let patches = read_patches(args.patch.as_deref(), args.mbox.as_deref())?;
+        let base = match &args.base {
+            Some(base) => Some(
+                repo.revparse_single(base)
+                    .and_then(|object| object.peel_to_commit())
+                    .with_context(|| format!("Failed to resolve base '{base}'"))?
+                    .id(),
+            ),
+            // A base named by the patch may not be fetched yet
+            None => patches
+                .iter()
+                .find_map(|p| p.base_commit.as_deref())
+                .and_then(|id| repo.revparse_single(id).ok())
+                .and_then(|object| object.peel_to_commit().ok())
+                .map(|commit| commit.id()),
+        };
+        let commits = git::virtual_diff::commits_from_patches(&repo, &patches, base)?;
+        let reviewer = repo.signature().ok().map(|sig| {
+            format!(
+                "{} <{}>",
+                sig.name().unwrap_or(""),
+                sig.email().unwrap_or("")
+            )
+        });
+
+        let mut config = config::Config::load_or_default()?;
+        config.display.context_lines = args.context;
+        if let Some(mode) = diff_mode {
+            config.display.diff_mode = mode;
+        }
+        let current_branch = git::current_branch(&repo).unwrap_or_default();
+
+        let path_filter = git::PathFilter {
+            include: args.pathspecs.clone(),
+            exclude: config.filter.exclude.clone(),
+        };
+
+        let mut app = app::App::new(repo, Vec::new(), config, current_branch);
+        app.set_virtual_commits(commits);
+        app.path_filter = path_filter;
+        app.init_diff();
+        if let Some(options) = print_options {
+            return print_review(app, &options);
+        }
+        run_tui(&mut app)?;
+        return write_reply(&app, &patches, reviewer.as_deref(), args.reply.as_deref());
+    }
+
+    // Range-diff mode compares two series instead of walking one range
+    if let Some(ranges) = &args.range_diff {
+        let old_range = git::range_diff::parse_series_range(&ranges[0])?;
+        let new_range = git::range_diff::parse_series_range(&ranges[1])?;
+        let pairs = git::range_diff::compute_range_diff(
+            &repo,
+            (&old_range.0, &old_range.1),
+            (&new_range.0, &new_range.1),
+        )?;
+
+        if pairs.is_empty() {
+            println!("No commits found in either range");
+            return Ok(());
+        }
+
+        let mut config = config::Config::load_or_default()?;
+        config.display.context_lines = args.context;
+        if let Some(mode) = diff_mode {
+            config.display.diff_mode = mode;
+        }
+        let current_branch = git::current_branch(&repo)?;
+
+        let path_filter = git::PathFilter {
+            include: args.pathspecs.clone(),
+            exclude: config.filter.exclude.clone(),
+        };
+
+        let mut app = app::App::new(repo, Vec::new(), config, current_branch);
+        app.set_range_diff(pairs);
+        // Pairs stay listed; the filter only limits the diffs
+        app.path_filter = path_filter;
+        app.load_comments();
+        app.load_log_decorations();
+        app.init_diff();
+        return match print_options {
+            Some(options) => print_review(app, &options),
+            None => run_tui(&mut app),
+        };
+    }
+
+    // A direct diff reviews one synthetic entry for the pair of trees
+    if let Some(revs) = &args.diff {
+        let (old_tree, new_tree, entry) = git::tree_pair(&repo, &revs[0], &revs[1])?;
+        if old_tree == new_tree {
+            println!("No differences between {} and {}", revs[0], revs[1]);
+            return Ok(());
+        }
+
+        let mut config = config::Config::load_or_default()?;
+        config.display.context_lines = args.context;
+        if let Some(mode) = diff_mode {
+            config.display.diff_mode = mode;
+        }
+        let current_branch = git::current_branch(&repo)?;
+
+        let path_filter = git::PathFilter {
+            include: args.pathspecs.clone(),
+            exclude: config.filter.exclude.clone(),
+        };
+
+        let mut app = app::App::new(repo, Vec::new(), config, current_branch);
+        app.set_tree_diff(old_tree, new_tree, entry);
+        app.path_filter = path_filter;
+        app.load_comments();
+        app.init_diff();
+        return match print_options {
+            Some(options) => print_review(app, &options),
+            None => run_tui(&mut app),
+        };
+    }
+
     // Get commits and base branch based on --range or --base
     let using_range = args.range.is_some();
-    let (commits, base_branch) = if let Some(range) = args.range {
+    let log_options = git::LogOptions {
+        first_parent: args.first_parent,
+        topo_order: args.topo_order || args.graph,
+        graph: args.graph,
+    };
+    let (commits, base_branch, end_ref) = if let Some(range) = args.range {
         // Use explicit range
         let (start_ref, end_ref) = git::parse_range(&range)?;
-        let commits = git::get_commit_log_range(&repo, &start_ref, &end_ref)?;
-        (commits, start_ref)
+        let commits = git::get_commit_log_range(&repo, &start_ref, &end_ref, &log_options)?;
+        (commits, start_ref, end_ref)
     } else {
         // Use base branch (auto-detect or explicit)
         let base = if let Some(base) = args.base {
@@ -130,13 +596,16 @@ fn main() -> Result<()> {
         } else {
             git::detect_base_branch(&repo)?
         };
-        let commits = git::get_commit_log(&repo, &base)?;
-        (commits, base)
+        let commits = git::get_commit_log(&repo, &base, &log_options)?;
+        (commits, base, "HEAD".to_string())
     };
 
     // Load configuration
     let mut config = config::Config::load_or_default()?;
     config.display.context_lines = args.context;
+    if let Some(mode) = diff_mode {
+        config.display.diff_mode = mode;
+    }
 
     if commits.is_empty() {
         if using_range {
@@ -150,24 +619,72 @@ fn main() -> Result<()> {
     }
 
     // Get current branch for comment storage
-    let current_branch = get_current_branch(&repo)?;
+    let current_branch = git::current_branch(&repo)?;
+
+    if args.check_approved {
+        let unapproved = unapproved_commits(&repo, &current_branch, &commits)?;
+        if unapproved.is_empty() {
+            println!("✓ All {} commit(s) approved", commits.len());
+            return Ok(());
+        }
+        for commit in &unapproved {
+            eprintln!("✗ {} {}", commit.short_id, commit.summary());
+        }
+        eprintln!(
+            "{} of {} commit(s) not approved",
+            unapproved.len(),
+            commits.len()
+        );
+        std::process::exit(1);
+    }
 
     // Initialize app state
+    let path_filter = git::PathFilter {
+        include: args.pathspecs.clone(),
+        exclude: config.filter.exclude.clone(),
+    };
+
     let mut app = app::App::new(repo, commits, config, current_branch);
+    app.log_options = log_options;
+    app.set_path_filter(path_filter);
 
     // Load comments from git notes
     app.load_comments();
+    app.load_log_decorations();
 
     // Load initial diff
     app.init_diff();
 
-    // Run TUI
-    run_tui(app)?;
+    if args.watch {
+        app.enable_watch(base_branch, end_ref);
+    }
 
-    Ok(())
+    match print_options {
+        Some(options) => print_review(app, &options),
+        None => run_tui(&mut app),
+    }
+}
+
+/// Print the review to stdout (`--print`)
+fn print_review(mut app: app::App, options: &ui::print::PrintOptions) -> Result<()> {
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    let result = ui::print::print_review(&mut app, &mut out, options).and_then(|_| {
+        out.flush()?;
+        Ok(())
+    });
+    // Output piped into `head` and the like stops early without an error
+    match result {
+        Err(e)
+            if e.downcast_ref::<io::Error>()
+                .is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe) =>
+        {
+            Ok(())
+        }
+        result => result,
+    }
 }
 
-fn run_tui(mut app: app::App) -> Result<()> {
+fn run_tui(app: &mut app::App) -> Result<()> {
     // Set up terminal
     crossterm::terminal::enable_raw_mode()?;
     let mut stdout = std::io::stdout();
@@ -181,7 +698,7 @@ fn run_tui(mut app: app::App) -> Result<()> {
     let mut terminal = ratatui::Terminal::new(backend)?;
 
     // Run app loop
-    let result = app_loop(&mut terminal, &mut app);
+    let result = app_loop(&mut terminal, app);
 
     // Restore terminal
     crossterm::terminal::disable_raw_mode()?;
@@ -199,29 +716,38 @@ fn app_loop(
     terminal: &mut ratatui::Terminal<ratatui::backend::CrosstermBackend<std::io::Stdout>>,
     app: &mut app::App,
 ) -> Result<()> {
+    let mut needs_redraw = true;
     loop {
-        // Draw UI
-        terminal.draw(|f| {
-            if let Err(e) = ui::render(f, app) {
-                eprintln!("Render error: {e}");
-            }
-        })?;
+        // Pick up changes made outside git-review (watch mode only)
+        needs_redraw |= app.check_for_repo_changes();
+        needs_redraw |= app.poll_diff_loader();
+
+        // Draw UI only when something changed
+        if needs_redraw {
+            terminal.draw(|f| {
+                if let Err(e) = ui::render(f, app) {
+                    eprintln!("Render error: {e}");
+                }
+            })?;
+            needs_redraw = false;
+        }
 
         // Handle input
         if crossterm::event::poll(std::time::Duration::from_millis(100))? {
             match crossterm::event::read()? {
-                crossterm::event::Event::Key(key) => {
-                    if input::handle_key_event(key, app)? {
-                        break; // Exit requested
-                    }
+                crossterm::event::Event::Key(key) if input::handle_key_event(key, app)? => {
+                    break; // Exit requested
                 }
                 crossterm::event::Event::Mouse(mouse) => {
                     input::handle_mouse_event(mouse, app)?;
+                    // Plain pointer movement doesn't change anything on screen
+                    needs_redraw = mouse.kind != crossterm::event::MouseEventKind::Moved;
                 }
                 crossterm::event::Event::Resize(width, height) => {
                     app.handle_resize(width, height);
+                    needs_redraw = true;
                 }
-                _ => {}
+                _ => needs_redraw = true,
             }
         }
     }
@@ -229,19 +755,190 @@ fn app_loop(
     Ok(())
 }
 
-/// Get the current branch name
-fn get_current_branch(repo: &Repository) -> Result<String> {
-    let head = repo.head().context("Failed to get HEAD reference")?;
-
-    if let Some(branch_name) = head.shorthand() {
-        // Remove "refs/heads/" prefix if present
-        let name = branch_name
-            .strip_prefix("refs/heads/")
-            .unwrap_or(branch_name);
-        Ok(name.to_string())
+/// Read and parse `--patch` or `--mbox` input, where `-` is stdin
+fn read_patches(patch: Option<&Path>, mbox: Option<&Path>) -> Result<Vec<git::mbox::PatchMessage>> {
+    let path = patch.or(mbox).context("No patch input")?;
+    let (bytes, name) = if path == Path::new("-") {
+        let mut bytes = Vec::new();
+        io::stdin()
+            .read_to_end(&mut bytes)
+            .context("Failed to read patch from stdin")?;
+        (bytes, "stdin".to_string())
     } else {
-        // Detached HEAD state - use commit SHA
-        let oid = head.target().context("HEAD has no target")?;
-        Ok(format!("detached-{}", &oid.to_string()[..7]))
+        let bytes =
+            std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        (bytes, path.display().to_string())
+    };
+    let text = String::from_utf8_lossy(&bytes);
+    match mbox {
+        Some(_) => git::mbox::parse_mbox(&text),
+        None => git::mbox::parse_patch(&text, &name),
     }
 }
+
+/// Write comments left on reviewed patches as reply emails (`--reply`)
+fn write_reply(
+    app: &app::App,
+    patches: &[git::mbox::PatchMessage],
+    reviewer: Option<&str>,
+    path: Option<&Path>,
+) -> Result<()> {
+    // Virtual commits are listed newest first, patches in series order
+    let commits = app.virtual_commits.as_deref().unwrap_or_default();
+    let replies: Vec<_> = patches
+        .iter()
+        .zip(commits.iter().rev())
+        .map(|(patch, commit)| {
+            let comments = app.comments_by_commit.get(&commit.commit.id.to_string());
+            (patch, comments)
+        })
+        .collect();
+    let reply = export::to_mail_reply(&replies, reviewer)?;
+    if reply.is_empty() {
+        return Ok(());
+    }
+
+    match path {
+        Some(path) => {
+            std::fs::write(path, reply)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!("Wrote review reply to {}", path.display());
+        }
+        None => print!("{reply}"),
+    }
+    Ok(())
+}
+
+/// Review files compared outside git (`--no-index`, `difftool`)
+///
+/// Returns the app after the UI quits so its comments can be written, or
+/// None when the diff was printed.
+fn review_files(
+    repo: Repository,
+    commit: git::virtual_diff::VirtualCommit,
+    config: config::Config,
+    pathspecs: &[String],
+    print_options: Option<ui::print::PrintOptions>,
+) -> Result<Option<app::App>> {
+    let path_filter = git::PathFilter {
+        include: pathspecs.to_vec(),
+        exclude: config.filter.exclude.clone(),
+    };
+
+    let mut app = app::App::new(repo, Vec::new(), config, String::new());
+    app.set_virtual_commits(vec![commit]);
+    app.path_filter = path_filter;
+    app.init_diff();
+    if let Some(options) = print_options {
+        print_review(app, &options)?;
+        return Ok(None);
+    }
+    run_tui(&mut app)?;
+    Ok(Some(app))
+}
+
+/// Write comments left in memory as Markdown (`--no-index`, `difftool`)
+///
+/// `append` adds to the file instead of replacing it, so the comments from
+/// each file `git difftool` opens end up together.
+fn write_markdown(app: &app::App, title: &str, path: Option<&Path>, append: bool) -> Result<()> {
+    let comments: Vec<_> = app
+        .comments_by_commit
+        .values()
+        .filter(|c| !c.is_empty() || c.verdict.is_some())
+        .cloned()
+        .collect();
+    if comments.is_empty() {
+        return Ok(());
+    }
+
+    let output = export::to_markdown(&comments, title)?;
+    match path {
+        Some(path) => {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .write(true)
+                .append(append)
+                .truncate(!append)
+                .open(path)
+                .and_then(|mut file| file.write_all(output.as_bytes()))
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!("Wrote review comments to {}", path.display());
+        }
+        None => print!("{output}"),
+    }
+    Ok(())
+}
+
+/// Find commits without an approve verdict on the given branch
+fn unapproved_commits<'a>(
+    repo: &Repository,
+    branch: &str,
+    commits: &'a [git::CommitInfo],
+) -> Result<Vec<&'a git::CommitInfo>> {
+    let mut unapproved = Vec::new();
+    for commit in commits {
+        let approved =
+            git::notes::read_comments(repo, branch, commit.id)?.is_some_and(|cc| cc.is_approved());
+        if !approved {
+            unapproved.push(commit);
+        }
+    }
+    Ok(unapproved)
+}
+
+/// Run a `comment` subcommand against the current branch's notes
+fn run_comment_command(
+    repo: &Repository,
+    branch: &str,
+    action: CommentAction,
+    context_lines: u32,
+) -> Result<()> {
+    match action {
+        CommentAction::Add {
+            commit,
+            file,
+            line,
+            side,
+            hunk,
+            text,
+        } => {
+            let anchor = match (line, hunk) {
+                (Some(number), _) => comment_cli::Anchor::Line {
+                    number,
+                    side: match side {
+                        SideArg::Old => app::Side::Old,
+                        SideArg::New => app::Side::New,
+                    },
+                },
+                (None, Some(header)) => comment_cli::Anchor::Hunk(header),
+                (None, None) => comment_cli::Anchor::File,
+            };
+            let text = comment_cli::read_text(text.text, text.text_file.as_deref())?;
+            let id = comment_cli::add_comment(
+                repo,
+                branch,
+                &commit,
+                &file,
+                anchor,
+                text,
+                context_lines,
+            )?;
+            println!("{id}");
+        }
+        CommentAction::List { commit } => {
+            println!(
+                "{}",
+                comment_cli::list_comments(repo, branch, commit.as_deref())?
+            );
+        }
+        CommentAction::Rm { id } => {
+            comment_cli::remove_comment(repo, branch, &id)?;
+        }
+        CommentAction::Edit { id, text } => {
+            let text = comment_cli::read_text(text.text, text.text_file.as_deref())?;
+            comment_cli::edit_comment(repo, branch, &id, text)?;
+        }
+    }
+    Ok(())
+}
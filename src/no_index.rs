@@ -0,0 +1,232 @@
+use anyhow::{bail, Context, Result};
+use chrono::Local;
+use git2::{ObjectType, Oid, Repository};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::git::virtual_diff::{FileSource, VirtualCommit, VirtualFile};
+use crate::git::{CommitInfo, Person};
+
+/// Compare two files or directories outside any repository
+///
+/// Two files become one entry named after both paths, where `/dev/null`
+/// stands for a missing file. Two directories are walked and matched by
+/// relative path, skipping files that are the same on both sides. Symlinks
+/// are compared by target like git does, unless `follow_symlinks` is set.
+/// The result holds full contents, so context can be expanded like any
+/// commit.
+pub fn compare(old: &Path, new: &Path, follow_symlinks: bool) -> Result<VirtualCommit> {
+    let files = match (old.is_dir(), new.is_dir()) {
+        (true, true) => compare_dirs(old, new, follow_symlinks)?,
+        (false, false) => {
+            let read = |path: &Path| match path == Path::new("/dev/null") {
+                true => Ok(None),
+                false => read_entry(path, follow_symlinks).map(Some),
+            };
+            let (old_content, new_content) = (read(old)?, read(new)?);
+            if old_content == new_content {
+                Vec::new()
+            } else {
+                vec![VirtualFile {
+                    old_path: old.display().to_string(),
+                    new_path: new.display().to_string(),
+                    source: FileSource::Contents {
+                        old: old_content,
+                        new: new_content,
+                    },
+                }]
+            }
+        }
+        _ => bail!(
+            "Cannot compare a file with a directory: {} and {}",
+            old.display(),
+            new.display()
+        ),
+    };
+
+    let label = format!("{} {}", old.display(), new.display());
+    let id = Oid::hash_object(ObjectType::Blob, label.as_bytes())?;
+    let person = Person {
+        name: std::env::var("USER").unwrap_or_default(),
+        email: String::new(),
+        time: Local::now().fixed_offset(),
+    };
+    Ok(VirtualCommit {
+        commit: CommitInfo {
+            id,
+            short_id: format!("{id:.7}"),
+            message: format!("Compare {} and {}", old.display(), new.display()),
+            author: person.clone(),
+            committer: person,
+            parent_ids: Vec::new(),
+            trailers: Vec::new(),
+            graph: String::new(),
+        },
+        files,
+    })
+}
+
+/// Match the files under two directories by relative path
+fn compare_dirs(
+    old_root: &Path,
+    new_root: &Path,
+    follow_symlinks: bool,
+) -> Result<Vec<VirtualFile>> {
+    let mut paths = BTreeSet::new();
+    walk(old_root, Path::new(""), follow_symlinks, &mut paths)?;
+    walk(new_root, Path::new(""), follow_symlinks, &mut paths)?;
+
+    let mut files = Vec::new();
+    for path in paths {
+        let read = |root: &Path| {
+            let full = root.join(&path);
+            match full.symlink_metadata() {
+                Ok(_) => read_entry(&full, follow_symlinks).map(Some),
+                Err(_) => Ok(None),
+            }
+        };
+        let (old, new) = (read(old_root)?, read(new_root)?);
+        if old == new {
+            continue;
+        }
+        let path = path.to_string_lossy().replace('\\', "/");
+        files.push(VirtualFile {
+            old_path: path.clone(),
+            new_path: path,
+            source: FileSource::Contents { old, new },
+        });
+    }
+    Ok(files)
+}
+
+/// Collect the relative paths of every file below `root`
+///
+/// Symlinks to directories are descended into only when following them;
+/// otherwise they're compared as links.
+fn walk(
+    root: &Path,
+    relative: &Path,
+    follow_symlinks: bool,
+    paths: &mut BTreeSet<PathBuf>,
+) -> Result<()> {
+    let dir = root.join(relative);
+    let entries =
+        fs::read_dir(&dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+    for entry in entries {
+        let entry = entry?;
+        let path = relative.join(entry.file_name());
+        let is_dir = if follow_symlinks {
+            fs::metadata(entry.path()).is_ok_and(|m| m.is_dir())
+        } else {
+            entry.file_type()?.is_dir()
+        };
+        if is_dir {
+            walk(root, &path, follow_symlinks, paths)?;
+        } else {
+            paths.insert(path);
+        }
+    }
+    Ok(())
+}
+
+/// Contents of a file, or the target of a symlink like git stores it
+fn read_entry(path: &Path, follow_symlinks: bool) -> Result<Vec<u8>> {
+    let metadata = path
+        .symlink_metadata()
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    if metadata.file_type().is_symlink() && !follow_symlinks {
+        let target = fs::read_link(path)?;
+        return Ok(target.to_string_lossy().into_owned().into_bytes());
+    }
+    fs::read(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+/// An empty repository in the temp directory, removed when dropped
+///
+/// The UI expects a repository to hold; when comparing files outside git
+/// nothing is read from or written to it.
+pub struct ScratchRepo {
+    path: PathBuf,
+}
+
+impl ScratchRepo {
+    pub fn create() -> Result<(Self, Repository)> {
+        let path = std::env::temp_dir().join(format!(
+            "git-review-no-index-{}-{}",
+            std::process::id(),
+            Local::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let repo = Repository::init_bare(&path).context("Failed to create scratch repository")?;
+        Ok((Self { path }, repo))
+    }
+}
+
+impl Drop for ScratchRepo {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::virtual_diff::files_from_virtual;
+    use crate::git::DiffOptions;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_compare_directories() {
+        let dir = TempDir::new().unwrap();
+        let (old, new) = (dir.path().join("old"), dir.path().join("new"));
+        for root in [&old, &new] {
+            fs::create_dir_all(root.join("sub")).unwrap();
+            fs::write(root.join("same.txt"), "same\n").unwrap();
+        }
+        fs::write(old.join("sub/changed.txt"), "one\ntwo\n").unwrap();
+        fs::write(new.join("sub/changed.txt"), "one\n2\n").unwrap();
+        fs::write(old.join("removed.txt"), "gone\n").unwrap();
+        fs::write(new.join("added.txt"), "new\n").unwrap();
+
+        let commit = compare(&old, &new, false).unwrap();
+        let paths: Vec<_> = commit.files.iter().map(|f| f.new_path.as_str()).collect();
+        assert_eq!(paths, vec!["added.txt", "removed.txt", "sub/changed.txt"]);
+        assert!(commit.has_contents());
+
+        let files = files_from_virtual(&commit.files, &DiffOptions::default()).unwrap();
+        assert_eq!(files[0].hunks[0].lines[0].content, "new");
+        assert_eq!(files[2].hunks[0].lines.len(), 3);
+
+        assert!(compare(&old, &new.join("added.txt"), false).is_err());
+        let same = compare(&old.join("same.txt"), &new.join("same.txt"), false).unwrap();
+        assert!(same.files.is_empty());
+        let added = compare(Path::new("/dev/null"), &new.join("added.txt"), false).unwrap();
+        assert!(matches!(
+            added.files[0].source,
+            FileSource::Contents { old: None, .. }
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_compare_follows_symlinked_directories() {
+        let dir = TempDir::new().unwrap();
+        let (old, new) = (dir.path().join("old"), dir.path().join("new"));
+        let target = dir.path().join("target");
+        fs::create_dir_all(&old).unwrap();
+        fs::create_dir_all(&new).unwrap();
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("inner.txt"), "inner\n").unwrap();
+        std::os::unix::fs::symlink(&target, new.join("linked")).unwrap();
+
+        // Followed: the directory's files are compared
+        let commit = compare(&old, &new, true).unwrap();
+        let paths: Vec<_> = commit.files.iter().map(|f| f.new_path.as_str()).collect();
+        assert_eq!(paths, vec!["linked/inner.txt"]);
+
+        // Not followed: the link itself is, by target
+        let commit = compare(&old, &new, false).unwrap();
+        let paths: Vec<_> = commit.files.iter().map(|f| f.new_path.as_str()).collect();
+        assert_eq!(paths, vec!["linked"]);
+    }
+}
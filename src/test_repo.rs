@@ -0,0 +1,88 @@
+//! Throwaway repositories for unit tests
+
+use git2::{Commit, Oid, Repository, Signature, Time, Tree};
+use std::path::Path;
+use std::sync::atomic::{AtomicI64, Ordering};
+use tempfile::TempDir;
+
+/// An empty repository with a work tree, removed with the directory
+pub fn init() -> (TempDir, Repository) {
+    let dir = TempDir::new().unwrap();
+    let repo = Repository::init(dir.path()).unwrap();
+    (dir, repo)
+}
+
+/// Write `files` to the work tree and commit them on top of HEAD
+pub fn commit(repo: &Repository, files: &[(&str, &[u8])], message: &str) -> Oid {
+    let tree = write_tree(repo, files);
+    let parent = head(repo);
+    let sig = signature();
+    let parents: Vec<_> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parents)
+        .unwrap()
+}
+
+/// Commit `files` on top of `parents` without moving any ref
+///
+/// The tree is the first parent's with `files` (flat paths) replaced.
+pub fn commit_on(
+    repo: &Repository,
+    parents: &[Oid],
+    files: &[(&str, &[u8])],
+    message: &str,
+) -> Oid {
+    let parents: Vec<_> = parents
+        .iter()
+        .map(|id| repo.find_commit(*id).unwrap())
+        .collect();
+    let base = parents.first().map(|p| p.tree().unwrap());
+    let mut builder = repo.treebuilder(base.as_ref()).unwrap();
+    for (path, content) in files {
+        builder
+            .insert(path, repo.blob(content).unwrap(), 0o100644)
+            .unwrap();
+    }
+    let tree = repo.find_tree(builder.write().unwrap()).unwrap();
+    let sig = signature();
+    let parents: Vec<_> = parents.iter().collect();
+    repo.commit(None, &sig, &sig, message, &tree, &parents)
+        .unwrap()
+}
+
+/// Replace HEAD with a commit of `files` on the same parents
+pub fn amend(repo: &Repository, files: &[(&str, &[u8])], message: &str) -> Oid {
+    let tree = write_tree(repo, files);
+    let head = head(repo).unwrap();
+    let sig = signature();
+    head.amend(
+        Some("HEAD"),
+        Some(&sig),
+        Some(&sig),
+        None,
+        Some(message),
+        Some(&tree),
+    )
+    .unwrap()
+}
+
+fn write_tree<'a>(repo: &'a Repository, files: &[(&str, &[u8])]) -> Tree<'a> {
+    let mut index = repo.index().unwrap();
+    for (path, content) in files {
+        std::fs::write(repo.workdir().unwrap().join(path), content).unwrap();
+        index.add_path(Path::new(path)).unwrap();
+    }
+    index.write().unwrap();
+    repo.find_tree(index.write_tree().unwrap()).unwrap()
+}
+
+fn head(repo: &Repository) -> Option<Commit<'_>> {
+    repo.head().ok().and_then(|h| h.peel_to_commit().ok())
+}
+
+/// Each commit is a second after the previous one, so time-sorted logs
+/// follow creation order
+fn signature() -> Signature<'static> {
+    static CLOCK: AtomicI64 = AtomicI64::new(1000);
+    let time = CLOCK.fetch_add(1, Ordering::Relaxed);
+    Signature::new("Test User", "test@example.com", &Time::new(time, 0)).unwrap()
+}
@@ -1,5 +1,5 @@
 use crate::app::{App, CommentMode};
-use crate::comments::CommentLevel;
+use crate::comments::{CommentLevel, VerdictKind};
 use crate::ui::theme::Theme;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::{
@@ -39,6 +39,40 @@ pub fn handle_key(key: KeyEvent, app: &mut App) -> anyhow::Result<bool> {
     Ok(false)
 }
 
+/// Handle keyboard input in verdict dialog
+pub fn handle_verdict_key(key: KeyEvent, app: &mut App) -> anyhow::Result<bool> {
+    match (key.code, key.modifiers) {
+        // Save verdict
+        (KeyCode::Char('s'), KeyModifiers::CONTROL) => {
+            app.save_verdict()?;
+        }
+        // Remove existing verdict
+        (KeyCode::Char('d'), KeyModifiers::CONTROL) => {
+            app.clear_verdict()?;
+        }
+        // Cancel
+        (KeyCode::Esc, KeyModifiers::NONE) => {
+            app.cancel_comment();
+        }
+        // Cycle verdict kind
+        (KeyCode::Tab, KeyModifiers::NONE) => {
+            app.cycle_verdict_kind();
+        }
+        // Summary text input
+        (KeyCode::Char(c), KeyModifiers::NONE) | (KeyCode::Char(c), KeyModifiers::SHIFT) => {
+            app.comment_draft.push(c);
+        }
+        (KeyCode::Backspace, KeyModifiers::NONE) => {
+            app.comment_draft.pop();
+        }
+        (KeyCode::Enter, KeyModifiers::NONE) => {
+            app.comment_draft.push('\n');
+        }
+        _ => {}
+    }
+    Ok(false)
+}
+
 /// Render comment creation dialog
 pub fn render_create(f: &mut Frame, app: &App, area: Rect) {
     if let CommentMode::Creating {
@@ -133,7 +167,11 @@ pub fn render_view(f: &mut Frame, app: &App, area: Rect) {
             ]));
             lines.push(Line::from(comment.text.clone()));
             lines.push(Line::from(Span::styled(
-                format!("  -- {}", comment.created_at.format("%Y-%m-%d %H:%M")),
+                format!(
+                    "  -- {}  id {}",
+                    comment.created_at.format("%Y-%m-%d %H:%M"),
+                    comment.id
+                ),
                 theme.context_style(),
             )));
         }
@@ -158,3 +196,78 @@ pub fn render_view(f: &mut Frame, app: &App, area: Rect) {
         f.render_widget(dialog, dialog_area);
     }
 }
+
+/// Render verdict dialog for the selected commit
+pub fn render_verdict(f: &mut Frame, app: &App, area: Rect) {
+    if let CommentMode::Verdict { kind } = &app.comment_mode {
+        let theme = Theme::default();
+
+        let dialog_width = (area.width * 60 / 100).max(40).min(area.width);
+        let dialog_height = (area.height * 40 / 100).max(12).min(area.height);
+        let x = (area.width.saturating_sub(dialog_width)) / 2;
+        let y = (area.height.saturating_sub(dialog_height)) / 2;
+
+        let dialog_area = Rect::new(x, y, dialog_width, dialog_height);
+
+        f.render_widget(Clear, dialog_area);
+
+        let short_id = app
+            .selected_commit()
+            .map(|c| c.short_id.clone())
+            .unwrap_or_default();
+        let title = format!(" Verdict: {short_id} ");
+
+        // Show all verdict kinds with the active one highlighted
+        let mut kind_spans = vec![Span::raw("Verdict: ")];
+        for option in [
+            VerdictKind::Approve,
+            VerdictKind::RequestChanges,
+            VerdictKind::Comment,
+        ] {
+            let style = if option == *kind {
+                theme.selected_style()
+            } else {
+                theme.context_style()
+            };
+            kind_spans.push(Span::styled(
+                format!(" {} {} ", option.badge(), option.label()),
+                style,
+            ));
+        }
+
+        let mut lines = vec![
+            Line::from(kind_spans),
+            Line::from(""),
+            Line::from(Span::styled("Summary (optional):", theme.context_style())),
+        ];
+
+        for line_text in app.comment_draft.lines() {
+            lines.push(Line::from(line_text.to_string()));
+        }
+
+        lines.push(Line::from(Span::styled("_", theme.selected_style())));
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled("Tab", theme.selected_style()),
+            Span::raw(" change  |  "),
+            Span::styled("Ctrl+S", theme.selected_style()),
+            Span::raw(" save  |  "),
+            Span::styled("Ctrl+D", theme.selected_style()),
+            Span::raw(" clear  |  "),
+            Span::styled("ESC", theme.selected_style()),
+            Span::raw(" cancel"),
+        ]));
+
+        let dialog = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_style(theme.selected_style()),
+            )
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: false });
+
+        f.render_widget(dialog, dialog_area);
+    }
+}
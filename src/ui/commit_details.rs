@@ -0,0 +1,117 @@
+use crate::app::App;
+use crate::git::{CommitInfo, Person};
+use crate::ui::theme::Theme;
+use ratatui::{
+    layout::Rect,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+/// Date format matching `git log`
+const DATE_FORMAT: &str = "%a %b %e %H:%M:%S %Y %z";
+
+/// Render the commit details pane at the top of `area` when visible
+///
+/// Returns the area left over for the diff view.
+pub fn render(f: &mut Frame, app: &App, area: Rect) -> Rect {
+    let (Some(details_area), diff_area) = split(app, area) else {
+        return area;
+    };
+    let Some(commit) = app.selected_commit() else {
+        return area;
+    };
+
+    let theme = Theme::default();
+    let paragraph = Paragraph::new(details_lines(commit, &theme))
+        .block(
+            Block::default()
+                .title(" Commit ")
+                .borders(Borders::ALL)
+                .border_style(theme.border_style()),
+        )
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, details_area);
+
+    diff_area
+}
+
+/// Split `area` into the details pane (None when hidden) and the area left
+/// for the diff view
+pub fn split(app: &App, area: Rect) -> (Option<Rect>, Rect) {
+    let Some(commit) = app.selected_commit().filter(|_| app.details_visible) else {
+        return (None, area);
+    };
+    let lines = details_lines(commit, &Theme::default());
+
+    // Size to the wrapped content, leaving at least half the area for the diff
+    let inner_width = area.width.saturating_sub(2).max(1) as usize;
+    let wrapped: usize = lines
+        .iter()
+        .map(|line| line.width().max(1).div_ceil(inner_width))
+        .sum();
+    let height = (wrapped as u16 + 2).min(area.height / 2);
+    if height < 3 {
+        return (None, area);
+    }
+
+    (
+        Some(Rect::new(area.x, area.y, area.width, height)),
+        Rect::new(area.x, area.y + height, area.width, area.height - height),
+    )
+}
+
+/// Build the metadata header followed by the full commit message
+pub fn details_lines<'a>(commit: &'a CommitInfo, theme: &Theme) -> Vec<Line<'a>> {
+    let mut lines = vec![Line::from(vec![
+        Span::styled("commit ", theme.label_style()),
+        Span::raw(commit.id.to_string()),
+    ])];
+
+    if commit.parent_ids.len() > 1 {
+        let parents: Vec<String> = commit
+            .parent_ids
+            .iter()
+            .map(|id| format!("{id:.7}"))
+            .collect();
+        lines.push(label_line("Merge:  ", parents.join(" "), theme));
+    }
+
+    lines.push(person_line("Author: ", &commit.author, theme));
+    if commit.committed_by_other() {
+        lines.push(person_line("Commit: ", &commit.committer, theme));
+    }
+    lines.push(Line::from(""));
+
+    // Full message, highlighting trailer keys
+    for text in commit.message.lines() {
+        let trailer = commit
+            .trailers
+            .iter()
+            .find(|t| text.starts_with(&format!("{}: ", t.key)));
+        match trailer {
+            Some(t) => lines.push(Line::from(vec![
+                Span::styled(&text[..t.key.len() + 1], theme.label_style()),
+                Span::raw(&text[t.key.len() + 1..]),
+            ])),
+            None => lines.push(Line::from(text)),
+        }
+    }
+
+    lines
+}
+
+fn person_line<'a>(label: &'a str, person: &Person, theme: &Theme) -> Line<'a> {
+    label_line(
+        label,
+        format!("{}  {}", person.display(), person.time.format(DATE_FORMAT)),
+        theme,
+    )
+}
+
+fn label_line<'a>(label: &'a str, value: String, theme: &Theme) -> Line<'a> {
+    Line::from(vec![
+        Span::styled(label, theme.label_style()),
+        Span::raw(value),
+    ])
+}
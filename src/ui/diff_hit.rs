@@ -0,0 +1,199 @@
+use crate::app::{App, DiffClick, Side};
+use crate::config::DiffMode;
+use crate::ui::theme::Theme;
+use ratatui::layout::{Position, Rect};
+use unicode_width::UnicodeWidthStr;
+
+/// Find the diff row under a mouse position
+///
+/// Mirrors the renderers: `area` is the diff view's area, including its
+/// borders, and each screen row shows one layout row.
+pub fn hit_test(app: &App, area: Rect, column: u16, row: u16) -> Option<DiffClick> {
+    match app.config.display.diff_mode {
+        DiffMode::Inline => hit_test_inline(app, area, column, row),
+        DiffMode::SideBySide => hit_test_side_by_side(app, area, column, row),
+    }
+}
+
+fn hit_test_inline(app: &App, area: Rect, column: u16, row: u16) -> Option<DiffClick> {
+    let inner = inner(area);
+    if !inner.contains(Position::new(column, row)) {
+        return None;
+    }
+
+    let index = app.scroll_offset + (row - inner.y) as usize;
+    let diff_row = app.diff_layout.row(index)?;
+    let line = crate::ui::diff_view::inline_row_line(
+        app,
+        diff_row,
+        &Theme::default(),
+        index,
+        inner.width as usize,
+    );
+
+    Some(DiffClick {
+        row: index,
+        side: None,
+        on_indicator: on_indicator(&line.to_string(), (column - inner.x) as usize),
+    })
+}
+
+fn hit_test_side_by_side(app: &App, area: Rect, column: u16, row: u16) -> Option<DiffClick> {
+    // Same split as the renderer
+    let half_width = area.width / 2;
+    let (side, column_area) = if column < area.x + half_width {
+        (
+            Side::Old,
+            Rect::new(area.x, area.y, half_width, area.height),
+        )
+    } else {
+        (
+            Side::New,
+            Rect::new(
+                area.x + half_width,
+                area.y,
+                area.width - half_width,
+                area.height,
+            ),
+        )
+    };
+    let inner = inner(column_area);
+    if !inner.contains(Position::new(column, row)) {
+        return None;
+    }
+
+    let index = app.scroll_offset + (row - inner.y) as usize;
+    if index >= app.diff_layout.len() {
+        return None;
+    }
+
+    let (left, right) = crate::ui::side_by_side::create_side_by_side_lines(
+        app,
+        &Theme::default(),
+        index,
+        1,
+        (half_width as usize).saturating_sub(2),
+        app.horizontal_scroll,
+    );
+    let line = match side {
+        Side::Old => left,
+        Side::New => right,
+    };
+    let text = line.first().map(|l| l.to_string()).unwrap_or_default();
+
+    Some(DiffClick {
+        row: index,
+        side: Some(side),
+        on_indicator: on_indicator(&text, (column - inner.x) as usize),
+    })
+}
+
+/// Area inside a block's borders
+fn inner(area: Rect) -> Rect {
+    Rect::new(
+        area.x + 1,
+        area.y + 1,
+        area.width.saturating_sub(2),
+        area.height.saturating_sub(2),
+    )
+}
+
+/// Whether `column` falls on the trailing ` [n]` comment indicator of `text`
+fn on_indicator(text: &str, column: usize) -> bool {
+    let text = text.trim_end();
+    let Some(start) = text.rfind(" [") else {
+        return false;
+    };
+    let is_indicator = text[start + 2..]
+        .strip_suffix(']')
+        .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()));
+
+    is_indicator && column > text[..start].width() && column < text.width()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::git::{FileDiff, Hunk, HunkLine, LineType};
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn context_line(n: usize, content: &str) -> HunkLine {
+        HunkLine {
+            line_type: LineType::Context,
+            old_line_num: Some(n),
+            new_line_num: Some(n),
+            content: content.to_string(),
+            raw: content.as_bytes().to_vec(),
+            parent_markers: None,
+            no_newline: false,
+        }
+    }
+
+    #[test]
+    fn test_inline_click_below_long_row() {
+        let (_dir, repo) = crate::test_repo::init();
+        let mut app = App::new(repo, Vec::new(), Config::default(), "main".to_string());
+        app.config.display.diff_mode = DiffMode::Inline;
+        app.log_pane_visible = false;
+        app.handle_resize(40, 20);
+        // Wider than the view: cut off, so it still takes one screen row
+        let long = "w".repeat(100);
+        app.all_files = vec![FileDiff {
+            old_path: "a.txt".to_string(),
+            new_path: "a.txt".to_string(),
+            hunks: vec![Hunk {
+                old_start: 1,
+                old_lines: 2,
+                new_start: 1,
+                new_lines: 2,
+                header: "@@ -1,2 +1,2 @@".to_string(),
+                lines: vec![context_line(1, &long), context_line(2, "second")],
+            }],
+            old_file_lines: None,
+            new_file_lines: None,
+            old_size: None,
+            new_size: None,
+            old_blob_id: None,
+            new_blob_id: None,
+        }];
+        app.update_file_filter();
+
+        let mut terminal = Terminal::new(TestBackend::new(40, 20)).unwrap();
+        terminal
+            .draw(|f| crate::ui::render(f, &mut app).unwrap())
+            .unwrap();
+        let buffer = terminal.backend().buffer().clone();
+        let screen_row = (0..20)
+            .find(|&y| {
+                let text: String = (0..40).map(|x| buffer[(x, y)].symbol()).collect();
+                text.contains("second")
+            })
+            .unwrap();
+
+        let area = crate::ui::diff_view_area(&app).unwrap();
+        let click = hit_test(&app, area, area.x + 2, screen_row).unwrap();
+        let second = app
+            .diff_layout
+            .rows()
+            .iter()
+            .position(|row| matches!(row, crate::app::DiffRow::Line { line: 1, .. }));
+        assert_eq!(Some(click.row), second);
+    }
+
+    #[test]
+    fn test_on_indicator() {
+        let text = "  12 +let x = [1]; [2]   ";
+        let start = text.find(" [2]").unwrap();
+
+        assert!(on_indicator(text, start + 1));
+        assert!(on_indicator(text, start + 3));
+        assert!(!on_indicator(text, start));
+        assert!(!on_indicator(text, start + 4)); // Padding after it
+        assert!(!on_indicator(text, 8));
+
+        // Array literals at the end of a line aren't indicators
+        assert!(!on_indicator("  12 +let x = [a]", 15));
+    }
+}
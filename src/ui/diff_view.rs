@@ -1,10 +1,11 @@
-use crate::app::App;
+use crate::app::{App, DiffRow, Pane};
 use crate::config::DiffMode;
-use crate::git::{HunkLine, LineType};
+use crate::git::{FileDiff, HunkLine, LineType};
 use crate::ui::theme::Theme;
 use anyhow::Result;
 use ratatui::{
     layout::Rect,
+    style::Style,
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Wrap},
     Frame,
@@ -16,12 +17,17 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) -> Result<()> {
 
     if app.current_files.is_empty() {
         // No diff to display
-        let placeholder = Paragraph::new("No diff available. Loading...")
+        let message = if app.loading_diff.is_some() {
+            "Loading diff..."
+        } else {
+            "No changes to display"
+        };
+        let placeholder = Paragraph::new(message)
             .block(
                 Block::default()
                     .title(" Diff ")
                     .borders(Borders::ALL)
-                    .border_style(theme.border_style()),
+                    .border_style(theme.pane_border_style(app.focus == Pane::Diff)),
             )
             .wrap(Wrap { trim: true });
 
@@ -71,19 +77,12 @@ fn render_side_by_side(f: &mut Frame, app: &App, area: Rect, theme: &Theme) {
             app.horizontal_scroll,
         );
 
-        // Build title with comment indicator
-        let comment_count = crate::ui::comment_indicator::file_comment_count(app, &file.new_path);
-        let new_title = if comment_count > 0 {
-            format!(" New: {} [{}] ", file.new_path, comment_count)
-        } else {
-            format!(" New: {} ", file.new_path)
-        };
-
+        let (old_title, new_title) = side_by_side_titles(app, file, theme);
         let left_paragraph = Paragraph::new(left_lines).block(
             Block::default()
-                .title(format!(" Old: {} ", file.old_path))
+                .title(old_title)
                 .borders(Borders::ALL)
-                .border_style(theme.border_style()),
+                .border_style(theme.pane_border_style(app.focus == Pane::Diff)),
         );
 
         // Render right side (added lines)
@@ -91,7 +90,7 @@ fn render_side_by_side(f: &mut Frame, app: &App, area: Rect, theme: &Theme) {
             Block::default()
                 .title(new_title)
                 .borders(Borders::ALL)
-                .border_style(theme.border_style()),
+                .border_style(theme.pane_border_style(app.focus == Pane::Diff)),
         );
 
         f.render_widget(left_paragraph, left_area);
@@ -102,20 +101,77 @@ fn render_side_by_side(f: &mut Frame, app: &App, area: Rect, theme: &Theme) {
             Block::default()
                 .title(" Diff (Side-by-Side) ")
                 .borders(Borders::ALL)
-                .border_style(theme.border_style()),
+                .border_style(theme.pane_border_style(app.focus == Pane::Diff)),
         );
         f.render_widget(placeholder, area);
     }
 }
 
+/// Old and new column titles for a file shown side by side
+///
+/// The new title carries the file's comment count; viewed files are dimmed.
+pub fn side_by_side_titles<'a>(app: &App, file: &FileDiff, theme: &Theme) -> (Span<'a>, Span<'a>) {
+    let comment_count = crate::ui::comment_indicator::file_comment_count(app, &file.new_path);
+    let new_size = size_label(file.new_file_lines, file.new_size);
+    let new_title = if comment_count > 0 {
+        format!(" New: {}{new_size} [{}] ", file.new_path, comment_count)
+    } else {
+        format!(" New: {}{new_size} ", file.new_path)
+    };
+
+    // Viewed files are dimmed and collapsed
+    let viewed = app.is_file_viewed(file);
+    let new_title = if viewed {
+        format!("{new_title}✓ viewed ")
+    } else {
+        new_title
+    };
+    let title_style = if viewed {
+        theme.viewed_style()
+    } else {
+        theme.normal_style()
+    };
+
+    let old_title = format!(
+        " Old: {}{} ",
+        file.old_path,
+        size_label(file.old_file_lines, file.old_size)
+    );
+    (
+        Span::styled(old_title, title_style),
+        Span::styled(new_title, title_style),
+    )
+}
+
 /// Render inline diff mode
+///
+/// Only the rows inside the viewport are formatted, using the precomputed
+/// layout, so the cost per frame doesn't depend on the size of the diff.
+/// Each layout row takes exactly one screen row: long lines are cut off and
+/// scrolled sideways like the side-by-side columns, never wrapped.
 fn render_inline(f: &mut Frame, app: &App, area: Rect, theme: &Theme) {
-    let lines = create_diff_lines(app, theme);
-    let visible_lines = lines
-        .into_iter()
-        .skip(app.scroll_offset)
-        .take(area.height.saturating_sub(2) as usize)
-        .collect::<Vec<_>>();
+    let height = area.height.saturating_sub(2) as usize;
+    let width = area.width.saturating_sub(2) as usize;
+    let visible_lines: Vec<Line> = app
+        .diff_layout
+        .window(app.scroll_offset, height)
+        .iter()
+        .enumerate()
+        .map(|(offset, row)| {
+            let line_index = app.scroll_offset + offset;
+            let line = inline_row_line(app, *row, theme, line_index, width);
+            if line_index == app.cursor_line {
+                highlight_row(line, width, theme.cursor_style())
+            } else if app
+                .selection_range()
+                .is_some_and(|r| r.contains(&line_index))
+            {
+                highlight_row(line, width, theme.selection_style())
+            } else {
+                line
+            }
+        })
+        .collect();
 
     let title = if app.current_files.len() > 1 {
         format!(" Diff (Inline) - {} files ", app.current_files.len())
@@ -123,112 +179,125 @@ fn render_inline(f: &mut Frame, app: &App, area: Rect, theme: &Theme) {
         " Diff (Inline) ".to_string()
     };
 
-    let diff_paragraph = Paragraph::new(visible_lines)
-        .block(
-            Block::default()
-                .title(title)
-                .borders(Borders::ALL)
-                .border_style(theme.border_style()),
-        )
-        .wrap(Wrap { trim: false });
+    let diff_paragraph = Paragraph::new(visible_lines).block(
+        Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(theme.pane_border_style(app.focus == Pane::Diff)),
+    );
 
     f.render_widget(diff_paragraph, area);
 }
 
-/// Create styled lines from all files' diffs
-fn create_diff_lines<'a>(app: &App, theme: &Theme) -> Vec<Line<'a>> {
-    let mut lines = Vec::new();
-    let mut line_index = 0;
-
-    // Show all files in one scrollable view
-    for (file_idx, file) in app.current_files.iter().enumerate() {
-        // File separator (except before first file)
-        if file_idx > 0 {
-            lines.push(Line::from(""));
-            line_index += 1;
-            lines.push(Line::from(vec![Span::styled(
-                "─".repeat(80),
-                theme.context_style(),
-            )]));
-            line_index += 1;
-            lines.push(Line::from(""));
-            line_index += 1;
-        }
+/// Highlight the cursor or a selected row, padded to `width` so blank rows
+/// show too
+fn highlight_row<'a>(mut line: Line<'a>, width: usize, style: Style) -> Line<'a> {
+    let padding = width.saturating_sub(line.width());
+    if padding > 0 {
+        line.spans.push(Span::raw(" ".repeat(padding)));
+    }
+    line.patch_style(style)
+}
 
-        // Show file header with comment indicator
-        let old_path_spans = vec![Span::styled(
-            format!("--- {}", file.old_path),
-            theme.removed_style(),
-        )];
-        let mut new_path_spans = vec![Span::styled(
-            format!("+++ {}", file.new_path),
-            theme.added_style(),
-        )];
-
-        // Add comment indicator for file
-        if let Some(indicator) =
-            crate::ui::comment_indicator::file_indicator(app, &file.new_path, theme)
-        {
-            new_path_spans.push(indicator);
-        }
+/// One row of the inline view as shown on screen, `width` columns wide
+pub fn inline_row_line<'a>(
+    app: &App,
+    row: DiffRow,
+    theme: &Theme,
+    line_index: usize,
+    width: usize,
+) -> Line<'a> {
+    let line = create_row_line(app, row, theme, line_index);
+    crate::ui::side_by_side::scroll_line(line, app.horizontal_scroll, width)
+}
 
-        lines.push(Line::from(old_path_spans));
-        line_index += 1;
-        lines.push(Line::from(new_path_spans));
-        line_index += 1;
-        lines.push(Line::from(""));
-        line_index += 1;
-
-        // Show hunks with expand buttons
-        for hunk in &file.hunks {
-            // Expand button above (if more context available)
-            if let Some(expand_line) = crate::ui::hunk_expander::create_expand_above_line(
-                hunk,
-                theme,
-                app.config.display.context_expand_increment,
-            ) {
-                lines.push(expand_line);
-                line_index += 1;
+/// Create the styled line for one row of the inline layout
+pub fn create_row_line<'a>(app: &App, row: DiffRow, theme: &Theme, line_index: usize) -> Line<'a> {
+    let increment = app.config.display.context_expand_increment;
+    let empty = || Line::from("");
+
+    match row {
+        // Side-by-side rows don't occur in the inline layout
+        DiffRow::Blank | DiffRow::Collapsed { .. } | DiffRow::Pair { .. } => empty(),
+        DiffRow::Separator => Line::from(vec![Span::styled("─".repeat(80), theme.context_style())]),
+        DiffRow::OldPath { file } => {
+            let file = &app.current_files[file];
+            // Viewed files collapse to a dimmed header
+            let style = if app.is_file_viewed(file) {
+                theme.viewed_style()
+            } else {
+                theme.removed_style()
+            };
+            Line::from(vec![
+                Span::styled(format!("--- {}", file.old_path), style),
+                Span::styled(
+                    size_label(file.old_file_lines, file.old_size),
+                    theme.context_style(),
+                ),
+            ])
+        }
+        DiffRow::NewPath { file } => {
+            let file = &app.current_files[file];
+            let viewed = app.is_file_viewed(file);
+            let style = if viewed {
+                theme.viewed_style()
+            } else {
+                theme.added_style()
+            };
+            let mut spans = vec![
+                Span::styled(format!("+++ {}", file.new_path), style),
+                Span::styled(
+                    size_label(file.new_file_lines, file.new_size),
+                    theme.context_style(),
+                ),
+            ];
+            if viewed {
+                spans.push(Span::styled(" ✓ viewed", theme.viewed_style()));
             }
 
-            // Hunk header with comment indicator
-            let mut hunk_header_spans = vec![Span::styled(
-                hunk.header.clone(),
-                theme.context_style(),
-            )];
+            // Add comment indicator for file
             if let Some(indicator) =
-                crate::ui::comment_indicator::hunk_indicator(app, &file.new_path, &hunk.header, theme)
+                crate::ui::comment_indicator::file_indicator(app, &file.new_path, theme)
             {
-                hunk_header_spans.push(indicator);
+                spans.push(indicator);
             }
-            lines.push(Line::from(hunk_header_spans));
-            line_index += 1;
-
-            // Hunk lines with comment indicators and search highlighting
-            for hunk_line in &hunk.lines {
-                let line = format_hunk_line(app, &file.new_path, hunk_line, theme, line_index);
-                lines.push(line);
-                line_index += 1;
-            }
-
-            // Expand button below (with file length check)
-            if let Some(expand_line) = crate::ui::hunk_expander::create_expand_below_line(
-                hunk,
+            Line::from(spans)
+        }
+        DiffRow::ExpandAbove { file, hunk } => {
+            let hunk = &app.current_files[file].hunks[hunk];
+            crate::ui::hunk_expander::create_expand_above_line(hunk, theme, increment)
+                .unwrap_or_else(empty)
+        }
+        DiffRow::HunkHeader { file, hunk } => {
+            let file = &app.current_files[file];
+            let hunk = &file.hunks[hunk];
+            let mut spans = vec![Span::styled(hunk.header.clone(), theme.context_style())];
+            if let Some(indicator) = crate::ui::comment_indicator::hunk_indicator(
+                app,
+                &file.new_path,
+                &hunk.header,
                 theme,
-                app.config.display.context_expand_increment,
-                file.new_file_lines,
             ) {
-                lines.push(expand_line);
-                line_index += 1;
+                spans.push(indicator);
             }
-
-            // Empty line between hunks
-            lines.push(Line::from(""));
-            line_index += 1;
+            Line::from(spans)
+        }
+        DiffRow::Line { file, hunk, line } => {
+            let file = &app.current_files[file];
+            let hunk_line = &file.hunks[hunk].lines[line];
+            format_hunk_line(app, &file.new_path, hunk_line, theme, line_index)
+        }
+        DiffRow::ExpandBelow { file, hunk } => {
+            let file = &app.current_files[file];
+            crate::ui::hunk_expander::create_expand_below_line(
+                &file.hunks[hunk],
+                theme,
+                increment,
+                file.new_file_lines,
+            )
+            .unwrap_or_else(empty)
         }
     }
-
-    lines
 }
 
 /// Format a single hunk line with appropriate styling, comment indicator, and search highlighting
@@ -249,45 +318,38 @@ fn format_hunk_line<'a>(app: &App, file_path: &str, hunk_line: &HunkLine, theme:
         .map(|n| format!("{n:4} "))
         .unwrap_or_else(|| "     ".to_string());
 
+    // Combined merge diffs show one marker column per parent
+    let prefix = hunk_line.parent_markers.as_deref().unwrap_or(prefix);
     let content = format!("{}{}{}", line_num, prefix, hunk_line.content);
 
-    // Get search matches for this line
-    let matches = app.get_matches_for_line(line_index);
-
-    // Build spans with search highlighting
-    let mut spans = if matches.is_empty() {
-        // No matches, single span
-        vec![Span::styled(content, style)]
-    } else {
-        // Build spans with highlighted matches
-        let mut result_spans = Vec::new();
-        let mut last_end = 0;
-
-        for (start, end) in matches {
-            // Add non-highlighted part before match
-            if start > last_end {
-                result_spans.push(Span::styled(
-                    content[last_end..start].to_string(),
-                    style,
-                ));
-            }
-
-            // Add highlighted match
-            result_spans.push(Span::styled(
-                content[start..end].to_string(),
-                theme.search_highlight_style(),
-            ));
+    let mut spans = vec![Span::styled(content, style)];
 
-            last_end = end;
-        }
-
-        // Add remaining non-highlighted part
-        if last_end < content.len() {
-            result_spans.push(Span::styled(content[last_end..].to_string(), style));
-        }
+    // Search matches, offset past line number and prefix
+    let offset = line_num.chars().count() + prefix.chars().count();
+    let matches: Vec<(usize, usize)> = app
+        .get_matches_for_line(line_index, None)
+        .into_iter()
+        .map(|(start, end)| (start + offset, end + offset))
+        .collect();
+    spans = crate::ui::whitespace::overlay_style(spans, &matches, theme.search_highlight_style());
+
+    // Whitespace errors in added lines, offset past line number and prefix
+    if app.highlight_whitespace_errors && hunk_line.line_type == LineType::Added {
+        let ranges: Vec<(usize, usize)> =
+            crate::ui::whitespace::whitespace_errors(&hunk_line.content)
+                .into_iter()
+                .map(|(start, end)| (start + offset, end + offset))
+                .collect();
+        spans =
+            crate::ui::whitespace::overlay_style(spans, &ranges, theme.whitespace_error_style());
+    }
 
-        result_spans
-    };
+    if hunk_line.no_newline {
+        spans.push(Span::styled(
+            " \\ No newline at end of file",
+            theme.label_style(),
+        ));
+    }
 
     // Add comment indicator if present
     if let Some(num) = line_num_value {
@@ -300,3 +362,86 @@ fn format_hunk_line<'a>(app: &App, file_path: &str, hunk_line: &HunkLine, theme:
 
     Line::from(spans)
 }
+
+/// Describe one version of a file, e.g. " (120 lines, 3.4 KiB)"
+///
+/// Empty when the version doesn't exist (added or deleted files).
+pub fn size_label(lines: Option<usize>, size: Option<u64>) -> String {
+    let (Some(lines), Some(size)) = (lines, size) else {
+        return String::new();
+    };
+    let size = match size {
+        s if s < 1024 => format!("{s} B"),
+        s if s < 1024 * 1024 => format!("{:.1} KiB", s as f64 / 1024.0),
+        s => format!("{:.1} MiB", s as f64 / (1024.0 * 1024.0)),
+    };
+    let unit = if lines == 1 { "line" } else { "lines" };
+    format!(" ({lines} {unit}, {size})")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::git::Hunk;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    #[test]
+    fn test_cursor_stays_on_screen_past_long_lines() {
+        let (_dir, repo) = crate::test_repo::init();
+        let mut app = App::new(repo, Vec::new(), Config::default(), "main".to_string());
+        app.config.display.diff_mode = DiffMode::Inline;
+        app.log_pane_visible = false;
+        app.handle_resize(40, 16);
+        let lines: Vec<HunkLine> = (1..=30)
+            .map(|n| HunkLine {
+                line_type: LineType::Context,
+                old_line_num: Some(n),
+                new_line_num: Some(n),
+                content: format!("{n} {}", "w".repeat(100)),
+                raw: Vec::new(),
+                parent_markers: None,
+                no_newline: false,
+            })
+            .collect();
+        app.all_files = vec![FileDiff {
+            old_path: "a.txt".to_string(),
+            new_path: "a.txt".to_string(),
+            hunks: vec![Hunk {
+                old_start: 1,
+                old_lines: 30,
+                new_start: 1,
+                new_lines: 30,
+                header: "@@ -1,30 +1,30 @@".to_string(),
+                lines,
+            }],
+            old_file_lines: None,
+            new_file_lines: None,
+            old_size: None,
+            new_size: None,
+            old_blob_id: None,
+            new_blob_id: None,
+        }];
+        app.update_file_filter();
+
+        // Paths, blank and header come first, so this is line 20
+        for _ in 0..23 {
+            app.move_cursor(1);
+        }
+        let mut terminal = Terminal::new(TestBackend::new(40, 16)).unwrap();
+        terminal
+            .draw(|f| crate::ui::render(f, &mut app).unwrap())
+            .unwrap();
+
+        let area = crate::ui::diff_view_area(&app).unwrap();
+        let y = area.y + 1 + (app.cursor_line - app.scroll_offset) as u16;
+        assert!(y < area.bottom() - 1);
+        let buffer = terminal.backend().buffer();
+        let text: String = (area.x..area.right())
+            .map(|x| buffer[(x, y)].symbol())
+            .collect();
+        assert!(text.contains("  20  20 www"), "{text:?}");
+        assert!(text.trim_end().ends_with(">│"), "{text:?}");
+    }
+}
@@ -7,17 +7,27 @@ use ratatui::{
 };
 
 /// Render the footer with keyboard shortcuts
+///
+/// Status messages (e.g. what was just copied) replace the shortcuts until
+/// the next key press.
 pub fn render(f: &mut Frame, app: &App, area: Rect) {
     let theme = Theme::default();
 
     // Show different shortcuts based on diff mode, with ?:help at the start for small screens
-    let shortcuts = if app.config.display.diff_mode == crate::config::DiffMode::SideBySide {
-        " ?:help | q:quit | /:search n:next N:prev | c:comment | v:view | space:log | s:side | i:inline | p/P:commit | Ctrl-PgUp/Dn:scroll"
+    let text = if app.yank_pending {
+        " copy: y line | h hunk as patch | p path:line | c commit id | any other key cancels"
+            .to_string()
+    } else if let Some(message) = &app.status_message {
+        format!(" {message}")
+    } else if app.visual_anchor.is_some() {
+        " -- VISUAL -- j/k:extend | y:copy lines | V/Esc:cancel".to_string()
+    } else if app.config.display.diff_mode == crate::config::DiffMode::SideBySide {
+        " ?:help | q:quit | /:search n:next N:prev | c:comment | v:view | y:copy | x:viewed | a:verdict | f:filter | w:whitespace | m:details | space:log tab:focus | s:side | i:inline | p/P:commit | Ctrl-PgUp/Dn:scroll".to_string()
     } else {
-        " ?:help | q:quit | /:search n:next N:prev | c:comment | v:view | space:log | p/P:commit | e:expand | r:reset | Ctrl-PgUp/Dn:scroll"
+        " ?:help | q:quit | /:search n:next N:prev | c:comment | v:view | y:copy | x:viewed | a:verdict | f:filter | w:whitespace | m:details | space:log tab:focus | p/P:commit | e:expand | r:reset | Ctrl-PgUp/Dn:scroll".to_string()
     };
 
-    let footer = Paragraph::new(shortcuts)
+    let footer = Paragraph::new(text)
         .style(theme.header_style())
         .block(Block::default().borders(Borders::NONE));
 
@@ -11,17 +11,29 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
     let theme = Theme::default();
 
     let header_text = if let Some(commit) = app.selected_commit() {
+        let mut commit_label = commit_label(app, &commit.short_id);
+        if !app.file_filter.is_empty() {
+            commit_label.push_str(&format!(" | filter: {}", app.file_filter));
+        }
         if let Some(file) = app.selected_file() {
+            let viewed = if app.is_file_viewed(file) { " ✓" } else { "" };
             format!(
-                " {} | {} ({}/{}) | {}",
-                commit.short_id,
+                " {} | {}{} ({}/{}) | {} | {}",
+                commit_label,
                 file.new_path,
+                viewed,
                 app.selected_file_index + 1,
                 app.current_files.len(),
+                app.whitespace_mode.label(),
                 commit.summary()
             )
         } else {
-            format!(" {} | No files | {}", commit.short_id, commit.summary())
+            format!(
+                " {} | No files | {} | {}",
+                commit_label,
+                app.whitespace_mode.label(),
+                commit.summary()
+            )
         }
     } else {
         " No commit selected".to_string()
@@ -33,3 +45,31 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
 
     f.render_widget(header, area);
 }
+
+/// Label identifying the selected commit, including range-diff pair details
+fn commit_label(app: &App, short_id: &str) -> String {
+    use crate::git::range_diff::PairStatus;
+
+    match app.current_range_pair() {
+        Some(pair) => match (&pair.old, &pair.new, pair.status) {
+            (Some(old), Some(new), PairStatus::Modified) => {
+                let view = if app.show_interdiff {
+                    "interdiff"
+                } else {
+                    "patch diff"
+                };
+                format!(
+                    "{} {} → {} [{view}]",
+                    pair.status.marker(),
+                    old.short_id,
+                    new.short_id
+                )
+            }
+            _ => format!("{} {short_id}", pair.status.marker()),
+        },
+        None => match app.effective_merge_mode() {
+            Some(mode) => format!("{short_id} [merge {}]", mode.label()),
+            None => short_id.to_string(),
+        },
+    }
+}
@@ -29,6 +29,10 @@ pub fn render(f: &mut Frame, area: Rect) {
             Span::styled("  q", theme.selected_style()),
             Span::raw("  - Quit application"),
         ]),
+        Line::from(vec![
+            Span::styled("  Q", theme.selected_style()),
+            Span::raw("  - Quit and skip the remaining git difftool files"),
+        ]),
         Line::from(vec![
             Span::styled("  ?", theme.selected_style()),
             Span::raw("  - Show/hide this help dialog"),
@@ -39,7 +43,15 @@ pub fn render(f: &mut Frame, area: Rect) {
             Span::styled("  j/k", theme.selected_style()),
             Span::raw(" or "),
             Span::styled("↓/↑", theme.selected_style()),
-            Span::raw(" - Scroll diff view"),
+            Span::raw(" - Move cursor (scrolls at the edges)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  g/G", theme.selected_style()),
+            Span::raw("  - Top/bottom of diff"),
+        ]),
+        Line::from(vec![
+            Span::styled("  Ctrl-d/u", theme.selected_style()),
+            Span::raw(" - Half page down/up"),
         ]),
         Line::from(vec![
             Span::styled("  n/p", theme.selected_style()),
@@ -55,6 +67,14 @@ pub fn render(f: &mut Frame, area: Rect) {
             Span::styled("  space", theme.selected_style()),
             Span::raw(" - Toggle commit log pane"),
         ]),
+        Line::from(vec![
+            Span::styled("  Tab", theme.selected_style()),
+            Span::raw("   - Focus log pane / diff (j/k follow focus)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  </>", theme.selected_style()),
+            Span::raw("   - Shrink/grow log pane (or drag its border)"),
+        ]),
         Line::from(vec![
             Span::styled("  s", theme.selected_style()),
             Span::raw("  - Side-by-side diff mode"),
@@ -87,6 +107,48 @@ pub fn render(f: &mut Frame, area: Rect) {
             Span::styled("  d", theme.selected_style()),
             Span::raw("  - Delete first comment on current file"),
         ]),
+        Line::from(vec![
+            Span::styled("  x", theme.selected_style()),
+            Span::raw("  - Mark/unmark current file as viewed"),
+        ]),
+        Line::from(vec![
+            Span::styled("  a", theme.selected_style()),
+            Span::raw("  - Approve / request changes on commit"),
+        ]),
+        Line::from(vec![
+            Span::styled("  t", theme.selected_style()),
+            Span::raw("  - Toggle patch diff / interdiff (range-diff)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  m", theme.selected_style()),
+            Span::raw("  - Show/hide commit message and details"),
+        ]),
+        Line::from(vec![
+            Span::styled("  M", theme.selected_style()),
+            Span::raw("  - Merges: combined / per parent / remerge"),
+        ]),
+        Line::from(vec![
+            Span::styled("  w", theme.selected_style()),
+            Span::raw("  - Cycle whitespace: all / -eol / -change / -all"),
+        ]),
+        Line::from(vec![
+            Span::styled("  W", theme.selected_style()),
+            Span::raw("  - Highlight whitespace errors in added lines"),
+        ]),
+        Line::from(vec![
+            Span::styled("  f", theme.selected_style()),
+            Span::raw("  - Filter files by name (Esc clears)"),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled("Copy", theme.header_style())),
+        Line::from(vec![
+            Span::styled("  y", theme.selected_style()),
+            Span::raw("  - Copy: y line, h hunk as patch, p path:line, c commit id"),
+        ]),
+        Line::from(vec![
+            Span::styled("  V", theme.selected_style()),
+            Span::raw("  - Select lines (j/k extend, y copies, Esc cancels)"),
+        ]),
         Line::from(""),
         Line::from(Span::styled(
             "Side-by-Side Navigation",
@@ -98,11 +160,14 @@ pub fn render(f: &mut Frame, area: Rect) {
             Span::styled("←/→", theme.selected_style()),
             Span::raw(" - Scroll horizontally (see long lines)"),
         ]),
+        Line::from("  Side by side, h/l at the edge of a line move the cursor between sides"),
         Line::from("  < and > indicators show hidden content"),
         Line::from(""),
         Line::from(Span::styled("Mouse", theme.header_style())),
-        Line::from("  Scroll wheel - Navigate diff vertically"),
+        Line::from("  Scroll wheel - Navigate diff vertically (Shift: horizontally)"),
         Line::from("  Click commit - Select commit"),
+        Line::from("  Click diff line - Move cursor; expand rows expand context"),
+        Line::from("  Click [n] - View comments; double-click line - Comment"),
         Line::from(""),
         Line::from(Span::styled(
             "Press ? or ESC to close",
@@ -1,15 +1,64 @@
-use crate::app::App;
+use crate::app::{App, Pane};
 use crate::ui::theme::Theme;
 use ratatui::{
     layout::Rect,
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem},
+    widgets::{Block, Borders, List, ListItem, ListState},
     Frame,
 };
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Width of the author column when showing full names
+const AUTHOR_NAME_WIDTH: usize = 12;
+
+/// How the author column is shown at a given pane width
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AuthorColumn {
+    Hidden,
+    Initials,
+    Name,
+}
+
+/// Optional columns enabled for a given pane width
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct LogColumns {
+    author: AuthorColumn,
+    date: bool,
+}
+
+impl LogColumns {
+    /// Pick columns so the summary keeps a usable share of the pane
+    fn for_width(inner_width: usize) -> Self {
+        let author = if inner_width >= 70 {
+            AuthorColumn::Name
+        } else if inner_width >= 46 {
+            AuthorColumn::Initials
+        } else {
+            AuthorColumn::Hidden
+        };
+        Self {
+            author,
+            date: inner_width >= 36,
+        }
+    }
+}
 
 /// Render the commit log pane
-pub fn render(f: &mut Frame, app: &App, area: Rect) {
+///
+/// `state` keeps the list scrolled so the selected commit stays visible.
+pub fn render(f: &mut Frame, app: &App, area: Rect, state: &mut ListState) {
     let theme = Theme::default();
+    let inner_width = area.width.saturating_sub(2) as usize;
+    let columns = LogColumns::for_width(inner_width);
+    let now = chrono::Local::now().timestamp();
+    let has_merges = app.commits.iter().any(|c| c.is_merge());
+    // Widest graph row, so the columns after it line up (0 when not shown)
+    let graph_width = app
+        .commits
+        .iter()
+        .map(|c| c.graph.width())
+        .max()
+        .unwrap_or(0);
 
     // Create list items from commits
     let items: Vec<ListItem> = app
@@ -17,28 +66,107 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         .iter()
         .enumerate()
         .map(|(idx, commit)| {
-            let is_selected = idx == app.selected_commit_index;
+            let mut spans = Vec::new();
 
-            // Format: [hash] message
-            let content = format!("[{}] {}", commit.short_id, commit.summary());
+            // Range-diff status marker (range-diff mode only)
+            if let Some(pair) = app.range_diff.as_ref().and_then(|pairs| pairs.get(idx)) {
+                spans.push(Span::styled(
+                    format!("{} ", pair.status.marker()),
+                    theme.pair_status_style(pair.status),
+                ));
+            }
 
-            // Truncate to fit area width
-            let max_width = area.width.saturating_sub(4) as usize; // Account for borders
-            let display_content = if content.len() > max_width {
-                format!("{}...", &content[..max_width.saturating_sub(3)])
-            } else {
-                content
-            };
+            // Verdict badge (blank when no verdict recorded)
+            let verdict = app
+                .comments_by_commit
+                .get(&commit.id.to_string())
+                .and_then(|cc| cc.verdict.as_ref());
+            spans.push(match verdict {
+                Some(v) => {
+                    Span::styled(format!("{} ", v.kind.badge()), theme.verdict_style(v.kind))
+                }
+                None => Span::raw("  "),
+            });
 
-            let style = if is_selected {
-                theme.selected_style()
-            } else {
-                theme.normal_style()
-            };
+            // Unpushed marker (only when the branch has an upstream)
+            if let Some(unpushed) = &app.unpushed {
+                spans.push(if unpushed.contains(&commit.id) {
+                    Span::styled("↑ ", theme.unpushed_style())
+                } else {
+                    Span::raw("  ")
+                });
+            }
+
+            // Merge marker column (only when the list contains merges)
+            if has_merges {
+                spans.push(if commit.is_merge() {
+                    Span::styled("M ", theme.merge_style())
+                } else {
+                    Span::raw("  ")
+                });
+            }
+
+            // Commit graph, computed over the listed commits so it matches
+            // the rows whatever the scroll position or filter
+            if graph_width > 0 {
+                spans.push(Span::styled(
+                    format!("{} ", pad_to_width(&commit.graph, graph_width)),
+                    theme.graph_style(),
+                ));
+            }
+
+            spans.push(Span::styled(
+                format!("{} ", commit.short_id),
+                theme.commit_hash_style(),
+            ));
+
+            match columns.author {
+                AuthorColumn::Name => spans.push(Span::styled(
+                    format!("{} ", pad_to_width(&commit.author.name, AUTHOR_NAME_WIDTH)),
+                    theme.context_style(),
+                )),
+                AuthorColumn::Initials => spans.push(Span::styled(
+                    format!("{:<2} ", initials(&commit.author.name)),
+                    theme.context_style(),
+                )),
+                AuthorColumn::Hidden => {}
+            }
 
-            let line = Line::from(vec![Span::styled(display_content, style)]);
+            if columns.date {
+                spans.push(Span::styled(
+                    format!("{:>4} ", relative_date(commit.author.time.timestamp(), now)),
+                    theme.context_style(),
+                ));
+            }
 
-            ListItem::new(line)
+            if let Some(refs) = app.ref_decorations.get(&commit.id) {
+                spans.push(Span::styled(
+                    format!("({}) ", refs.join(", ")),
+                    theme.ref_decoration_style(),
+                ));
+            }
+
+            let count = app.comment_count(commit);
+            if count > 0 {
+                spans.push(Span::styled(
+                    format!("[{count}] "),
+                    theme.comment_indicator_style(),
+                ));
+            }
+
+            // Summary fills what is left; refs and counts are truncated with it
+            spans.push(Span::raw(commit.summary().to_string()));
+            truncate_spans(&mut spans, inner_width);
+
+            let item = ListItem::new(Line::from(spans));
+            if idx == app.selected_commit_index {
+                item.style(theme.selected_style())
+            } else if app.is_commit_excluded(commit) {
+                // Only touches filtered-out paths
+                item.style(theme.viewed_style())
+            } else {
+                item.style(theme.normal_style())
+            }
         })
         .collect();
 
@@ -47,9 +175,133 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
             Block::default()
                 .title(" Commits ")
                 .borders(Borders::ALL)
-                .border_style(theme.border_style()),
+                .border_style(theme.pane_border_style(app.focus == Pane::Log)),
         )
         .highlight_style(theme.selected_style());
 
-    f.render_widget(list, area);
+    state.select(Some(app.selected_commit_index));
+    f.render_stateful_widget(list, area, state);
+}
+
+/// Up to two uppercase initials from an author name
+fn initials(name: &str) -> String {
+    name.split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .take(2)
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// Compact age of a timestamp relative to `now` (e.g. "5m", "3d", "2y")
+pub(crate) fn relative_date(timestamp: i64, now: i64) -> String {
+    let secs = now.saturating_sub(timestamp).max(0);
+    let (value, unit) = match secs {
+        s if s < 60 => return "now".to_string(),
+        s if s < 3600 => (s / 60, "m"),
+        s if s < 86_400 => (s / 3600, "h"),
+        s if s < 7 * 86_400 => (s / 86_400, "d"),
+        s if s < 30 * 86_400 => (s / (7 * 86_400), "w"),
+        s if s < 365 * 86_400 => (s / (30 * 86_400), "mo"),
+        s => (s / (365 * 86_400), "y"),
+    };
+    format!("{value}{unit}")
+}
+
+/// Truncate a string to a display width, appending "…" when cut
+///
+/// Works on characters and their terminal width, so multi-byte and
+/// double-width characters are never split.
+pub(crate) fn truncate_to_width(text: &str, max_width: usize) -> String {
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+
+    let mut result = String::new();
+    let mut width = 0;
+    for c in text.chars() {
+        let char_width = c.width().unwrap_or(0);
+        if width + char_width > max_width - 1 {
+            break;
+        }
+        result.push(c);
+        width += char_width;
+    }
+    result.push('…');
+    result
+}
+
+/// Truncate or pad a string to exactly `width` display columns
+fn pad_to_width(text: &str, width: usize) -> String {
+    let truncated = truncate_to_width(text, width);
+    let padding = width.saturating_sub(truncated.width());
+    format!("{truncated}{}", " ".repeat(padding))
+}
+
+/// Truncate a line's spans so their combined width fits `max_width`
+fn truncate_spans(spans: &mut Vec<Span>, max_width: usize) {
+    let mut remaining = max_width;
+    for idx in 0..spans.len() {
+        let width = spans[idx].width();
+        if width > remaining {
+            let text = truncate_to_width(&spans[idx].content, remaining);
+            spans[idx].content = text.into();
+            spans.truncate(idx + 1);
+            return;
+        }
+        remaining -= width;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_to_width_multibyte() {
+        // Slicing bytes here would panic inside the multi-byte characters
+        assert_eq!(truncate_to_width("héllo wörld", 6), "héllo…");
+        assert_eq!(truncate_to_width("日本語のコミット", 7), "日本語…");
+        assert_eq!(truncate_to_width("short", 10), "short");
+        assert_eq!(truncate_to_width("anything", 0), "");
+    }
+
+    #[test]
+    fn test_relative_date() {
+        let now = 1_000_000_000;
+        assert_eq!(relative_date(now - 10, now), "now");
+        assert_eq!(relative_date(now - 5 * 60, now), "5m");
+        assert_eq!(relative_date(now - 3 * 3600, now), "3h");
+        assert_eq!(relative_date(now - 2 * 86_400, now), "2d");
+        assert_eq!(relative_date(now - 14 * 86_400, now), "2w");
+        assert_eq!(relative_date(now - 90 * 86_400, now), "3mo");
+        assert_eq!(relative_date(now - 800 * 86_400, now), "2y");
+        // Clock skew: future dates are "now"
+        assert_eq!(relative_date(now + 100, now), "now");
+    }
+
+    #[test]
+    fn test_columns_adapt_to_width() {
+        let narrow = LogColumns::for_width(20);
+        assert_eq!(narrow.author, AuthorColumn::Hidden);
+        assert!(!narrow.date);
+
+        let medium = LogColumns::for_width(50);
+        assert_eq!(medium.author, AuthorColumn::Initials);
+        assert!(medium.date);
+
+        let wide = LogColumns::for_width(100);
+        assert_eq!(wide.author, AuthorColumn::Name);
+    }
+
+    #[test]
+    fn test_initials_and_padding() {
+        assert_eq!(initials("andrew leech"), "AL");
+        assert_eq!(initials("Prince"), "P");
+        assert_eq!(initials(""), "");
+        assert_eq!(pad_to_width("Zoë", 5), "Zoë  ");
+        assert_eq!(pad_to_width("A Very Long Name", 8), "A Very …");
+    }
 }
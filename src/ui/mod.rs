@@ -1,5 +1,7 @@
 pub mod comment_dialog;
 pub mod comment_indicator;
+pub mod commit_details;
+pub mod diff_hit;
 pub mod diff_view;
 pub mod footer;
 pub mod header;
@@ -7,16 +9,20 @@ pub mod help_dialog;
 pub mod hunk_expander;
 pub mod layout;
 pub mod log_pane;
+pub mod print;
 pub mod search_prompt;
 pub mod side_by_side;
 pub mod theme;
+pub mod whitespace;
 
 use crate::app::App;
 use anyhow::Result;
-use ratatui::Frame;
+use ratatui::{layout::Rect, Frame};
 
 /// Main render function
-pub fn render(f: &mut Frame, app: &App) -> Result<()> {
+///
+/// Takes the app mutably only to keep the log list's scroll position.
+pub fn render(f: &mut Frame, app: &mut App) -> Result<()> {
     let size = f.area();
 
     // Calculate layout
@@ -38,11 +44,15 @@ pub fn render(f: &mut Frame, app: &App) -> Result<()> {
             // Split content into log pane and diff view
             if let (Some(log_area), Some(diff_area)) = (layout_info.log_pane, layout_info.diff_area)
             {
-                log_pane::render(f, app, log_area);
+                let mut list_state = std::mem::take(&mut app.log_list_state);
+                log_pane::render(f, app, log_area, &mut list_state);
+                app.log_list_state = list_state;
+                let diff_area = commit_details::render(f, app, diff_area);
                 diff_view::render(f, app, diff_area)?;
             }
         } else {
             // Full width diff view
+            let content_area = commit_details::render(f, app, content_area);
             diff_view::render(f, app, content_area)?;
         }
     }
@@ -66,6 +76,9 @@ pub fn render(f: &mut Frame, app: &App) -> Result<()> {
         CommentMode::ViewingComments(_) => {
             comment_dialog::render_view(f, app, size);
         }
+        CommentMode::Verdict { .. } => {
+            comment_dialog::render_verdict(f, app, size);
+        }
         CommentMode::Normal => {}
     }
 
@@ -74,3 +87,19 @@ pub fn render(f: &mut Frame, app: &App) -> Result<()> {
 
     Ok(())
 }
+
+/// Where the diff view is drawn for the current terminal size and panes
+pub fn diff_view_area(app: &App) -> Option<Rect> {
+    let layout_info = layout::calculate_layout(
+        app.terminal_width,
+        app.terminal_height,
+        app.log_pane_visible,
+        app.config.ui.log_pane_width_ratio,
+    );
+    let area = if app.log_pane_visible {
+        layout_info.diff_area?
+    } else {
+        layout_info.content?
+    };
+    Some(commit_details::split(app, area).1)
+}
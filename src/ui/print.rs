@@ -0,0 +1,375 @@
+use crate::app::{App, DiffRow, Side};
+use crate::comments::Comment;
+use crate::config::DiffMode;
+use crate::ui::theme::Theme;
+use anyhow::Result;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+use std::io::Write;
+use unicode_width::UnicodeWidthStr;
+
+/// Output settings for `--print`
+#[derive(Debug, Clone, Copy)]
+pub struct PrintOptions {
+    /// Total width of side-by-side output
+    pub width: usize,
+    /// Emit ANSI colour sequences
+    pub color: bool,
+}
+
+/// Print every commit's details and diff to `out` without starting the UI
+///
+/// Rows come from the same layout and line renderers as the TUI, with each
+/// stored comment printed under the line, hunk or file it is attached to.
+pub fn print_review(app: &mut App, out: &mut impl Write, options: &PrintOptions) -> Result<()> {
+    let theme = Theme::default();
+    app.visual_anchor = None;
+
+    for index in 0..app.commits.len() {
+        app.select_commit(index);
+        app.wait_for_diff();
+
+        if index > 0 {
+            writeln!(out)?;
+        }
+        if let Some(commit) = app.selected_commit() {
+            for line in crate::ui::commit_details::details_lines(commit, &theme) {
+                write_line(out, &line, options.color)?;
+            }
+            writeln!(out)?;
+        }
+
+        if app.current_files.is_empty() {
+            let message = app
+                .status_message
+                .as_deref()
+                .unwrap_or("No changes to display");
+            writeln!(out, "{message}")?;
+            continue;
+        }
+
+        match app.config.display.diff_mode {
+            DiffMode::Inline => {
+                hide_cursor(app);
+                print_inline(app, out, &theme, options)?;
+            }
+            DiffMode::SideBySide => {
+                for file in 0..app.current_files.len() {
+                    app.select_file(file);
+                    hide_cursor(app);
+                    if file > 0 {
+                        writeln!(out)?;
+                    }
+                    print_side_by_side(app, out, &theme, options)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Move the cursor past the last row so no row is drawn highlighted
+fn hide_cursor(app: &mut App) {
+    app.cursor_line = usize::MAX;
+}
+
+fn print_inline(
+    app: &App,
+    out: &mut impl Write,
+    theme: &Theme,
+    options: &PrintOptions,
+) -> Result<()> {
+    for (index, row) in app.diff_layout.rows().iter().enumerate() {
+        let line = crate::ui::diff_view::create_row_line(app, *row, theme, index);
+        write_line(out, &line, options.color)?;
+        for comment in row_comments(app, *row, Side::New) {
+            write_comment(out, comment, "", theme, options)?;
+        }
+    }
+    Ok(())
+}
+
+fn print_side_by_side(
+    app: &App,
+    out: &mut impl Write,
+    theme: &Theme,
+    options: &PrintOptions,
+) -> Result<()> {
+    let Some(file) = app.selected_file() else {
+        return Ok(());
+    };
+    // Two columns and the divider between them
+    let max_width = options.width.saturating_sub(1) / 2;
+    let divider = Span::styled("│", theme.border_style());
+
+    let (old_title, new_title) = crate::ui::diff_view::side_by_side_titles(app, file, theme);
+    let old_title = crate::ui::log_pane::truncate_to_width(&old_title.content, max_width);
+    let title = Line::from(vec![
+        Span::styled(format!("{old_title:<max_width$}"), new_title.style),
+        divider.clone(),
+        new_title,
+    ]);
+    write_line(out, &title, options.color)?;
+    let rule = "─".repeat(max_width);
+    writeln!(
+        out,
+        "{}",
+        styled(
+            &format!("{rule}┼{rule}"),
+            theme.border_style(),
+            options.color
+        )
+    )?;
+
+    if let Some(cc) = app.current_commit_comments() {
+        for comment in cc.file_level_comments(&file.new_path) {
+            write_comment(out, comment, "", theme, options)?;
+        }
+    }
+
+    let rows = app.diff_layout.rows();
+    let (left, right) =
+        crate::ui::side_by_side::create_side_by_side_lines(app, theme, 0, rows.len(), max_width, 0);
+    let right_column = " ".repeat(max_width + 1);
+    for ((row, mut left), right) in rows.iter().zip(left).zip(right) {
+        left.spans.push(divider.clone());
+        left.spans.extend(right.spans);
+        write_line(out, &left, options.color)?;
+
+        // Context lines sit on both sides; their comments print once, on the right
+        let both_sides = matches!(row, DiffRow::Pair { old, new, .. } if old == new);
+        if !both_sides {
+            for comment in row_comments(app, *row, Side::Old) {
+                write_comment(out, comment, "", theme, options)?;
+            }
+        }
+        if matches!(row, DiffRow::Pair { .. }) {
+            for comment in row_comments(app, *row, Side::New) {
+                write_comment(out, comment, &right_column, theme, options)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Comments attached to a layout row
+///
+/// File comments go under the file header, hunk comments under the hunk
+/// header and line comments under the line on `side`.
+fn row_comments(app: &App, row: DiffRow, side: Side) -> Vec<&Comment> {
+    let Some(cc) = app.current_commit_comments() else {
+        return Vec::new();
+    };
+    let Some(file) = row.file().map(|f| &app.current_files[f]) else {
+        return Vec::new();
+    };
+
+    let line = match row {
+        DiffRow::NewPath { .. } => return cc.file_level_comments(&file.new_path),
+        DiffRow::HunkHeader { hunk, .. } => {
+            return cc.comments_at_hunk(&file.new_path, &file.hunks[hunk].header);
+        }
+        DiffRow::Pair { hunk, old, new, .. } => match side {
+            Side::Old => old,
+            Side::New => new,
+        }
+        .map(|line| (hunk, line)),
+        DiffRow::Line { hunk, line, .. } => Some((hunk, line)),
+        _ => None,
+    };
+    let Some(line) = line.map(|(hunk, line)| &file.hunks[hunk].lines[line]) else {
+        return Vec::new();
+    };
+    line.comment_line_number()
+        .map(|number| cc.comments_at_line(&file.new_path, number, line.line_type))
+        .unwrap_or_default()
+}
+
+/// Print a comment below the row it belongs to, indented past line numbers
+fn write_comment(
+    out: &mut impl Write,
+    comment: &Comment,
+    indent: &str,
+    theme: &Theme,
+    options: &PrintOptions,
+) -> Result<()> {
+    for text in comment.text.lines() {
+        let line = format!("{indent}     ┃ {text}");
+        writeln!(
+            out,
+            "{}",
+            styled(&line, theme.comment_indicator_style(), options.color)
+        )?;
+    }
+    Ok(())
+}
+
+/// Write a styled line, trimming the padding the TUI uses to clear cells
+fn write_line(out: &mut impl Write, line: &Line, color: bool) -> Result<()> {
+    let mut text = String::new();
+    for span in &line.spans {
+        text.push_str(&styled(&span.content, line.style.patch(span.style), color));
+    }
+    if !color {
+        text.truncate(text.trim_end().len());
+    }
+    writeln!(out, "{text}")?;
+    Ok(())
+}
+
+/// Wrap text in the ANSI sequences for a style (plain text without colour)
+fn styled(text: &str, style: Style, color: bool) -> String {
+    let codes = sgr_codes(style);
+    if !color || codes.is_empty() || text.width() == 0 {
+        return text.to_string();
+    }
+    format!("\x1b[{}m{text}\x1b[0m", codes.join(";"))
+}
+
+/// SGR parameters selecting a style's colours and modifiers
+fn sgr_codes(style: Style) -> Vec<String> {
+    let mut codes = Vec::new();
+    for (modifier, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if style.add_modifier.contains(modifier) {
+            codes.push(code.to_string());
+        }
+    }
+    if let Some(code) = style.fg.and_then(|c| color_code(c, false)) {
+        codes.push(code);
+    }
+    if let Some(code) = style.bg.and_then(|c| color_code(c, true)) {
+        codes.push(code);
+    }
+    codes
+}
+
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let base = if background { 40 } else { 30 };
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => base,
+        Color::Red => base + 1,
+        Color::Green => base + 2,
+        Color::Yellow => base + 3,
+        Color::Blue => base + 4,
+        Color::Magenta => base + 5,
+        Color::Cyan => base + 6,
+        Color::Gray => base + 7,
+        Color::DarkGray => base + 60,
+        Color::LightRed => base + 61,
+        Color::LightGreen => base + 62,
+        Color::LightYellow => base + 63,
+        Color::LightBlue => base + 64,
+        Color::LightMagenta => base + 65,
+        Color::LightCyan => base + 66,
+        Color::White => base + 67,
+        Color::Rgb(r, g, b) => return Some(format!("{};2;{r};{g};{b}", base + 8)),
+        Color::Indexed(i) => return Some(format!("{};5;{i}", base + 8)),
+    };
+    Some(code.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comments::CommitComments;
+    use crate::config::Config;
+    use crate::git::LineType;
+    use crate::test_repo;
+
+    fn print(mode: DiffMode) -> Vec<String> {
+        let (_dir, repo) = test_repo::init();
+        let base = test_repo::commit(&repo, &[("a.txt", b"one\ntwo\nthree\n")], "Base");
+        let tip = test_repo::commit(&repo, &[("a.txt", b"one\n2\nthree\n")], "Change two");
+
+        let commits =
+            crate::git::get_commit_log_range(&repo, &base.to_string(), "HEAD", &Default::default())
+                .unwrap();
+        let mut config = Config::default();
+        config.display.diff_mode = mode;
+        let mut app = App::new(repo, commits, config, "main".to_string());
+        let mut cc = CommitComments::new(tip.to_string(), "main".to_string());
+        cc.add_comment(
+            Comment::new_line(
+                "a.txt".to_string(),
+                2,
+                LineType::Added,
+                "Why 2?".to_string(),
+            )
+            .unwrap(),
+        );
+        app.comments_by_commit.insert(tip.to_string(), cc);
+        app.init_diff();
+
+        let mut out = Vec::new();
+        let options = PrintOptions {
+            width: 61,
+            color: false,
+        };
+        print_review(&mut app, &mut out, &options).unwrap();
+        String::from_utf8(out)
+            .unwrap()
+            .lines()
+            // Skip the commit details down to the message and the blank after it
+            .skip_while(|l| *l != "Change two")
+            .skip(2)
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_print_inline_with_comments() {
+        let lines = print(DiffMode::Inline);
+        assert_eq!(
+            lines[..8],
+            [
+                "--- a.txt (3 lines, 14 B)",
+                "+++ a.txt (3 lines, 12 B) [1]",
+                "",
+                "@@ -1,3 +1,3 @@",
+                "   1  one",
+                "   2 -two",
+                "   2 +2 [1]",
+                "     ┃ Why 2?",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_print_side_by_side_with_comments() {
+        let lines = print(DiffMode::SideBySide);
+        assert_eq!(
+            lines[..7],
+            [
+                " Old: a.txt (3 lines, 14 B)   │ New: a.txt (3 lines, 12 B) [1]",
+                "──────────────────────────────┼──────────────────────────────",
+                "@@ -1,3 +1,3 @@               │@@ -1,3 +1,3 @@",
+                "   1  one                     │   1  one",
+                "   2 -two                     │   2 +2 [1]",
+                "                                    ┃ Why 2?",
+                "   3  three                   │   3  three",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_styled_uses_ansi_sequences() {
+        let style = Style::default()
+            .fg(Color::Green)
+            .bg(Color::Rgb(1, 2, 3))
+            .add_modifier(Modifier::BOLD);
+        assert_eq!(styled("x", style, true), "\x1b[1;32;48;2;1;2;3mx\x1b[0m");
+        assert_eq!(styled("x", style, false), "x");
+        assert_eq!(styled("x", Style::default(), true), "x");
+    }
+}
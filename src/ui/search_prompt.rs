@@ -1,4 +1,4 @@
-use crate::app::{App, SearchMode};
+use crate::app::{App, FilterMode, SearchMode};
 use crate::ui::theme::Theme;
 use ratatui::{
     layout::Rect,
@@ -7,13 +7,22 @@ use ratatui::{
     Frame,
 };
 
-/// Render search prompt at bottom of screen (like less)
+/// Render the search or file filter prompt at bottom of screen (like less)
 pub fn render(f: &mut Frame, app: &App, area: Rect) {
-    // Only render if in search mode
-    if app.search_mode != SearchMode::Entering {
-        return;
+    if app.search_mode == SearchMode::Entering {
+        render_prompt(f, area, " Search ", format!("/{}_", app.search_query));
+    } else if app.file_filter_mode == FilterMode::Entering {
+        let title = format!(
+            " Filter files ({}/{}) ",
+            app.current_files.len(),
+            app.all_files.len()
+        );
+        render_prompt(f, area, &title, format!("{}_", app.file_filter));
     }
+}
 
+/// Render a one-line input prompt at the bottom of `area`
+fn render_prompt(f: &mut Frame, area: Rect, title: &str, prompt_text: String) {
     let theme = Theme::default();
 
     // Calculate prompt area at bottom of screen
@@ -25,16 +34,13 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         prompt_height,
     );
 
-    // Build prompt text with cursor
-    let prompt_text = format!("/{}_", app.search_query);
-
     let prompt_paragraph = Paragraph::new(Line::from(vec![Span::styled(
         prompt_text,
         theme.selected_style(),
     )]))
     .block(
         Block::default()
-            .title(" Search ")
+            .title(title)
             .borders(Borders::ALL)
             .border_style(theme.selected_style()),
     );
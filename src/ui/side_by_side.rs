@@ -1,11 +1,12 @@
-use crate::app::App;
+use crate::app::{App, DiffRow, Side};
 use crate::git::{HunkLine, LineType};
 use crate::ui::theme::Theme;
 use ratatui::text::{Line, Span};
+use std::ops::Range;
 
 /// Create side-by-side diff lines (left: old/removed, right: new/added)
 /// Only creates lines within the visible window to save memory on large diffs
-/// Aligns removed and added lines side-by-side for proper comparison
+/// Rows come from the shared diff layout, which pairs removed and added lines
 /// Lines are horizontally scrolled by horizontal_offset and truncated to max_width
 pub fn create_side_by_side_lines<'a>(
     app: &App,
@@ -17,52 +18,26 @@ pub fn create_side_by_side_lines<'a>(
 ) -> (Vec<Line<'a>>, Vec<Line<'a>>) {
     let mut left_lines = Vec::with_capacity(limit);
     let mut right_lines = Vec::with_capacity(limit);
-    let mut current_line = 0;
-    let end_line = skip + limit;
-
-    if let Some(file) = app.selected_file() {
-        // Show hunks
-        for hunk in &file.hunks {
-            // Hunk header on both sides (scrolled and truncated if needed)
-            if current_line >= skip && current_line < end_line {
-                // Check if this hunk has comments
-                let hunk_comment_count = crate::ui::comment_indicator::hunk_comment_count(
+    let blank = || Line::from(" ".repeat(max_width));
+
+    for (offset, row) in app.diff_layout.window(skip, limit).iter().enumerate() {
+        let line_index = skip + offset;
+        match *row {
+            DiffRow::Collapsed { .. } => {
+                // Viewed files collapse to a single placeholder line
+                let text =
+                    apply_horizontal_scroll("File marked as viewed (x to unmark)", 0, max_width);
+                left_lines.push(Line::from(Span::styled(text.clone(), theme.viewed_style())));
+                right_lines.push(Line::from(Span::styled(text, theme.viewed_style())));
+            }
+            DiffRow::HunkHeader { file, hunk } => {
+                let header = format_hunk_header(
                     app,
-                    &file.new_path,
-                    &hunk.header,
-                );
-
-                let indicator_text = if hunk_comment_count > 0 {
-                    format!(" [{hunk_comment_count}]")
-                } else {
-                    String::new()
-                };
-
-                // Apply horizontal scroll to header only, keeping indicator visible
-                let indicator_len = indicator_text.len();
-                let available_for_header = max_width.saturating_sub(indicator_len);
-
-                let mut header = apply_horizontal_scroll(
-                    &hunk.header,
+                    &app.current_files[file].new_path,
+                    &app.current_files[file].hunks[hunk].header,
+                    max_width,
                     horizontal_offset,
-                    available_for_header,
                 );
-
-                // Add indicator at the end (stays visible)
-                header.push_str(&indicator_text);
-
-                // Ensure header fits exactly within max_width
-                let header_len = header.chars().count();
-                match header_len.cmp(&max_width) {
-                    std::cmp::Ordering::Greater => {
-                        header = header.chars().take(max_width).collect();
-                    }
-                    std::cmp::Ordering::Less => {
-                        header.push_str(&" ".repeat(max_width - header_len));
-                    }
-                    std::cmp::Ordering::Equal => {}
-                }
-
                 left_lines.push(Line::from(vec![Span::styled(
                     header.clone(),
                     theme.context_style(),
@@ -72,134 +47,66 @@ pub fn create_side_by_side_lines<'a>(
                     theme.context_style(),
                 )]));
             }
-            current_line += 1;
-            if current_line >= end_line {
-                break;
-            }
-
-            // Process hunk lines with proper alignment
-            let mut i = 0;
-            while i < hunk.lines.len() && current_line < end_line {
-                let hunk_line = &hunk.lines[i];
-
-                match hunk_line.line_type {
-                    LineType::Context => {
-                        // Context appears on both sides
-                        if current_line >= skip {
-                            let left_line = format_side_line(
-                                app,
-                                &file.new_path,
-                                hunk_line,
-                                theme,
-                                true,
-                                max_width,
-                                horizontal_offset,
-                            );
-                            let right_line = format_side_line(
+            DiffRow::Pair {
+                file,
+                hunk,
+                old,
+                new,
+            } => {
+                let file = &app.current_files[file];
+                let lines = &file.hunks[hunk].lines;
+                let side_line = |index: Option<usize>, side: Side| {
+                    index
+                        .map(|i| {
+                            format_side_line(
                                 app,
                                 &file.new_path,
-                                hunk_line,
+                                &lines[i],
                                 theme,
-                                false,
+                                side,
+                                &app.get_matches_for_line(line_index, Some(side)),
                                 max_width,
                                 horizontal_offset,
-                            );
-                            left_lines.push(left_line);
-                            right_lines.push(right_line);
-                        }
-                        current_line += 1;
-                        i += 1;
-                    }
-                    LineType::Removed => {
-                        // Collect consecutive removed lines
-                        let removed_start = i;
-                        while i < hunk.lines.len() && hunk.lines[i].line_type == LineType::Removed {
-                            i += 1;
-                        }
-
-                        // Collect consecutive added lines (if any follow)
-                        let added_start = i;
-                        while i < hunk.lines.len() && hunk.lines[i].line_type == LineType::Added {
-                            i += 1;
-                        }
-
-                        let removed_lines = &hunk.lines[removed_start..added_start];
-                        let added_lines = &hunk.lines[added_start..i];
-
-                        // Pair up removed and added lines side-by-side
-                        let max_len = removed_lines.len().max(added_lines.len());
-                        for j in 0..max_len {
-                            if current_line >= end_line {
-                                break;
-                            }
-
-                            if current_line >= skip {
-                                let left = removed_lines
-                                    .get(j)
-                                    .map(|line| {
-                                        format_side_line(
-                                            app,
-                                            &file.new_path,
-                                            line,
-                                            theme,
-                                            true,
-                                            max_width,
-                                            horizontal_offset,
-                                        )
-                                    })
-                                    .unwrap_or_else(|| Line::from(" ".repeat(max_width)));
-                                let right = added_lines
-                                    .get(j)
-                                    .map(|line| {
-                                        format_side_line(
-                                            app,
-                                            &file.new_path,
-                                            line,
-                                            theme,
-                                            false,
-                                            max_width,
-                                            horizontal_offset,
-                                        )
-                                    })
-                                    .unwrap_or_else(|| Line::from(" ".repeat(max_width)));
-
-                                left_lines.push(left);
-                                right_lines.push(right);
-                            }
-                            current_line += 1;
-                        }
-                    }
-                    LineType::Added => {
-                        // Standalone added lines (not following removed)
-                        if current_line >= skip {
-                            let right_line = format_side_line(
-                                app,
-                                &file.new_path,
-                                hunk_line,
-                                theme,
-                                false,
-                                max_width,
-                                horizontal_offset,
-                            );
-                            // Pad empty left side to prevent artifacts
-                            left_lines.push(Line::from(" ".repeat(max_width)));
-                            right_lines.push(right_line);
-                        }
-                        current_line += 1;
-                        i += 1;
-                    }
-                }
+                            )
+                        })
+                        // Pad empty side to prevent artifacts
+                        .unwrap_or_else(blank)
+                };
+                left_lines.push(side_line(old, Side::Old));
+                right_lines.push(side_line(new, Side::New));
             }
-
             // Empty line between hunks (padded to prevent artifacts)
-            if current_line >= skip && current_line < end_line {
-                let empty = " ".repeat(max_width);
-                left_lines.push(Line::from(empty.clone()));
-                right_lines.push(Line::from(empty));
+            _ => {
+                left_lines.push(blank());
+                right_lines.push(blank());
             }
-            current_line += 1;
-            if current_line >= end_line {
-                break;
+        }
+
+        // Cursor: only the targeted column for lines, both for anything else.
+        // The visual selection covers the cursor's column.
+        let highlight = if line_index == app.cursor_line {
+            let sides = match row {
+                DiffRow::Pair { .. } => vec![row.cursor_side(app.cursor_side)],
+                _ => vec![Side::Old, Side::New],
+            };
+            Some((sides, theme.cursor_style()))
+        } else if app
+            .selection_range()
+            .is_some_and(|r| r.contains(&line_index))
+        {
+            Some((vec![app.cursor_side], theme.selection_style()))
+        } else {
+            None
+        };
+        if let Some((sides, style)) = highlight {
+            for side in sides {
+                let lines = match side {
+                    Side::Old => &mut left_lines,
+                    Side::New => &mut right_lines,
+                };
+                if let Some(line) = lines.pop() {
+                    lines.push(line.patch_style(style));
+                }
             }
         }
     }
@@ -207,16 +114,62 @@ pub fn create_side_by_side_lines<'a>(
     (left_lines, right_lines)
 }
 
+/// Hunk header with its comment count, scrolled and fitted to max_width
+fn format_hunk_header(
+    app: &App,
+    file_path: &str,
+    header: &str,
+    max_width: usize,
+    horizontal_offset: usize,
+) -> String {
+    // Check if this hunk has comments
+    let hunk_comment_count =
+        crate::ui::comment_indicator::hunk_comment_count(app, file_path, header);
+
+    let indicator_text = if hunk_comment_count > 0 {
+        format!(" [{hunk_comment_count}]")
+    } else {
+        String::new()
+    };
+
+    // Apply horizontal scroll to header only, keeping indicator visible
+    let indicator_len = indicator_text.len();
+    let available_for_header = max_width.saturating_sub(indicator_len);
+
+    let mut header = apply_horizontal_scroll(header, horizontal_offset, available_for_header);
+
+    // Add indicator at the end (stays visible)
+    header.push_str(&indicator_text);
+
+    // Ensure header fits exactly within max_width
+    let header_len = header.chars().count();
+    match header_len.cmp(&max_width) {
+        std::cmp::Ordering::Greater => {
+            header = header.chars().take(max_width).collect();
+        }
+        std::cmp::Ordering::Less => {
+            header.push_str(&" ".repeat(max_width - header_len));
+        }
+        std::cmp::Ordering::Equal => {}
+    }
+    header
+}
+
 /// Format a line for side-by-side view with horizontal scrolling
+///
+/// `matches` are search hits as character ranges into the line content.
+#[allow(clippy::too_many_arguments)]
 fn format_side_line<'a>(
     app: &App,
     file_path: &str,
     hunk_line: &HunkLine,
     theme: &Theme,
-    is_left: bool,
+    side: Side,
+    matches: &[(usize, usize)],
     max_width: usize,
     horizontal_offset: usize,
 ) -> Line<'a> {
+    let is_left = side == Side::Old;
     let (prefix, style) = match hunk_line.line_type {
         LineType::Added => ("+", theme.added_style()),
         LineType::Removed => ("-", theme.removed_style()),
@@ -257,6 +210,11 @@ fn format_side_line<'a>(
     } else {
         String::new()
     };
+    let indicator_text = if hunk_line.no_newline {
+        format!(" \\ No newline{indicator_text}")
+    } else {
+        indicator_text
+    };
 
     // Build full line (line number and indicator don't scroll, only content)
     let full_content = format!("{}{}", prefix, hunk_line.content);
@@ -291,18 +249,96 @@ fn format_side_line<'a>(
         std::cmp::Ordering::Equal => {}
     }
 
-    Line::from(vec![Span::styled(display, style)])
+    let mut spans = vec![Span::styled(display, style)];
+
+    // Map content ranges through the horizontal scroll, past the +/- prefix
+    let content_len = full_content.chars().count();
+    let start = horizontal_offset.min(content_len);
+    let left_marker = usize::from(start > 0);
+    let visible = scrolled_content.chars().count().saturating_sub(left_marker);
+    let base = line_num.chars().count() + left_marker;
+    let on_screen = |ranges: &[(usize, usize)]| -> Vec<(usize, usize)> {
+        ranges
+            .iter()
+            .map(|&(s, e)| (s + 1, e + 1))
+            .filter_map(|(s, e)| {
+                let (s, e) = (s.max(start), e.min(start + visible));
+                (s < e).then(|| (base + s - start, base + e - start))
+            })
+            .collect()
+    };
+
+    spans = crate::ui::whitespace::overlay_style(
+        spans,
+        &on_screen(matches),
+        theme.search_highlight_style(),
+    );
+
+    // Whitespace errors in added lines
+    if app.highlight_whitespace_errors && hunk_line.line_type == LineType::Added {
+        let errors = crate::ui::whitespace::whitespace_errors(&hunk_line.content);
+        spans = crate::ui::whitespace::overlay_style(
+            spans,
+            &on_screen(&errors),
+            theme.whitespace_error_style(),
+        );
+    }
+
+    Line::from(spans)
 }
 
 /// Apply horizontal scroll with indicators
 fn apply_horizontal_scroll(line: &str, offset: usize, max_width: usize) -> String {
     let chars: Vec<char> = line.chars().collect();
+    let (has_left, visible, has_right) = scroll_window(chars.len(), offset, max_width);
+    let visible: String = chars[visible].iter().collect();
+
+    format!(
+        "{}{}{}",
+        if has_left { "<" } else { "" },
+        visible,
+        if has_right { ">" } else { "" }
+    )
+}
 
-    if chars.is_empty() {
-        return String::new();
+/// Scroll a styled line sideways and cut it to `max_width`, keeping each
+/// character's style and marking hidden text with `<` and `>`
+pub fn scroll_line<'a>(line: Line<'a>, offset: usize, max_width: usize) -> Line<'a> {
+    let len = line.spans.iter().map(|s| s.content.chars().count()).sum();
+    let (has_left, visible, has_right) = scroll_window(len, offset, max_width);
+    if !has_left && !has_right {
+        return line;
+    }
+
+    let mut spans = Vec::new();
+    if has_left {
+        spans.push(Span::raw("<"));
+    }
+    let mut start = 0;
+    for span in &line.spans {
+        let count = span.content.chars().count();
+        let (from, to) = (visible.start.max(start), visible.end.min(start + count));
+        if from < to {
+            let text: String = span
+                .content
+                .chars()
+                .skip(from - start)
+                .take(to - from)
+                .collect();
+            spans.push(Span::styled(text, span.style));
+        }
+        start += count;
+    }
+    if has_right {
+        spans.push(Span::raw(">"));
     }
+    Line::from(spans).style(line.style)
+}
 
-    let start_idx = offset.min(chars.len());
+/// Characters of a `len`-character line shown when scrolled by `offset`,
+/// and whether `<` and `>` indicators are needed on either end
+fn scroll_window(len: usize, offset: usize, max_width: usize) -> (bool, Range<usize>, bool) {
+    let start_idx = offset.min(len);
     let has_left = start_idx > 0;
 
     // Reserve space for indicators
@@ -312,8 +348,8 @@ fn apply_horizontal_scroll(line: &str, offset: usize, max_width: usize) -> Strin
         max_width
     };
 
-    let end_idx = (start_idx + available_width).min(chars.len());
-    let has_right = end_idx < chars.len();
+    let end_idx = (start_idx + available_width).min(len);
+    let has_right = end_idx < len;
 
     // Adjust end if we need space for '>'
     let final_end = if has_right {
@@ -322,12 +358,5 @@ fn apply_horizontal_scroll(line: &str, offset: usize, max_width: usize) -> Strin
         end_idx
     };
 
-    let visible: String = chars[start_idx..final_end].iter().collect();
-
-    format!(
-        "{}{}{}",
-        if has_left { "<" } else { "" },
-        visible,
-        if has_right { ">" } else { "" }
-    )
+    (has_left, start_idx..final_end.max(start_idx), has_right)
 }
@@ -1,5 +1,8 @@
 use ratatui::style::{Color, Modifier, Style};
 
+use crate::comments::VerdictKind;
+use crate::git::range_diff::PairStatus;
+
 /// Theme colors inspired by GitHub's diff UI
 pub struct Theme {
     pub bg: Color,
@@ -70,6 +73,15 @@ impl Theme {
         Style::default().fg(self.border)
     }
 
+    /// Border of a pane, highlighted while it has keyboard focus
+    pub fn pane_border_style(&self, focused: bool) -> Style {
+        if focused {
+            Style::default().fg(Color::Cyan)
+        } else {
+            self.border_style()
+        }
+    }
+
     /// Style for comment indicators - uses blue/cyan to avoid confusion with diff colors
     pub fn comment_indicator_style(&self) -> Style {
         Style::default()
@@ -77,6 +89,87 @@ impl Theme {
             .add_modifier(Modifier::BOLD)
     }
 
+    /// Style for files marked as viewed - dimmed so unreviewed files stand out
+    pub fn viewed_style(&self) -> Style {
+        Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::DIM)
+    }
+
+    /// Style for commit verdict badges
+    pub fn verdict_style(&self, kind: VerdictKind) -> Style {
+        let color = match kind {
+            VerdictKind::Approve => Color::Green,
+            VerdictKind::RequestChanges => Color::Red,
+            VerdictKind::Comment => Color::Cyan,
+        };
+        Style::default().fg(color).add_modifier(Modifier::BOLD)
+    }
+
+    /// Style for range-diff pair status markers
+    pub fn pair_status_style(&self, status: PairStatus) -> Style {
+        let color = match status {
+            PairStatus::Unchanged => self.context_fg,
+            PairStatus::Modified => Color::Yellow,
+            PairStatus::Added => Color::Green,
+            PairStatus::Dropped => Color::Red,
+        };
+        Style::default().fg(color).add_modifier(Modifier::BOLD)
+    }
+
+    /// Style for abbreviated commit hashes in the log pane
+    pub fn commit_hash_style(&self) -> Style {
+        Style::default().fg(Color::Yellow)
+    }
+
+    /// Style for branch and tag names pointing at a commit
+    pub fn ref_decoration_style(&self) -> Style {
+        Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    /// Style for the marker on merge commits
+    pub fn merge_style(&self) -> Style {
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    /// Style for the commit graph lanes in the log pane
+    pub fn graph_style(&self) -> Style {
+        Style::default().fg(Color::Blue)
+    }
+
+    /// Style for the marker on commits not yet pushed upstream
+    pub fn unpushed_style(&self) -> Style {
+        Style::default()
+            .fg(Color::Green)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    /// Style for commit metadata labels and trailer keys in the details pane
+    pub fn label_style(&self) -> Style {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    /// Style for whitespace errors (trailing spaces, mixed indentation) in added lines
+    pub fn whitespace_error_style(&self) -> Style {
+        Style::default().bg(Color::Red)
+    }
+
+    /// Style for the cursor row in the diff view
+    pub fn cursor_style(&self) -> Style {
+        Style::default().bg(Color::DarkGray)
+    }
+
+    /// Style for rows in the visual selection
+    pub fn selection_style(&self) -> Style {
+        Style::default().bg(Color::Blue)
+    }
+
     /// Style for search match highlighting
     pub fn search_highlight_style(&self) -> Style {
         Style::default()
@@ -0,0 +1,113 @@
+use ratatui::{style::Style, text::Span};
+
+/// Find whitespace errors in a line, as character ranges
+///
+/// Flags trailing whitespace and indentation that mixes spaces and tabs,
+/// matching the checks `git diff --check` does by default.
+pub fn whitespace_errors(content: &str) -> Vec<(usize, usize)> {
+    let chars: Vec<char> = content.chars().collect();
+    let is_ws = |c: &char| *c == ' ' || *c == '\t';
+    let mut errors = Vec::new();
+
+    let indent = chars.iter().take_while(|c| is_ws(c)).count();
+    let indent_chars = &chars[..indent];
+    if indent < chars.len() && indent_chars.contains(&' ') && indent_chars.contains(&'\t') {
+        errors.push((0, indent));
+    }
+
+    let trailing = chars.iter().rev().take_while(|c| is_ws(c)).count();
+    if trailing > 0 {
+        let start = chars.len() - trailing;
+        match errors.last_mut() {
+            // Whitespace-only lines: the whole line is one error
+            Some(last) if last.1 >= start => last.1 = chars.len(),
+            _ => errors.push((start, chars.len())),
+        }
+    }
+
+    errors
+}
+
+/// Patch `style` onto the characters of `spans` covered by `ranges`
+///
+/// Ranges are character offsets into the concatenated span contents. Spans
+/// are split at range boundaries so surrounding text keeps its style.
+pub fn overlay_style<'a>(
+    spans: Vec<Span<'a>>,
+    ranges: &[(usize, usize)],
+    style: Style,
+) -> Vec<Span<'a>> {
+    if ranges.is_empty() {
+        return spans;
+    }
+
+    let in_range = |pos: usize| ranges.iter().any(|&(s, e)| pos >= s && pos < e);
+    let mut result = Vec::new();
+    let mut pos = 0;
+
+    for span in spans {
+        let mut current = String::new();
+        let mut current_marked = None;
+        for c in span.content.chars() {
+            let marked = in_range(pos);
+            if current_marked.is_some_and(|m| m != marked) {
+                result.push(styled_part(
+                    &span,
+                    std::mem::take(&mut current),
+                    current_marked,
+                    style,
+                ));
+            }
+            current_marked = Some(marked);
+            current.push(c);
+            pos += 1;
+        }
+        if !current.is_empty() {
+            result.push(styled_part(&span, current, current_marked, style));
+        }
+    }
+
+    result
+}
+
+fn styled_part<'a>(span: &Span, text: String, marked: Option<bool>, style: Style) -> Span<'a> {
+    if marked == Some(true) {
+        Span::styled(text, span.style.patch(style))
+    } else {
+        Span::styled(text, span.style)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    #[test]
+    fn test_whitespace_errors() {
+        assert!(whitespace_errors("clean line").is_empty());
+        assert!(whitespace_errors("\tindented").is_empty());
+        assert!(whitespace_errors("").is_empty());
+        assert_eq!(whitespace_errors("trailing  "), vec![(8, 10)]);
+        assert_eq!(whitespace_errors(" \tmixed"), vec![(0, 2)]);
+        assert_eq!(whitespace_errors(" \tboth \t"), vec![(0, 2), (6, 8)]);
+        assert_eq!(whitespace_errors("   "), vec![(0, 3)]);
+    }
+
+    #[test]
+    fn test_overlay_style_splits_spans() {
+        let base = Style::default().fg(Color::Green);
+        let error = Style::default().bg(Color::Red);
+        let spans = vec![Span::styled("ab", base), Span::styled("cd  ", base)];
+
+        let result = overlay_style(spans, &[(4, 6)], error);
+        let parts: Vec<(&str, Style)> = result
+            .iter()
+            .map(|s| (s.content.as_ref(), s.style))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![("ab", base), ("cd", base), ("  ", base.patch(error))]
+        );
+    }
+}
@@ -178,3 +178,237 @@ fn test_parse_range_function() {
     assert!(parse_range("..HEAD").is_err());
     assert!(parse_range("HEAD..").is_err());
 }
+
+#[test]
+fn test_check_approved() {
+    use git_review::comments::{CommitComments, VerdictKind};
+
+    let temp_dir = create_test_repo();
+
+    let mut cmd = Command::cargo_bin("git-review").expect("Failed to find binary");
+    cmd.args(["--check-approved", "--range", "HEAD~1..HEAD"]);
+    cmd.current_dir(temp_dir.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Feature change"))
+        .stderr(predicate::str::contains("1 of 1 commit(s) not approved"));
+
+    // Approve the feature commit
+    let repo = git2::Repository::open(temp_dir.path()).expect("Failed to open repo");
+    let head = repo.head().unwrap().target().unwrap();
+    let mut comments = CommitComments::new(head.to_string(), "feature".to_string());
+    comments.set_verdict(VerdictKind::Approve, "");
+    git_review::git::notes::write_comments(&repo, "feature", head, &comments)
+        .expect("Failed to write verdict");
+
+    let mut cmd = Command::cargo_bin("git-review").expect("Failed to find binary");
+    cmd.args(["--check-approved", "--range", "HEAD~1..HEAD"]);
+    cmd.current_dir(temp_dir.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("All 1 commit(s) approved"));
+}
+
+#[test]
+fn test_print_with_comments() {
+    use git_review::comments::{Comment, CommitComments};
+    use git_review::git::LineType;
+
+    let temp_dir = create_test_repo();
+    let repo = git2::Repository::open(temp_dir.path()).expect("Failed to open repo");
+    let head = repo.head().unwrap().target().unwrap();
+    let mut comments = CommitComments::new(head.to_string(), "feature".to_string());
+    let comment = Comment::new_line(
+        "file1.txt".to_string(),
+        1,
+        LineType::Added,
+        "Say what changed".to_string(),
+    )
+    .expect("Failed to create comment");
+    comments.add_comment(comment);
+    git_review::git::notes::write_comments(&repo, "feature", head, &comments)
+        .expect("Failed to write comment");
+
+    let mut cmd = Command::cargo_bin("git-review").expect("Failed to find binary");
+    cmd.args([
+        "--print",
+        "--inline",
+        "--color=never",
+        "--range",
+        "HEAD~1..HEAD",
+    ]);
+    cmd.current_dir(temp_dir.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Feature change"))
+        .stdout(predicate::str::contains(
+            "   1 +Modified content [1]\n     ┃ Say what changed\n",
+        ))
+        .stdout(predicate::str::contains("\x1b[").not());
+}
+
+#[test]
+fn test_comment_subcommands() {
+    let temp_dir = create_test_repo();
+
+    let mut cmd = Command::cargo_bin("git-review").expect("Failed to find binary");
+    cmd.args([
+        "comment",
+        "add",
+        "--file",
+        "file1.txt",
+        "--line",
+        "1",
+        "-t",
+        "Nit",
+    ]);
+    cmd.current_dir(temp_dir.path());
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let id = String::from_utf8(output).unwrap().trim().to_string();
+    assert_eq!(id.len(), 8);
+
+    let mut cmd = Command::cargo_bin("git-review").expect("Failed to find binary");
+    cmd.args(["comment", "edit", &id, "-F", "-"]);
+    cmd.current_dir(temp_dir.path());
+    cmd.write_stdin("Rename this\n").assert().success();
+
+    let mut cmd = Command::cargo_bin("git-review").expect("Failed to find binary");
+    cmd.args(["comment", "list", "--commit", "HEAD"]);
+    cmd.current_dir(temp_dir.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(format!("\"id\": \"{id}\"")))
+        .stdout(predicate::str::contains("\"text\": \"Rename this\""))
+        .stdout(predicate::str::contains("\"line_type\": \"added\""));
+
+    let mut cmd = Command::cargo_bin("git-review").expect("Failed to find binary");
+    cmd.args(["comment", "rm", &id]);
+    cmd.current_dir(temp_dir.path());
+    cmd.assert().success();
+
+    let mut cmd = Command::cargo_bin("git-review").expect("Failed to find binary");
+    cmd.args(["comment", "rm", &id]);
+    cmd.current_dir(temp_dir.path());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("No comment with id"));
+}
+
+#[test]
+fn test_print_patch_file() {
+    let temp_dir = create_test_repo();
+    let output = StdCommand::new("git")
+        .args(["format-patch", "-1", "--stdout"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to run git format-patch");
+    std::fs::write(temp_dir.path().join("change.patch"), &output.stdout)
+        .expect("Failed to write patch");
+    // Review it against the commit it was made from
+    StdCommand::new("git")
+        .args(["checkout", "-q", "HEAD~1"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to check out base");
+
+    let mut cmd = Command::cargo_bin("git-review").expect("Failed to find binary");
+    cmd.args([
+        "--print",
+        "--inline",
+        "--color=never",
+        "--patch",
+        "change.patch",
+    ]);
+    cmd.current_dir(temp_dir.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Feature change"))
+        .stdout(predicate::str::contains("-Initial content"))
+        .stdout(predicate::str::contains("+Modified content"));
+}
+
+#[test]
+fn test_print_tree_diff() {
+    let temp_dir = create_test_repo();
+
+    let mut cmd = Command::cargo_bin("git-review").expect("Failed to find binary");
+    cmd.args([
+        "--print",
+        "--inline",
+        "--color=never",
+        "--diff",
+        "HEAD~1",
+        "feature",
+    ]);
+    cmd.current_dir(temp_dir.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Diff HEAD~1..feature"))
+        .stdout(predicate::str::contains("+Modified content"));
+
+    let mut cmd = Command::cargo_bin("git-review").expect("Failed to find binary");
+    cmd.args(["--diff", "feature", "HEAD"]);
+    cmd.current_dir(temp_dir.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No differences"));
+}
+
+#[test]
+fn test_print_no_index_outside_repository() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    std::fs::write(temp_dir.path().join("a.conf"), "port = 80\nhost = a\n")
+        .expect("Failed to write a.conf");
+    std::fs::write(temp_dir.path().join("b.conf"), "port = 8080\nhost = a\n")
+        .expect("Failed to write b.conf");
+
+    let mut cmd = Command::cargo_bin("git-review").expect("Failed to find binary");
+    cmd.args([
+        "--print",
+        "--inline",
+        "--color=never",
+        "--no-index",
+        "a.conf",
+        "b.conf",
+    ]);
+    cmd.current_dir(temp_dir.path());
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Compare a.conf and b.conf"))
+        .stdout(predicate::str::contains("-port = 80"))
+        .stdout(predicate::str::contains("+port = 8080"));
+}
+
+#[test]
+fn test_difftool_backend() {
+    let temp_dir = create_test_repo();
+    let bin = assert_cmd::cargo::cargo_bin("git-review");
+    let tool = format!(
+        "'{}' --print --inline --color=never difftool \"$LOCAL\" \"$REMOTE\" \"$MERGED\"",
+        bin.display()
+    );
+
+    // One invocation per file, named by $MERGED
+    let output = StdCommand::new("git")
+        .args(["-c", &format!("difftool.git-review.cmd={tool}")])
+        .args(["difftool", "-y", "--tool=git-review", "HEAD~1", "HEAD"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to run git difftool");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{output:?}");
+    assert!(stdout.contains("file1.txt (1/1)"), "{stdout}");
+    assert!(stdout.contains("+Modified content"), "{stdout}");
+
+    // Directory diff of the two trees
+    let output = StdCommand::new("git")
+        .args(["-c", &format!("difftool.git-review.cmd={tool}")])
+        .args(["difftool", "-d", "--tool=git-review", "HEAD~1", "HEAD"])
+        .current_dir(temp_dir.path())
+        .output()
+        .expect("Failed to run git difftool -d");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{output:?}");
+    assert!(stdout.contains("file1.txt"), "{stdout}");
+    assert!(stdout.contains("-Initial content"), "{stdout}");
+}